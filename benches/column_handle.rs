@@ -0,0 +1,42 @@
+//!filtering 1M lines by a column looked up by name in every line and by a ColumnHandle,
+//!run with cargo bench --bench column_handle
+
+#![feature(test)]
+
+extern crate test;
+
+use rundas::{Data, DataFrame};
+use test::{black_box, Bencher};
+
+const LINES: i32 = 1_000_000;
+
+fn frame() -> DataFrame {
+    let header = vec![
+        "id", "time", "lat", "lon", "altitude", "heading", "vehicle", "driver", "speed",
+    ];
+    let lines = (0..LINES).map(|i| {
+        let mut line: Vec<Data> = (0..8).map(|column| Data::Integer(i ^ column)).collect();
+        line.push(Data::Float((i % 130) as f32));
+        line
+    });
+    DataFrame::new(header).append_lines(lines)
+}
+
+#[bench]
+fn filter_by_name(b: &mut Bencher) {
+    let df = frame();
+    b.iter(|| {
+        let fast = df.clone().filter(|line| line["speed"].as_float() > 100.0);
+        black_box(fast.len())
+    });
+}
+
+#[bench]
+fn filter_by_handle(b: &mut Bencher) {
+    let df = frame();
+    let speed = df.column_handle("speed");
+    b.iter(|| {
+        let fast = df.clone().filter(|line| line[speed].as_float() > 100.0);
+        black_box(fast.len())
+    });
+}
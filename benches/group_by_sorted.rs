@@ -0,0 +1,35 @@
+//!grouping 1M lines sorted by a key with 1000 distinct values by hashing every key
+//!and by splitting the frame into runs, run with cargo bench --bench group_by_sorted
+
+#![feature(test)]
+
+extern crate test;
+
+use rundas::{Data, DataFrame};
+use test::{black_box, Bencher};
+
+const LINES: i32 = 1_000_000;
+const KEYS: i32 = 1_000;
+
+fn sorted_frame() -> DataFrame {
+    let lines = (0..LINES).map(|i| vec![Data::Integer(i / (LINES / KEYS)), Data::Integer(i)]);
+    DataFrame::new(vec!["key", "value"]).append_lines(lines)
+}
+
+#[bench]
+fn group_by_hashing(b: &mut Bencher) {
+    let df = sorted_frame();
+    b.iter(|| black_box(df.clone().group_by(|line| line[0].as_integer()).len()));
+}
+
+#[bench]
+fn group_by_runs(b: &mut Bencher) {
+    let df = sorted_frame();
+    b.iter(|| {
+        black_box(
+            df.clone()
+                .group_by_sorted(|line| line[0].as_integer())
+                .len(),
+        )
+    });
+}
@@ -0,0 +1,111 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, LitStr};
+
+///implements rundas::FromLine for a struct with named fields and adds a struct
+///"{Name}Handles" with the column handles of all fields
+///
+///a field is read from the column with its name unless #[rundas(rename = "column")] is given,
+///its type has to implement rundas::FromCell
+#[proc_macro_derive(FromLine, attributes(rundas))]
+pub fn derive_from_line(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(input) {
+        Ok(tokens) => tokens.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
+
+fn expand(input: DeriveInput) -> Result<TokenStream2, Error> {
+    let name = input.ident;
+    let vis = input.vis;
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            input.generics,
+            "FromLine can not be derived for generic structs",
+        ));
+    }
+    let Data::Struct(data) = input.data else {
+        return Err(Error::new_spanned(
+            name,
+            "FromLine can only be derived for structs",
+        ));
+    };
+    let Fields::Named(fields) = data.fields else {
+        return Err(Error::new_spanned(
+            name,
+            "FromLine needs a struct with named fields",
+        ));
+    };
+
+    let mut idents = Vec::new();
+    let mut columns = Vec::new();
+    for field in fields.named {
+        let ident = field.ident.expect("named fields have an ident");
+        let mut column = ident.to_string();
+        for attr in field
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("rundas"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    let rename: LitStr = meta.value()?.parse()?;
+                    column = rename.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("the only rundas attribute is rename"))
+                }
+            })?;
+        }
+        idents.push(ident);
+        columns.push(column);
+    }
+
+    let handles = format_ident!("{}Handles", name);
+    Ok(quote! {
+        #vis struct #handles {
+            #(#idents: ::rundas::ColumnHandle,)*
+        }
+
+        impl #handles {
+            ///looks up the columns of all fields, the error lists the missing ones
+            pub fn resolve(df: &::rundas::DataFrame) -> ::std::result::Result<Self, ::rundas::ShapeError> {
+                let header: ::std::vec::Vec<&str> = df.header().collect();
+                let mut missing = ::std::vec::Vec::new();
+                #(
+                    let #idents = header.iter().position(|head_elem| *head_elem == #columns);
+                    if #idents.is_none() {
+                        missing.push(::std::format!("'{}'", #columns));
+                    }
+                )*
+                if !missing.is_empty() {
+                    return ::std::result::Result::Err(::rundas::ShapeError::MissingColumns {
+                        columns: missing,
+                    });
+                }
+                ::std::result::Result::Ok(#handles {
+                    #(#idents: df.column_handle(#idents.expect("checked above")),)*
+                })
+            }
+        }
+
+        impl ::rundas::FromLine for #name {
+            type Handles = #handles;
+
+            fn resolve(df: &::rundas::DataFrame) -> ::std::result::Result<#handles, ::rundas::ShapeError> {
+                #handles::resolve(df)
+            }
+
+            fn from_line(
+                line: &::rundas::Line,
+                handles: &#handles,
+            ) -> ::std::result::Result<Self, ::rundas::DataTypeError> {
+                ::std::result::Result::Ok(#name {
+                    #(#idents: ::rundas::FromCell::from_cell(&line[handles.#idents])?,)*
+                })
+            }
+        }
+    })
+}
@@ -0,0 +1,79 @@
+use rundas::{Data, DataFrame, DataTypeError, FromLine, ShapeError};
+
+#[derive(FromLine, Debug, PartialEq)]
+struct Reading {
+    #[rundas(rename = "sensor id")]
+    sensor: i32,
+    value: f32,
+    note: Option<String>,
+}
+
+fn readings(value: Data) -> DataFrame {
+    let rows = vec![
+        vec![
+            Data::Integer(7),
+            Data::Float(1.5),
+            Data::String(Box::new("calibrated".to_owned())),
+        ],
+        vec![Data::Integer(8), value, Data::Null],
+    ];
+    DataFrame::from_rows(vec!["sensor id", "value", "note"], rows).expect("the rows fit the header")
+}
+
+#[test]
+fn renamed_and_optional_fields_are_read() {
+    let df = readings(Data::Integer(2));
+    let lines: Vec<Reading> = df
+        .iter_typed::<Reading>()
+        .expect("every column exists")
+        .collect::<Result<_, _>>()
+        .expect("every cell has the type of its field");
+    assert_eq!(
+        lines,
+        vec![
+            Reading {
+                sensor: 7,
+                value: 1.5,
+                note: Some("calibrated".to_owned()),
+            },
+            Reading {
+                sensor: 8,
+                value: 2.0,
+                note: None,
+            },
+        ]
+    );
+}
+
+#[test]
+fn a_type_mismatch_names_the_line() {
+    let df = readings(Data::Boolean(true));
+    let results: Vec<Result<Reading, DataTypeError>> = df
+        .iter_typed::<Reading>()
+        .expect("every column exists")
+        .collect();
+    assert!(results[0].is_ok());
+    assert_eq!(
+        results[1],
+        Err(DataTypeError {
+            line: 1,
+            expected: "Float",
+            found: Data::Boolean(true),
+        })
+    );
+}
+
+#[test]
+fn missing_columns_are_listed_once() {
+    let df = readings(Data::Float(0.5)).drop_columns(&["sensor id", "note"]);
+    let Err(err) = df.iter_typed::<Reading>() else {
+        panic!("two columns are missing");
+    };
+    assert_eq!(
+        err,
+        ShapeError::MissingColumns {
+            columns: vec!["'sensor id'".to_owned(), "'note'".to_owned()],
+        }
+    );
+    assert!(ReadingHandles::resolve(&readings(Data::Null)).is_ok());
+}
@@ -1,27 +1,80 @@
 use std::iter::FusedIterator;
 
-use std::ops::Deref;
+use std::ops::{Deref, Range};
 use std::sync::Arc;
 use std::{collections::HashMap, hash::Hash};
 
 mod data;
-pub use data::{Data, SimpleDateTime};
+pub use data::{CappedData, Data, DataDisplayConfig, SimpleDateTime};
 mod line;
 pub use line::Line;
 mod group;
-pub use group::Groups;
+pub use group::{DistributionEntry, Groups};
+mod string_cache;
+pub use string_cache::{Category, StringCache};
+mod aggregate;
+mod arithmetic;
+mod binary;
+mod bins;
+pub use aggregate::Agg;
+use aggregate::CompensatedSum;
+pub use bins::Binning;
+mod cache;
+mod derived;
+mod error;
+pub use error::{
+    AppendError, ArithmeticError, BackwardStep, BinError, ColumnCastError, DataTypeError,
+    GroupCacheError, JoinError, MonotonicReport, ShapeError, WriteError,
+};
 
+mod index_map;
+use index_map::IndexMap;
 mod indexing;
-use indexing::DataFrameColumnIndex;
+pub use indexing::ColumnHandle;
+pub use indexing::DataFrameColumnIndex;
+mod lazy_cells;
+use lazy_cells::LazyCells;
 
 mod display;
+pub use display::DisplayOptions;
 mod file_io;
+mod read_options;
+pub use read_options::{ParseRules, ReadOptions};
 mod frame_extension;
+pub use frame_extension::Coercion;
+mod join;
+pub use join::{JoinNaming, JoinReport};
+mod monotonic;
+pub use monotonic::MonotonicStrategy;
+mod observer;
+use observer::observed;
+pub use observer::{clear_op_observer, set_op_observer, OpEvent};
+mod keys;
+mod quantile;
+mod reshape;
+pub use quantile::DescribeOptions;
+mod runs;
+mod sample;
+mod sort;
+pub use sort::{SortKey, SortOptions, SortOrder};
+mod transform;
+pub use transform::Unmatched;
+#[cfg(feature = "futures")]
+mod stream;
+mod type_fix;
+pub use type_fix::{TypeFix, TypeFixOptions, TypeFixPlan};
+mod typed;
+pub use typed::{FromCell, FromLine};
+mod writer;
+pub use writer::RowSink;
 
 pub struct BaseDataFrame {
     identity_index_map: Vec<usize>,
     header: Vec<String>,
     data: Vec<Vec<Data>>,
+    //the cache the Categories of this frame are stored in
+    string_cache: Option<StringCache>,
+    parse_rules: ParseRules,
 }
 
 pub struct DataFrame {
@@ -57,13 +110,34 @@ enum InnerDataFrame {
     ColumnReorder {
         df: DataFrame,
         index_map: Vec<usize>,
+        //index_map resolved to the positions in the lines of df, see DataFrame::reorder_columns
+        line_map: Vec<usize>,
     },
     LineReorder {
         df: DataFrame,
-        index_map: Vec<usize>,
+        index_map: IndexMap,
+    },
+    Computed {
+        df: DataFrame,
+        header: Vec<String>,
+        identity_index_map: Vec<usize>,
+        f: Arc<dyn Fn(Line) -> Data + Send + Sync>,
+        //only the computed cell is stored, the others are read from the lines of df
+        cells: LazyCells,
+        //the columns the computed one is derived from if it was added by with_derived
+        depends_on: Option<Vec<String>>,
+    },
+    Concat {
+        //never empty, all parts have the same header
+        parts: Vec<DataFrame>,
+        //the number of lines in all parts up to and including this one
+        ends: Vec<usize>,
     },
 }
 
+//the index_map and line_map of a ColumnReorder
+type ColumnMaps = (Vec<usize>, Vec<usize>);
+
 impl From<DataFrame> for BaseDataFrame {
     fn from(df: DataFrame) -> Self {
         let arc_df: DataFrame = match Arc::try_unwrap(df.inner) {
@@ -86,6 +160,8 @@ impl From<DataFrame> for BaseDataFrame {
             identity_index_map: (0..header.len()).collect(),
             header,
             data,
+            string_cache: arc_df.string_cache().cloned(),
+            parse_rules: arc_df.parse_rules(),
         }
     }
 }
@@ -96,6 +172,8 @@ impl DataFrame {
             identity_index_map: Vec::new(),
             header: Vec::new(),
             data: Vec::new(),
+            string_cache: None,
+            parse_rules: ParseRules::default(),
         };
         InnerDataFrame::Base { df }.into()
     }
@@ -105,18 +183,54 @@ impl DataFrame {
             identity_index_map: (0..header.len()).collect(),
             header: header.drain(..).map(|s| s.into()).collect(),
             data: Vec::new(),
+            string_cache: None,
+            parse_rules: ParseRules::default(),
+        };
+        InnerDataFrame::Base { df }.into()
+    }
+
+    ///an empty frame that can take rows lines without reallocating
+    pub fn with_capacity(mut header: Vec<impl Into<String>>, rows: usize) -> DataFrame {
+        let df = BaseDataFrame {
+            identity_index_map: (0..header.len()).collect(),
+            header: header.drain(..).map(|s| s.into()).collect(),
+            data: Vec::with_capacity(rows),
+            string_cache: None,
+            parse_rules: ParseRules::default(),
         };
         InnerDataFrame::Base { df }.into()
     }
 
+    ///takes the rows as they are after checking all of their lenghts,
+    ///the first row with a wrong lenght is reported
+    pub fn from_rows(
+        mut header: Vec<impl Into<String>>,
+        rows: Vec<Vec<Data>>,
+    ) -> Result<DataFrame, ShapeError> {
+        if let Some((line, row)) = rows
+            .iter()
+            .enumerate()
+            .find(|(_line, row)| row.len() != header.len())
+        {
+            return Err(ShapeError::LineLength {
+                line,
+                expected: header.len(),
+                found: row.len(),
+            });
+        }
+        let df = BaseDataFrame {
+            identity_index_map: (0..header.len()).collect(),
+            header: header.drain(..).map(|s| s.into()).collect(),
+            data: rows,
+            string_cache: None,
+            parse_rules: ParseRules::default(),
+        };
+        Ok(InnerDataFrame::Base { df }.into())
+    }
+
     pub fn head(self, lines: usize) -> DataFrame {
         if lines < self.len() {
-            let index_map = (0..lines).collect();
-            InnerDataFrame::LineReorder {
-                df: self,
-                index_map,
-            }
-            .into()
+            self.line_range(0..lines)
         } else {
             self
         }
@@ -124,12 +238,8 @@ impl DataFrame {
 
     pub fn tail(self, lines: usize) -> DataFrame {
         if lines < self.len() {
-            let index_map = (self.len() - lines..self.len()).collect();
-            InnerDataFrame::LineReorder {
-                df: self,
-                index_map,
-            }
-            .into()
+            let len = self.len();
+            self.line_range(len - lines..len)
         } else {
             self
         }
@@ -139,20 +249,71 @@ impl DataFrame {
         assert!(start <= end);
         assert!(end <= self.len());
 
-        let index_map = (start..end).collect();
+        self.line_range(start..end)
+    }
+
+    ///views of the lines before mid and of the lines from mid on,
+    ///panics if mid > len like slice::split_at
+    pub fn split_at(self, mid: usize) -> (DataFrame, DataFrame) {
+        assert!(
+            mid <= self.len(),
+            "mid is {mid} but the frame has {} lines",
+            self.len()
+        );
+        let len = self.len();
+        (self.clone().line_range(0..mid), self.line_range(mid..len))
+    }
+
+    ///views of chunk_size lines each in order, the last one holds the rest and may be shorter,
+    ///an empty frame has no chunks. panics if chunk_size is 0 like slice::chunks
+    pub fn chunks(&self, chunk_size: usize) -> impl Iterator<Item = DataFrame> + '_ {
+        assert!(chunk_size != 0, "chunk_size must not be 0");
+        (0..self.len()).step_by(chunk_size).map(move |start| {
+            let end = (start + chunk_size).min(self.len());
+            self.clone().line_range(start..end)
+        })
+    }
 
+    ///a view of the lines in range, a line view is sliced instead of stacking another one on it
+    fn line_range(self, range: Range<usize>) -> DataFrame {
+        if let InnerDataFrame::LineReorder { df, index_map } = self.inner.deref() {
+            return InnerDataFrame::LineReorder {
+                df: df.clone(),
+                index_map: index_map.slice(range),
+            }
+            .into();
+        }
         InnerDataFrame::LineReorder {
             df: self,
-            index_map,
+            index_map: range.into(),
         }
         .into()
     }
 
+    ///the rules the frame was read with, used when lines are appended from text
+    pub fn parse_rules(&self) -> ParseRules {
+        match self.inner.deref() {
+            InnerDataFrame::Base { df } => df.parse_rules,
+            InnerDataFrame::ColumnReorder { df, .. }
+            | InnerDataFrame::LineReorder { df, .. }
+            | InnerDataFrame::Computed { df, .. } => df.parse_rules(),
+            InnerDataFrame::Concat { parts, .. } => parts[0].parse_rules(),
+        }
+    }
+
+    pub fn with_parse_rules(self, parse_rules: ParseRules) -> DataFrame {
+        let mut base = BaseDataFrame::from(self);
+        base.parse_rules = parse_rules;
+        InnerDataFrame::Base { df: base }.into()
+    }
+
     pub fn len(&self) -> usize {
         match self.inner.deref() {
             InnerDataFrame::Base { df } => df.data.len(),
             InnerDataFrame::LineReorder { index_map, .. } => index_map.len(),
             InnerDataFrame::ColumnReorder { df, .. } => df.len(),
+            InnerDataFrame::Computed { cells, .. } => cells.len(),
+            InnerDataFrame::Concat { ends, .. } => ends.last().copied().unwrap_or(0),
         }
     }
 
@@ -161,6 +322,8 @@ impl DataFrame {
             InnerDataFrame::Base { df } => df.header.len(),
             InnerDataFrame::LineReorder { df, .. } => df.num_columns(),
             InnerDataFrame::ColumnReorder { index_map, .. } => index_map.len(),
+            InnerDataFrame::Computed { header, .. } => header.len(),
+            InnerDataFrame::Concat { parts, .. } => parts[0].num_columns(),
         }
     }
 
@@ -168,61 +331,296 @@ impl DataFrame {
         self.len() == 0
     }
 
-    pub fn sort<F, K>(self, mut key_gen: F) -> DataFrame
+    ///(number of lines, number of columns)
+    pub fn shape(&self) -> (usize, usize) {
+        (self.len(), self.num_columns())
+    }
+
+    ///stable sort by the key key_gen returns, it is called once per line
+    pub fn sort<F, K>(self, key_gen: F) -> DataFrame
     where
         F: FnMut(Line) -> K,
         K: Ord,
     {
-        let mut index_map = (0..self.len()).collect::<Vec<_>>();
-        index_map.sort_by_key(|index| {
-            let line = self
-                .get(*index)
-                .expect("unreachable since index_map is 0 to len");
-            key_gen(line)
-        });
-
-        InnerDataFrame::LineReorder {
-            df: self,
-            index_map,
-        }
-        .into()
+        let keys: Vec<K> = self.iter().map(key_gen).collect();
+        self.sort_by_positions(|a, b| keys[a].cmp(&keys[b]), true)
     }
 
     pub fn drop_column<I>(self, index: I) -> DataFrame
     where
         I: DataFrameColumnIndex,
     {
-        let index_to_remove = index.get_usize(self.header());
-        InnerDataFrame::ColumnReorder {
-            index_map: (0..self.num_columns())
-                .filter(|index| *index != index_to_remove)
-                .collect(),
-            df: self,
+        self.drop_columns(&[index])
+    }
+
+    ///all indizes refer to the header of self (so they do not shift while dropping),
+    ///an index may occur more than once. panics if a column a kept derived column depends on
+    ///is dropped, select returns this as an error
+    pub fn drop_columns<I>(self, indizes: &[I]) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let mut keep = vec![true; self.num_columns()];
+        for index in indizes {
+            let index = index.get_usize(self.header());
+            assert!(
+                index < keep.len(),
+                "index out of Bound: {index} but frame has {} columns",
+                keep.len()
+            );
+            keep[index] = false;
         }
-        .into()
+        let index_map = (0..self.num_columns())
+            .filter(|index| keep[*index])
+            .collect::<Vec<_>>();
+        if let Err(err) = self.check_dependencies(&index_map) {
+            panic!("{err}");
+        }
+        self.reorder_columns(index_map)
     }
 
+    ///panics like drop_columns if a kept derived column depends on a column that is not kept
     pub fn drop_all_column_except<I>(self, indizes: &[I]) -> DataFrame
     where
         I: DataFrameColumnIndex,
     {
-        let to_keep = indizes.iter().map(|i| i.get_usize(self.header())).collect();
-        InnerDataFrame::ColumnReorder {
+        let to_keep = indizes
+            .iter()
+            .map(|i| i.get_usize(self.header()))
+            .collect::<Vec<_>>();
+        if let Err(err) = self.check_dependencies(&to_keep) {
+            panic!("{err}");
+        }
+        self.reorder_columns(to_keep)
+    }
+
+    ///the lines for which predicate is true projected to columns, the predicate sees the
+    ///whole line so it may use columns that are not kept
+    pub fn select<I, F>(self, columns: &[I], predicate: F) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+        F: FnMut(Line) -> bool,
+    {
+        let header: Vec<&str> = self.header().collect();
+        let mut to_keep = Vec::with_capacity(columns.len());
+        let mut missing = Vec::new();
+        for column in columns {
+            match column.try_get_usize(&header) {
+                Ok(index) => to_keep.push(index),
+                Err(column) => missing.push(column),
+            }
+        }
+        if !missing.is_empty() {
+            return Err(ShapeError::MissingColumns { columns: missing });
+        }
+        self.check_dependencies(&to_keep)?;
+        Ok(self.filter(predicate).reorder_columns(to_keep))
+    }
+
+    ///adds a column whose cells are computed by f when a line is first accessed,
+    ///so only the lines actually read (e.g. after head) evaluate f
+    pub fn with_computed_column(
+        self,
+        header: &str,
+        f: Arc<dyn Fn(Line) -> Data + Send + Sync>,
+    ) -> DataFrame {
+        self.push_computed(header, f, None)
+    }
+
+    fn push_computed(
+        self,
+        header: &str,
+        f: Arc<dyn Fn(Line) -> Data + Send + Sync>,
+        depends_on: Option<Vec<String>>,
+    ) -> DataFrame {
+        let mut computed_header: Vec<_> = self.header().map(|string| string.to_owned()).collect();
+        computed_header.push(header.to_owned());
+        InnerDataFrame::Computed {
+            identity_index_map: (0..computed_header.len()).collect(),
+            header: computed_header,
+            f,
+            cells: LazyCells::new(self.len()),
+            depends_on,
             df: self,
-            index_map: to_keep,
         }
         .into()
     }
 
+    ///copies all visible data into a new base frame, computing all lazy columns,
+    ///columns added by with_derived are evaluated last on the copied lines
+    pub fn materialize(self) -> DataFrame {
+        observed("materialize", self.len(), DataFrame::len, move || {
+            let derivations = self.derivations();
+            if derivations.is_empty() {
+                InnerDataFrame::Base {
+                    df: BaseDataFrame::from(self),
+                }
+                .into()
+            } else {
+                self.rederive(derivations, true)
+            }
+        })
+    }
+
     pub fn fold_column<I, T, F>(&self, index: I, init: T, f: F) -> T
     where
         I: DataFrameColumnIndex,
         F: FnMut(T, Data) -> T,
+    {
+        self.column(index).cloned().fold(init, f)
+    }
+
+    ///like fold_column but f borrows the cells instead of getting a clone
+    pub fn fold_column_ref<I, T, F>(&self, index: I, init: T, f: F) -> T
+    where
+        I: DataFrameColumnIndex,
+        F: FnMut(T, &Data) -> T,
+    {
+        self.column(index).fold(init, f)
+    }
+
+    ///sums an Integer column in an i64, which cannot overflow for less than 2^32 lines,
+    ///Null cells are skipped and every other cell is an error
+    pub fn sum_column_i64<I>(&self, index: I) -> Result<i64, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.checked_sum_column(index)
+            .map(|sum| sum.expect("unreachable for less than 2^32 lines"))
+    }
+
+    ///like sum_column_i64 but returns None instead of overflowing
+    pub fn checked_sum_column<I>(&self, index: I) -> Result<Option<i64>, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let mut sum = Some(0i64);
+        for (line_index, line) in self.iter().enumerate() {
+            match &line[index] {
+                Data::Integer(int) => sum = sum.and_then(|sum| sum.checked_add(*int as i64)),
+                Data::Null => {}
+                found => {
+                    return Err(DataTypeError {
+                        line: line_index,
+                        expected: "Integer",
+                        found: found.clone(),
+                    })
+                }
+            }
+        }
+        Ok(sum)
+    }
+
+    ///the sum of the Integer and Float cells, None if there is none,
+    ///a NaN makes the sum NaN. Null cells are skipped and every other cell
+    ///is skipped as well unless strict is set, then it is an error
+    pub fn sum_column<I>(&self, index: I, strict: bool) -> Result<Option<f64>, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let (sum, count) = self.fold_numeric(
+            index,
+            strict,
+            CompensatedSum::default(),
+            |mut sum, value| {
+                sum.add(value);
+                sum
+            },
+        )?;
+        Ok((count > 0).then(|| sum.sum()))
+    }
+
+    ///like sum_column divided by the number of numeric cells
+    pub fn mean_column<I>(&self, index: I, strict: bool) -> Result<Option<f64>, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let (sum, count) = self.fold_numeric(
+            index,
+            strict,
+            CompensatedSum::default(),
+            |mut sum, value| {
+                sum.add(value);
+                sum
+            },
+        )?;
+        Ok((count > 0).then(|| sum.sum() / count as f64))
+    }
+
+    ///like sum_column but NaN is skipped unless every numeric cell is NaN
+    pub fn min_column<I>(&self, index: I, strict: bool) -> Result<Option<f64>, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.fold_numeric(index, strict, None, |min: Option<f64>, value| {
+            Some(min.map_or(value, |min| min.min(value)))
+        })
+        .map(|(min, _count)| min)
+    }
+
+    ///like min_column
+    pub fn max_column<I>(&self, index: I, strict: bool) -> Result<Option<f64>, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.fold_numeric(index, strict, None, |max: Option<f64>, value| {
+            Some(max.map_or(value, |max| max.max(value)))
+        })
+        .map(|(max, _count)| max)
+    }
+
+    ///folds the Integer and Float cells as f64 and counts them
+    fn fold_numeric<I, T, F>(
+        &self,
+        index: I,
+        strict: bool,
+        init: T,
+        mut f: F,
+    ) -> Result<(T, usize), DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+        F: FnMut(T, f64) -> T,
+    {
+        let mut acc = init;
+        let mut count = 0;
+        for (line_index, data) in self.column(index).enumerate() {
+            let value = match data {
+                Data::Integer(int) => *int as f64,
+                Data::Float(float) => *float as f64,
+                Data::Null => continue,
+                _ if !strict => continue,
+                found => {
+                    return Err(DataTypeError {
+                        line: line_index,
+                        expected: "Integer or Float",
+                        found: found.clone(),
+                    })
+                }
+            };
+            acc = f(acc, value);
+            count += 1;
+        }
+        Ok((acc, count))
+    }
+
+    ///resolves a column once so closures over lines (filter, sort, group_by, ...)
+    ///can index with the handle instead of comparing header names on every line
+    pub fn column_handle<I>(&self, index: I) -> ColumnHandle
+    where
+        I: DataFrameColumnIndex,
     {
         let index = index.get_usize(self.header());
-        self.iter().map(|line| line[index].clone()).fold(init, f)
+        assert!(
+            index < self.num_columns(),
+            "index out of Bound: {index} but frame has {} columns",
+            self.num_columns()
+        );
+        ColumnHandle::new(index)
     }
 
+    ///indexing the line by &str inside the closure searches the header on every line,
+    ///prefer a ColumnHandle from DataFrame::column_handle
     pub fn filter<F>(self, mut filter: F) -> DataFrame
     where
         F: FnMut(Line) -> bool,
@@ -231,11 +629,11 @@ impl DataFrame {
             .iter()
             .enumerate()
             .filter_map(|(i, line)| if filter(line) { Some(i) } else { None })
-            .collect();
+            .collect::<Vec<_>>();
 
         InnerDataFrame::LineReorder {
             df: self,
-            index_map,
+            index_map: index_map.into(),
         }
         .into()
     }
@@ -245,61 +643,348 @@ impl DataFrame {
         F: FnMut(Line) -> G,
         G: Hash + Eq,
     {
-        let mut map = HashMap::new();
-        for (i, line) in self.iter().enumerate() {
-            let key = grouper(line);
-            let vec: &mut Vec<_> = map.entry(key).or_default();
-            vec.push(i);
-        }
-
-        Groups::new(
-            map.drain()
-                .map(|(key, index_map)| {
-                    (
-                        key,
-                        InnerDataFrame::LineReorder {
-                            df: self.clone(),
-                            index_map,
-                        }
-                        .into(),
-                    )
-                })
-                .collect(),
-        )
+        observed("group_by", self.len(), Groups::len, move || {
+            let mut map = HashMap::new();
+            for (i, line) in self.iter().enumerate() {
+                let key = grouper(line);
+                let vec: &mut Vec<_> = map.entry(key).or_default();
+                vec.push(i);
+            }
+
+            //first occurrence order, the first index of each group is its first line
+            let mut groups: Vec<(G, Vec<usize>)> = map.drain().collect();
+            groups.sort_unstable_by_key(|(_key, index_map)| index_map[0]);
+
+            Groups::new(groups.into_iter().map(|(key, index_map)| {
+                (
+                    key,
+                    InnerDataFrame::LineReorder {
+                        df: self.clone(),
+                        index_map: index_map.into(),
+                    }
+                    .into(),
+                )
+            }))
+        })
+    }
+
+    ///like group_by for a frame sorted by the key, a group ends whenever the key changes
+    ///so no line is hashed
+    ///
+    ///if the frame is not sorted a key can occur in more than one run of lines, these runs are
+    ///concatenated into one group (debug builds panic instead)
+    pub fn group_by_sorted<F, G>(self, mut grouper: F) -> Groups<G>
+    where
+        F: FnMut(Line) -> G,
+        G: Hash + Eq,
+    {
+        observed("group_by_sorted", self.len(), Groups::len, move || {
+            let mut runs = Vec::new();
+            let mut current: Option<(G, usize)> = None;
+            for (i, line) in self.iter().enumerate() {
+                let key = grouper(line);
+                match current.take() {
+                    Some((current_key, start)) if current_key == key => {
+                        current = Some((current_key, start))
+                    }
+                    Some((current_key, start)) => {
+                        runs.push((current_key, start..i));
+                        current = Some((key, i));
+                    }
+                    None => current = Some((key, i)),
+                }
+            }
+            if let Some((key, start)) = current {
+                runs.push((key, start..self.len()));
+            }
+
+            //the start of the first run keeps the groups in first occurrence order
+            let mut map: HashMap<G, (usize, DataFrame)> = HashMap::with_capacity(runs.len());
+            for (key, range) in runs {
+                let start = range.start;
+                let group: DataFrame = InnerDataFrame::LineReorder {
+                    df: self.clone(),
+                    index_map: range.into(),
+                }
+                .into();
+                match map.remove(&key) {
+                    Some((first, previous)) => {
+                        debug_assert!(false, "group_by_sorted on a frame not sorted by the key");
+                        map.insert(key, (first, previous.concat(group)));
+                    }
+                    None => {
+                        map.insert(key, (start, group));
+                    }
+                }
+            }
+            let mut groups: Vec<_> = map.drain().collect();
+            groups.sort_unstable_by_key(|(_key, (first, _group))| *first);
+            Groups::new(
+                groups
+                    .into_iter()
+                    .map(|(key, (_first, group))| (key, group)),
+            )
+        })
     }
 
-    pub fn header(&self) -> HeaderIter {
+    ///groups by the value of a column and removes that column from the groups,
+    ///reverses Groups::to_long_frame
+    pub fn group_by_column_as_keys<I>(self, index: I) -> Groups<Data>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let mut groups = self.group_by(|line| line[index].clone());
+        for (_key, group) in groups.iter_mut() {
+            *group = group.clone().drop_column(index);
+        }
+        groups
+    }
+
+    pub fn header(&self) -> HeaderIter<'_> {
         HeaderIter::new(self)
     }
 
-    pub fn iter(&self) -> LineIter {
+    pub fn iter(&self) -> LineIter<'_> {
         LineIter::new(self)
     }
 
-    pub fn get(&self, index: usize) -> Option<Line> {
+    ///the cells of one column from the first to the last line, the index is resolved once
+    pub fn column<I>(&self, index: I) -> ColumnIter<'_>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        assert!(
+            index < self.num_columns(),
+            "index out of Bound: {index} but frame has {} columns",
+            self.num_columns()
+        );
+        ColumnIter {
+            lines: self.iter(),
+            index,
+        }
+    }
+
+    pub fn column_to_vec<I>(&self, index: I) -> Vec<Data>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.column(index).cloned().collect()
+    }
+
+    pub fn get(&self, index: usize) -> Option<Line<'_>> {
         match self.inner.deref() {
             InnerDataFrame::Base { df } => df
                 .data
                 .get(index)
                 .map(|line| Line::new(&df.header, line, &df.identity_index_map)),
             InnerDataFrame::LineReorder { df, index_map } => {
-                index_map.get(index).and_then(|index| df.get(*index))
+                index_map.get(index).and_then(|index| df.get(index))
             }
 
-            InnerDataFrame::ColumnReorder { df, index_map } => {
+            InnerDataFrame::ColumnReorder { df, line_map, .. } => {
                 let line = df.get(index);
-                line.map(|line| line.with_index_map(index_map))
+                line.map(|line| line.with_index_map(line_map))
+            }
+
+            InnerDataFrame::Computed {
+                header,
+                identity_index_map,
+                cells,
+                ..
+            } => (index < cells.len())
+                .then(|| Line::computed(header, identity_index_map, self, index)),
+
+            InnerDataFrame::Concat { parts, ends } => {
+                let part = ends.partition_point(|end| *end <= index);
+                let start = part.checked_sub(1).map(|part| ends[part]).unwrap_or(0);
+                parts.get(part).and_then(|df| df.get(index - start))
+            }
+        }
+    }
+
+    ///the cell at position of the line index of a computed frame, the computed cell is evaluated
+    ///on its first access and the others are read from the frame below
+    pub(super) fn computed_cell(&self, index: usize, position: usize) -> &Data {
+        let InnerDataFrame::Computed {
+            df,
+            header,
+            f,
+            cells,
+            ..
+        } = self.inner.deref()
+        else {
+            unreachable!("only lines of computed frames point back to their frame")
+        };
+        let line = df
+            .get(index)
+            .expect("unreachable since cells has the length of df");
+        if position + 1 < header.len() {
+            line.data(position)
+        } else {
+            cells.get_or_init(index, || f(line))
+        }
+    }
+
+    ///other behind self without copying any line, self is extended in place if it is
+    ///already a concatenation that is not shared
+    pub(super) fn concat(mut self, other: DataFrame) -> DataFrame {
+        assert!(
+            self.header().eq(other.header()),
+            "cannot concat frames with different headers"
+        );
+        let len = self.len() + other.len();
+        if let Some(InnerDataFrame::Concat { parts, ends }) = Arc::get_mut(&mut self.inner) {
+            parts.push(other);
+            ends.push(len);
+            return self;
+        }
+        InnerDataFrame::Concat {
+            ends: vec![self.len(), len],
+            parts: vec![self, other],
+        }
+        .into()
+    }
+
+    ///a line of self indexes its cells through this map, None if it differs between lines
+    fn line_index_map(&self) -> Option<&[usize]> {
+        match self.inner.deref() {
+            InnerDataFrame::Base { df } => Some(&df.identity_index_map),
+            InnerDataFrame::LineReorder { df, .. } => df.line_index_map(),
+            InnerDataFrame::ColumnReorder { line_map, .. } => Some(line_map),
+            InnerDataFrame::Computed {
+                identity_index_map, ..
+            } => Some(identity_index_map),
+            InnerDataFrame::Concat { parts, .. } => {
+                let first = parts[0].line_index_map()?;
+                parts
+                    .iter()
+                    .all(|part| part.line_index_map() == Some(first))
+                    .then_some(first)
             }
         }
     }
 
+    ///a view of the columns at the positions of index_map,
+    ///the lines of self keep their cells so index_map has to be resolved against their map
+    fn reorder_columns(self, index_map: Vec<usize>) -> DataFrame {
+        let df: DataFrame = if self.line_index_map().is_some() {
+            self
+        } else {
+            InnerDataFrame::Base {
+                df: BaseDataFrame::from(self),
+            }
+            .into()
+        };
+        let inner_map = df
+            .line_index_map()
+            .expect("unreachable since df is a Base frame otherwise");
+        let line_map = index_map.iter().map(|index| inner_map[*index]).collect();
+        InnerDataFrame::ColumnReorder {
+            df,
+            index_map,
+            line_map,
+        }
+        .into()
+    }
+
+    ///collapses a stack of line and column views into at most one line view below one column
+    ///view by composing their maps, no line is copied and the frame below the stack
+    ///(a base, computed or concatenated frame) is shared
+    pub fn compact(self) -> DataFrame {
+        let (df, lines, columns) = self.flatten_views();
+        let df = match lines {
+            Some(index_map) => InnerDataFrame::LineReorder { df, index_map }.into(),
+            None => df,
+        };
+        match columns {
+            Some((index_map, line_map)) => InnerDataFrame::ColumnReorder {
+                df,
+                index_map,
+                line_map,
+            }
+            .into(),
+            None => df,
+        }
+    }
+
+    ///the first frame below the views with the composed line map and (index map, line map)
+    ///of the columns, a line view can be moved below a column view since it does not change
+    ///the cells of its lines
+    fn flatten_views(&self) -> (DataFrame, Option<IndexMap>, Option<ColumnMaps>) {
+        match self.inner.deref() {
+            InnerDataFrame::LineReorder { df, index_map } => {
+                let (df, lines, columns) = df.flatten_views();
+                let lines = match lines {
+                    Some(inner) => inner.compose(index_map),
+                    None => index_map.clone(),
+                };
+                (df, Some(lines), columns)
+            }
+            InnerDataFrame::ColumnReorder {
+                df,
+                index_map,
+                line_map,
+            } => {
+                let (df, lines, columns) = df.flatten_views();
+                let index_map = match columns {
+                    Some((inner, _inner_line_map)) => {
+                        index_map.iter().map(|index| inner[*index]).collect()
+                    }
+                    None => index_map.clone(),
+                };
+                //line_map already points into the lines of the frame below all views
+                (df, lines, Some((index_map, line_map.clone())))
+            }
+            _ => (self.clone(), None, None),
+        }
+    }
+
+    ///concatenates groups of the same frame in the given order, as a single view if all of them
+    ///are line views of one frame
+    pub(super) fn merge_groups(mut groups: Vec<DataFrame>) -> DataFrame {
+        let views: Option<Vec<_>> = groups
+            .iter()
+            .map(|group| match group.inner.deref() {
+                InnerDataFrame::LineReorder { df, index_map } => Some((df, index_map)),
+                _ => None,
+            })
+            .collect();
+
+        if let Some(views) = views {
+            let shared = views
+                .windows(2)
+                .all(|pair| Arc::ptr_eq(&pair[0].0.inner, &pair[1].0.inner));
+            if let (true, Some((df, _))) = (shared, views.first()) {
+                let df = (*df).clone();
+                let index_map = views
+                    .iter()
+                    .flat_map(|(_df, index_map)| index_map.iter())
+                    .collect::<Vec<_>>();
+                return InnerDataFrame::LineReorder {
+                    df,
+                    index_map: index_map.into(),
+                }
+                .into();
+            }
+        }
+
+        groups
+            .drain(..)
+            .reduce(|merged, group| merged.append_data_frame(group))
+            .unwrap_or_else(DataFrame::empty)
+    }
+
     fn get_on_header(&self, index: usize) -> Option<&str> {
         match self.inner.deref() {
             InnerDataFrame::Base { df, .. } => df.header.get(index).map(|string| &string[..]),
             InnerDataFrame::LineReorder { df, .. } => df.get_on_header(index),
-            InnerDataFrame::ColumnReorder { df, index_map } => index_map
+            InnerDataFrame::ColumnReorder { df, index_map, .. } => index_map
                 .get(index)
                 .and_then(|index| df.get_on_header(*index)),
+            InnerDataFrame::Computed { header, .. } => header.get(index).map(|string| &string[..]),
+            InnerDataFrame::Concat { parts, .. } => parts[0].get_on_header(index),
         }
     }
 }
@@ -355,6 +1040,31 @@ impl<'df> Iterator for LineIter<'df> {
     }
 }
 
+pub struct ColumnIter<'df> {
+    lines: LineIter<'df>,
+    index: usize,
+}
+
+impl<'df> FusedIterator for ColumnIter<'df> {}
+impl<'df> ExactSizeIterator for ColumnIter<'df> {}
+impl<'df> DoubleEndedIterator for ColumnIter<'df> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.lines.next_back().map(|line| line.data(self.index))
+    }
+}
+
+impl<'df> Iterator for ColumnIter<'df> {
+    type Item = &'df Data;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.lines.next().map(|line| line.data(self.index))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.lines.size_hint()
+    }
+}
+
 impl<'df> IntoIterator for &'df DataFrame {
     type Item = Line<'df>;
 
@@ -415,3 +1125,633 @@ impl<'df> Iterator for HeaderIter<'df> {
         (size, Some(size))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    ///n lines of x with a computed column of 2 * x counting its evaluations in calls
+    fn counted(n: i32, calls: &Arc<AtomicUsize>) -> DataFrame {
+        let calls = calls.clone();
+        DataFrame::new(vec!["x"])
+            .append_lines((0..n).map(|x| vec![Data::Integer(x)]))
+            .with_computed_column(
+                "double",
+                Arc::new(move |line| {
+                    calls.fetch_add(1, Ordering::SeqCst);
+                    Data::Integer(line["x"].as_integer() * 2)
+                }),
+            )
+    }
+
+    fn column(df: &DataFrame, index: usize) -> Vec<i32> {
+        df.iter().map(|line| line[index].as_integer()).collect()
+    }
+
+    #[test]
+    fn from_rows_reports_the_first_wrong_row() {
+        let rows = |broken: usize| -> Vec<Vec<Data>> {
+            (0..5)
+                .map(|line| {
+                    let mut row = vec![Data::Integer(line as i32), Data::Float(0.5)];
+                    if line == broken {
+                        row.pop();
+                    }
+                    row
+                })
+                .collect()
+        };
+        let Err(err) = DataFrame::from_rows(vec!["id", "value"], rows(2)) else {
+            panic!("row 2 is short");
+        };
+        assert_eq!(
+            err,
+            ShapeError::LineLength {
+                line: 2,
+                expected: 2,
+                found: 1
+            }
+        );
+        assert_eq!(err.to_string(), "Line 2 has 1 entries but 2 were expected");
+
+        let df = DataFrame::from_rows(vec!["id", "value"], rows(5)).expect("all rows fit");
+        assert_eq!(df.shape(), (5, 2));
+        assert_eq!(column(&df, 0), [0, 1, 2, 3, 4]);
+        let df = DataFrame::with_capacity(vec!["id", "value"], 5).append_lines(rows(5).into_iter());
+        assert_eq!(df.shape(), (5, 2));
+    }
+
+    #[test]
+    fn head_only_computes_the_lines_it_keeps() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = counted(10_000, &calls).head(5);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        assert_eq!(column(&df, 1), [0, 2, 4, 6, 8]);
+        assert_eq!(column(&df, 1), [0, 2, 4, 6, 8]);
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn filter_computes_only_what_it_reads() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = counted(100, &calls).filter(|line| line["x"].as_integer() % 10 == 0);
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert_eq!(column(&df, 1), [0, 20, 40, 60, 80, 100, 120, 140, 160, 180]);
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = counted(100, &calls).filter(|line| line["double"].as_integer() > 150);
+        assert_eq!(calls.load(Ordering::SeqCst), 100);
+        assert_eq!(column(&df, 0), (76..100).collect::<Vec<_>>());
+        assert_eq!(column(&df, 1), (76..100).map(|x| 2 * x).collect::<Vec<_>>());
+        assert_eq!(calls.load(Ordering::SeqCst), 100);
+    }
+
+    #[test]
+    fn stacked_computed_columns_below_a_column_view() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = counted(10, &calls)
+            .with_computed_column(
+                "sum",
+                Arc::new(|line| Data::Integer(line[0].as_integer() + line[1].as_integer())),
+            )
+            .drop_column("x");
+        assert_eq!(df.num_columns(), 2);
+        assert_eq!(df.header().collect::<Vec<_>>(), ["double", "sum"]);
+
+        let sums: Vec<_> = (0..10).map(|x| 3 * x).collect();
+        assert_eq!(column(&df, 1), sums);
+        assert_eq!(column(&df, 0), (0..10).map(|x| 2 * x).collect::<Vec<_>>());
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+
+        let df = df.materialize();
+        assert!(matches!(df.inner.deref(), InnerDataFrame::Base { .. }));
+        assert_eq!(column(&df, 1), sums);
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+    }
+
+    #[test]
+    fn cells_are_allocated_in_chunks() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = counted(3000, &calls);
+        let InnerDataFrame::Computed { cells, .. } = df.inner.deref() else {
+            panic!("df is a computed frame")
+        };
+        assert_eq!(cells.len(), 3000);
+        assert_eq!(
+            df.get(2999).expect("df has 3000 lines")[1],
+            Data::Integer(5998)
+        );
+        assert_eq!(
+            df.get(1024).expect("df has 3000 lines")[1],
+            Data::Integer(2048)
+        );
+        assert!(df.get(3000).is_none());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    fn single_column(values: impl IntoIterator<Item = Data>) -> DataFrame {
+        DataFrame::new(vec!["value"]).append_lines(values.into_iter().map(|data| vec![data]))
+    }
+
+    #[test]
+    fn integer_sum_goes_past_i32_max() {
+        let df = single_column([Data::Integer(i32::MAX), Data::Null, Data::Integer(1)]);
+        assert_eq!(df.sum_column_i64(0), Ok(i32::MAX as i64 + 1));
+        assert_eq!(df.checked_sum_column(0), Ok(Some(i32::MAX as i64 + 1)));
+        //the i32 fold wraps around
+        assert_eq!(
+            df.fold_column(0, 0i32, |sum, data| sum
+                .wrapping_add(data.try_as_integer().unwrap_or(0))),
+            i32::MIN
+        );
+
+        let df = single_column([Data::Integer(i32::MIN), Data::Integer(-1)]);
+        assert_eq!(df.sum_column_i64(0), Ok(i32::MIN as i64 - 1));
+    }
+
+    #[test]
+    fn integer_sum_rejects_other_types() {
+        let df = single_column([Data::Integer(1), Data::Float(1.0)]);
+        let err = df.sum_column_i64(0).expect_err("a Float is not summed");
+        assert_eq!(err.line, 1);
+    }
+
+    #[test]
+    fn aggregates_of_empty_and_string_columns_are_none() {
+        let empty = single_column([]);
+        let strings = single_column(["a", "b"].map(|text| Data::String(Box::new(text.to_owned()))));
+        for df in [&empty, &strings] {
+            assert_eq!(df.sum_column(0, false), Ok(None));
+            assert_eq!(df.mean_column(0, false), Ok(None));
+            assert_eq!(df.min_column(0, false), Ok(None));
+            assert_eq!(df.max_column(0, false), Ok(None));
+        }
+        assert_eq!(empty.sum_column(0, true), Ok(None));
+        let err = strings
+            .max_column(0, true)
+            .expect_err("a String is not a number");
+        assert_eq!((err.line, err.expected), (0, "Integer or Float"));
+    }
+
+    #[test]
+    fn aggregates_mix_integers_and_floats() {
+        let df = single_column([
+            Data::Integer(3),
+            Data::Float(-1.5),
+            Data::Null,
+            Data::Float(f32::NAN),
+            Data::Integer(7),
+            Data::String(Box::new("7".to_owned())),
+        ]);
+        //min and max skip NaN, sum and mean do not
+        assert_eq!(df.min_column(0, false), Ok(Some(-1.5)));
+        assert_eq!(df.max_column(0, false), Ok(Some(7.0)));
+        assert!(df
+            .sum_column(0, false)
+            .is_ok_and(|sum| sum.is_some_and(f64::is_nan)));
+        assert!(df
+            .mean_column(0, false)
+            .is_ok_and(|mean| mean.is_some_and(f64::is_nan)));
+
+        let df = df.head(3);
+        assert_eq!(df.sum_column(0, true), Ok(Some(1.5)));
+        assert_eq!(df.mean_column(0, true), Ok(Some(0.75)));
+        let only_nan = single_column([Data::Float(f32::NAN), Data::Null]);
+        assert!(only_nan
+            .min_column(0, true)
+            .is_ok_and(|min| min.is_some_and(f64::is_nan)));
+    }
+
+    #[test]
+    fn float_sum_keeps_small_values_next_to_large_ones() {
+        //2^53 + 1 is not a f64, so a plain sum loses every one
+        let large = 2f32.powi(53);
+        let values = std::iter::once(Data::Float(large))
+            .chain(std::iter::repeat_n(Data::Integer(1), 1000))
+            .chain(std::iter::once(Data::Float(-large)));
+        let df = single_column(values);
+        assert_eq!(df.sum_column(0, true), Ok(Some(1000.0)));
+        assert_eq!(df.mean_column(0, true), Ok(Some(1000.0 / 1002.0)));
+    }
+
+    #[test]
+    fn float_sum_matches_the_exact_sum() {
+        //every value is a multiple of 1/8, so the sum is exact in eighths as an i128
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let values: Vec<f32> = (0..200_000)
+            .map(|line| match line % 1000 {
+                0 => 2f32.powi(60),
+                500 => -2f32.powi(60),
+                _ => (next() % (1 << 20)) as f32 / 8.0 + 1e5,
+            })
+            .collect();
+        let exact_eighths: i128 = values.iter().map(|value| (*value * 8.0) as i128).sum();
+        let exact = exact_eighths as f64 / 8.0;
+        let naive: f64 = values.iter().map(|value| *value as f64).sum();
+
+        let df = single_column(values.iter().map(|value| Data::Float(*value)));
+        let sum = df
+            .sum_column(0, true)
+            .expect("every cell is a Float")
+            .expect("the column has values");
+        assert!(
+            (sum - exact).abs() <= exact.abs() * f64::EPSILON,
+            "{sum} != {exact}"
+        );
+        assert!(
+            (naive - exact).abs() > (sum - exact).abs(),
+            "the data needs compensation"
+        );
+    }
+
+    fn letters() -> DataFrame {
+        let header = vec!["a", "b", "c", "d", "e"];
+        let row = (0..5).map(Data::Integer).collect();
+        DataFrame::new(header).append_lines(std::iter::once(row))
+    }
+
+    #[test]
+    fn drop_columns_resolves_against_the_original_header() {
+        let df = letters();
+        let mixed = [
+            df.column_handle(1),
+            df.column_handle("d"),
+            df.column_handle(3),
+        ];
+        let dropped = df.clone().drop_columns(&mixed);
+        assert_eq!(dropped.header().collect::<Vec<_>>(), vec!["a", "c", "e"]);
+        assert_eq!(
+            dropped
+                .get(0)
+                .expect("one line")
+                .iter()
+                .cloned()
+                .collect::<Vec<_>>(),
+            vec![Data::Integer(0), Data::Integer(2), Data::Integer(4)]
+        );
+
+        //positions and names give the same frame, a column given twice is dropped once
+        let by_position = df.clone().drop_columns(&[1, 3, 1]);
+        let by_name = df.clone().drop_columns(&["b", "d", "b"]);
+        assert_eq!(by_position.to_string(), dropped.to_string());
+        assert_eq!(by_name.to_string(), dropped.to_string());
+
+        //a single layer on top of the base frame
+        match by_name.inner.deref() {
+            InnerDataFrame::ColumnReorder { df, .. } => {
+                assert!(matches!(df.inner.deref(), InnerDataFrame::Base { .. }))
+            }
+            _ => panic!("drop_columns adds a column view"),
+        }
+    }
+
+    fn people() -> DataFrame {
+        let rows = [("ada", 36), ("alan", 41), ("grace", 29), ("kurt", 30)]
+            .into_iter()
+            .enumerate()
+            .map(|(id, (name, age))| {
+                vec![
+                    Data::Integer(id as i32),
+                    Data::String(Box::new(name.to_owned())),
+                    Data::Integer(age),
+                ]
+            });
+        DataFrame::new(vec!["id", "name", "age"]).append_lines(rows)
+    }
+
+    #[test]
+    fn select_filters_on_a_column_it_drops() {
+        let df = people()
+            .select(&["name", "id"], |line| line["age"].as_integer() > 30)
+            .expect("both columns exist");
+        assert_eq!(df.header().collect::<Vec<_>>(), ["name", "id"]);
+        let lines: Vec<(String, i32)> = df
+            .iter()
+            .map(|line| (line[0].as_string(), line[1].as_integer()))
+            .collect();
+        assert_eq!(lines, [("ada".to_owned(), 0), ("alan".to_owned(), 1)]);
+
+        //one column view on one line view
+        match df.inner.deref() {
+            InnerDataFrame::ColumnReorder { df, .. } => {
+                assert!(matches!(
+                    df.inner.deref(),
+                    InnerDataFrame::LineReorder { .. }
+                ))
+            }
+            _ => panic!("select ends with a column view"),
+        }
+    }
+
+    #[test]
+    fn select_names_every_missing_column() {
+        let Err(err) = people().select(&["name", "height", "weight"], |_line| true) else {
+            panic!("height and weight are missing");
+        };
+        assert_eq!(
+            err,
+            ShapeError::MissingColumns {
+                columns: vec!["'height'".to_owned(), "'weight'".to_owned()]
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "the header does not contain 'height', 'weight'"
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Header does not contain 'f'")]
+    fn drop_column_names_the_missing_column() {
+        letters().drop_column("f");
+    }
+
+    #[test]
+    #[should_panic(expected = "index out of Bound: 5 but frame has 5 columns")]
+    fn drop_columns_rejects_positions_past_the_header() {
+        letters().drop_columns(&[0, 5]);
+    }
+
+    fn keyed(keys: &[i32]) -> DataFrame {
+        let rows = keys
+            .iter()
+            .enumerate()
+            .map(|(line, key)| vec![Data::Integer(*key), Data::Integer(line as i32)]);
+        DataFrame::new(vec!["key", "line"]).append_lines(rows)
+    }
+
+    #[test]
+    fn sorted_grouping_finds_the_hashed_groups() {
+        let df = keyed(&[1, 1, 1, 4, 5, 5, 9]);
+        let runs = df.clone().group_by_sorted(|line| line[0].as_integer());
+        let hashed = df.group_by(|line| line[0].as_integer());
+        assert_eq!(runs.len(), 4);
+        for (key, group) in hashed.iter() {
+            assert_eq!(column(&runs[*key], 1), column(group, 1), "group {key}");
+        }
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "group_by_sorted on a frame not sorted by the key")]
+    fn unsorted_frames_panic_in_debug_builds() {
+        keyed(&[1, 2, 1]).group_by_sorted(|line| line[0].as_integer());
+    }
+
+    fn numbers(lines: i32) -> DataFrame {
+        let rows = (0..lines).map(|id| {
+            vec![
+                Data::Integer(id),
+                Data::Integer(id / 4),
+                Data::Float(id as f32),
+            ]
+        });
+        DataFrame::new(vec!["id", "quarter", "value"]).append_lines(rows)
+    }
+
+    fn is_range_view(df: &DataFrame) -> bool {
+        match df.inner.deref() {
+            InnerDataFrame::LineReorder { index_map, .. } => {
+                matches!(index_map, IndexMap::Range(_))
+            }
+            _ => panic!("not a line view"),
+        }
+    }
+
+    fn id(line: &Line) -> i32 {
+        line[0].try_as_integer().expect("the ids are integers")
+    }
+
+    ///the same lines as a view with a range and as a view with a vec
+    fn range_and_vec(start: i32, end: i32) -> (DataFrame, DataFrame) {
+        let df = numbers(20);
+        let by_range = df.clone().range(start as usize, end as usize);
+        let by_vec = df.filter(|line| (start..end).contains(&id(&line)));
+        assert!(is_range_view(&by_range));
+        assert!(!is_range_view(&by_vec));
+        (by_range, by_vec)
+    }
+
+    fn assert_same(left: &DataFrame, right: &DataFrame) {
+        assert_eq!(left.shape(), right.shape());
+        assert_eq!(left.to_string(), right.to_string());
+    }
+
+    #[test]
+    fn contiguous_views_are_ranges() {
+        let df = numbers(20);
+        assert!(is_range_view(&df.clone().head(5)));
+        assert!(is_range_view(&df.clone().tail(5)));
+        assert!(is_range_view(&df.clone().range(2, 7)));
+        let (left, right) = df.clone().split_at(7);
+        assert!(is_range_view(&left) && is_range_view(&right));
+        assert!(df.chunks(6).all(|chunk| is_range_view(&chunk)));
+        assert!(is_range_view(&df.clone().head(10).tail(3)));
+
+        let groups = df.clone().group_by_sorted(|line| line[1].clone());
+        assert!(groups.iter().all(|(_key, group)| is_range_view(group)));
+        assert!(!is_range_view(&df.filter(|_line| true).head(3)));
+    }
+
+    #[test]
+    fn chunks_end_with_the_rest() {
+        let df = numbers(20).filter(|line| id(&line) % 3 != 0);
+        let ids = |df: &DataFrame| df.iter().map(|line| id(&line)).collect::<Vec<_>>();
+        let chunks: Vec<Vec<i32>> = df.chunks(5).map(|chunk| ids(&chunk)).collect();
+        assert_eq!(
+            chunks,
+            [
+                vec![1, 2, 4, 5, 7],
+                vec![8, 10, 11, 13, 14],
+                vec![16, 17, 19]
+            ]
+        );
+        assert_eq!(df.chunks(13).count(), 1);
+        assert_eq!(numbers(0).chunks(4).count(), 0);
+
+        let (left, right) = df.clone().split_at(13);
+        assert_eq!((left.len(), right.len()), (13, 0));
+        let (left, right) = df.split_at(2);
+        assert_eq!((ids(&left), right.len()), (vec![1, 2], 11));
+    }
+
+    #[test]
+    #[should_panic(expected = "chunk_size must not be 0")]
+    fn chunks_of_zero_lines_panic() {
+        let _ = numbers(3).chunks(0);
+    }
+
+    #[test]
+    #[should_panic(expected = "mid is 4 but the frame has 3 lines")]
+    fn split_past_the_end_panics() {
+        let _ = numbers(3).split_at(4);
+    }
+
+    #[test]
+    fn both_index_maps_read_the_same() {
+        let (by_range, by_vec) = range_and_vec(3, 13);
+        assert_same(&by_range, &by_vec);
+        assert_eq!(by_range.len(), 10);
+        assert_eq!(by_range.sum_column(2, true), by_vec.sum_column(2, true));
+        for column in by_range.header() {
+            assert!(by_range.column(column).eq(by_vec.column(column)));
+        }
+        for index in 0..12 {
+            let line = |df: &DataFrame| df.get(index).map(|line| id(&line));
+            assert_eq!(line(&by_range), line(&by_vec));
+        }
+    }
+
+    #[test]
+    fn both_index_maps_stack() {
+        let (by_range, by_vec) = range_and_vec(3, 13);
+        let even = |line: Line| id(&line) % 2 == 0;
+        for (left, right) in [
+            (by_range.clone().head(4), by_vec.clone().head(4)),
+            (by_range.clone().tail(4), by_vec.clone().tail(4)),
+            (by_range.clone().range(2, 9), by_vec.clone().range(2, 9)),
+            (by_range.clone().filter(even), by_vec.clone().filter(even)),
+            (
+                by_range.clone().sort_desc(|line| id(&line)),
+                by_vec.clone().sort_desc(|line| id(&line)),
+            ),
+            (by_range.clone().sample(6, 7), by_vec.clone().sample(6, 7)),
+        ] {
+            assert_same(&left, &right);
+            assert_same(&left.clone().compact(), &right.clone().compact());
+            let left = left.drop_column(1).head(3);
+            let right = right.drop_column(1).head(3);
+            assert_same(&left, &right);
+            assert_same(&left.compact(), &right.compact());
+        }
+
+        let split = |df: DataFrame| {
+            let (left, right) = df.split_at(4);
+            [left.to_csv_string(), right.to_csv_string()]
+        };
+        assert_eq!(split(by_range.clone()), split(by_vec.clone()));
+        let chunks = |df: &DataFrame| {
+            df.chunks(3)
+                .map(|chunk| chunk.to_csv_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(chunks(&by_range), chunks(&by_vec));
+    }
+
+    #[test]
+    fn groups_of_both_index_maps_merge_the_same() {
+        let df = numbers(20);
+        let by_range = df.clone().group_by_sorted(|line| line[1].clone());
+        let by_vec = df.clone().group_by(|line| line[1].clone());
+        assert!(!by_vec.iter().any(|(_key, group)| is_range_view(group)));
+
+        let merge = |groups: Groups<Data>| {
+            let merged = groups
+                .sorted_by(|key, _group| key.as_integer())
+                .map_keys(|key| key.try_as_integer().expect("the keys are integers") % 2);
+            [merged[0].to_string(), merged[1].to_string()]
+        };
+        assert_eq!(
+            merge(df.clone().group_by_sorted(|line| line[1].clone())),
+            merge(df.clone().group_by(|line| line[1].clone()))
+        );
+
+        let annotate = |groups: Groups<Data>| {
+            groups
+                .annotate("size", |_key, group| Data::Integer(group.len() as i32))
+                .to_csv_string()
+        };
+        assert_eq!(annotate(by_range), annotate(by_vec));
+    }
+
+    ///the number of line and column views above the first other frame and that frame
+    fn views(df: &DataFrame) -> (usize, usize, &DataFrame) {
+        match df.inner.deref() {
+            InnerDataFrame::LineReorder { df, .. } => {
+                let (lines, columns, below) = views(df);
+                (lines + 1, columns, below)
+            }
+            InnerDataFrame::ColumnReorder { df, .. } => {
+                let (lines, columns, below) = views(df);
+                (lines, columns + 1, below)
+            }
+            _ => (0, 0, df),
+        }
+    }
+
+    fn assert_same_cells(left: &DataFrame, right: &DataFrame) {
+        assert!(left.header().eq(right.header()));
+        assert_eq!(left.len(), right.len());
+        for index in 0..left.len() {
+            let left_line = left.get(index).expect("index is below len");
+            let right_line = right.get(index).expect("index is below len");
+            for column in 0..left.num_columns() {
+                assert_eq!(left_line[column], right_line[column], "{index} {column}");
+            }
+        }
+        for column in left.header() {
+            assert!(left.column(column).eq(right.column(column)), "{column}");
+        }
+    }
+
+    #[test]
+    fn compact_keeps_every_cell() {
+        let base = numbers(40);
+        let stacked = base
+            .clone()
+            .filter(|line| id(&line) % 3 != 0)
+            .sort_desc(|line| line[1].try_as_integer())
+            .drop_column("quarter")
+            .head(12)
+            .filter(|line| id(&line) % 2 == 0)
+            .drop_all_column_except(&["value", "id"])
+            .sort(|line| line["id"].try_as_integer().map(|id| id % 5));
+        assert_eq!(views(&stacked).0, 5);
+        assert_eq!(views(&stacked).1, 2);
+
+        let compacted = stacked.clone().compact();
+        let (lines, columns, below) = views(&compacted);
+        assert!(lines <= 1 && columns <= 1);
+        assert!(Arc::ptr_eq(&below.inner, &base.inner));
+        assert_same_cells(&stacked, &compacted);
+        let ids: Vec<Data> = compacted.iter().map(|line| line["id"].clone()).collect();
+        assert_eq!(ids, [20, 26, 32, 22, 38, 28, 34].map(Data::Integer));
+
+        //views on top of a compacted frame compact again
+        let more = compacted
+            .clone()
+            .tail(4)
+            .drop_column(0)
+            .filter(|_line| true);
+        assert_same_cells(&more, &more.clone().compact());
+        assert_eq!(views(&more.compact()).0, 1);
+    }
+
+    #[test]
+    fn compact_stops_at_computed_and_concatenated_frames() {
+        let computed = numbers(10)
+            .filter(|line| id(&line) > 2)
+            .with_computed_column("double", Arc::new(|line| &line[0] + &line[0]));
+        let (first, second) = computed.split_at(3);
+        let stacked = second
+            .concat(first)
+            .drop_column("value")
+            .sort_desc(|line| line["double"].try_as_integer())
+            .head(5)
+            .drop_column("id");
+        let compacted = stacked.clone().compact();
+        assert_same_cells(&stacked, &compacted);
+        let (lines, columns, below) = views(&compacted);
+        assert_eq!((lines, columns), (1, 1));
+        assert!(matches!(below.inner.deref(), InnerDataFrame::Concat { .. }));
+    }
+}
@@ -0,0 +1,375 @@
+use std::cmp::Reverse;
+use std::collections::HashMap;
+
+use super::{Data, DataFrame, DataFrameColumnIndex};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Agg {
+    Sum,
+    Mean,
+    Count,
+    Min,
+    Max,
+    First,
+    Last,
+    ///sample standard deviation
+    Std,
+    Median,
+}
+
+impl Agg {
+    ///numeric aggregations skip every cell that is neither Integer nor Float
+    ///and yield Null if no numeric cell is left, Count counts all non Null cells
+    pub(super) fn apply(&self, values: &[Data]) -> Data {
+        match self {
+            Agg::Count => {
+                Data::Integer(values.iter().filter(|data| !data.is_null()).count() as i32)
+            }
+            Agg::First => values.first().cloned().unwrap_or(Data::Null),
+            Agg::Last => values.last().cloned().unwrap_or(Data::Null),
+            Agg::Sum => Self::numeric(values)
+                .map(|(int_sum, float_sum, _count, only_integers)| {
+                    if only_integers {
+                        i32::try_from(int_sum)
+                            .map(Data::Integer)
+                            .unwrap_or(Data::Float(int_sum as f32))
+                    } else {
+                        Data::Float(float_sum as f32)
+                    }
+                })
+                .unwrap_or(Data::Null),
+            Agg::Mean => Self::numeric(values)
+                .map(|(_int_sum, float_sum, count, _only_integers)| {
+                    Data::Float((float_sum / count as f64) as f32)
+                })
+                .unwrap_or(Data::Null),
+            Agg::Std => {
+                let floats = Self::floats(values);
+                if floats.len() < 2 {
+                    return Data::Null;
+                }
+                let mean = floats.iter().sum::<f64>() / floats.len() as f64;
+                let squares: f64 = floats.iter().map(|float| (float - mean).powi(2)).sum();
+                Data::Float((squares / (floats.len() - 1) as f64).sqrt() as f32)
+            }
+            Agg::Median => {
+                let mut floats = Self::floats(values);
+                if floats.is_empty() {
+                    return Data::Null;
+                }
+                floats.sort_by(f64::total_cmp);
+                let middle = floats.len() / 2;
+                if floats.len().is_multiple_of(2) {
+                    Data::Float(((floats[middle - 1] + floats[middle]) / 2.0) as f32)
+                } else {
+                    Data::Float(floats[middle] as f32)
+                }
+            }
+            Agg::Min => Self::extremum(values, |a, b| a.min(b), |a, b| a.min(b)),
+            Agg::Max => Self::extremum(values, |a, b| a.max(b), |a, b| a.max(b)),
+        }
+    }
+
+    ///the lowercase name, used in the names of aggregated columns
+    pub fn name(&self) -> &'static str {
+        match self {
+            Agg::Sum => "sum",
+            Agg::Mean => "mean",
+            Agg::Count => "count",
+            Agg::Min => "min",
+            Agg::Max => "max",
+            Agg::First => "first",
+            Agg::Last => "last",
+            Agg::Std => "std",
+            Agg::Median => "median",
+        }
+    }
+
+    ///(integer sum, float sum, count, only integers)
+    ///
+    ///the float sum is accumulated in an f64 with Neumaier compensation,
+    ///so its error does not grow with the number of values
+    fn numeric(values: &[Data]) -> Option<(i64, f64, usize, bool)> {
+        let mut int_sum = 0i64;
+        let mut float_sum = CompensatedSum::default();
+        let mut count = 0;
+        let mut only_integers = true;
+        for data in values {
+            match data {
+                Data::Integer(int) => {
+                    int_sum += *int as i64;
+                    float_sum.add(*int as f64);
+                }
+                Data::Float(float) => {
+                    float_sum.add(*float as f64);
+                    only_integers = false;
+                }
+                _ => continue,
+            }
+            count += 1;
+        }
+        if count == 0 {
+            None
+        } else {
+            Some((int_sum, float_sum.sum(), count, only_integers))
+        }
+    }
+
+    fn floats(values: &[Data]) -> Vec<f64> {
+        values
+            .iter()
+            .filter_map(|data| match data {
+                Data::Integer(int) => Some(*int as f64),
+                Data::Float(float) => Some(*float as f64),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn extremum(
+        values: &[Data],
+        pick_int: fn(i32, i32) -> i32,
+        pick_float: fn(f32, f32) -> f32,
+    ) -> Data {
+        values
+            .iter()
+            .fold(Data::Null, |acc, data| match (acc, data) {
+                (Data::Null, Data::Integer(int)) => Data::Integer(*int),
+                (Data::Null, Data::Float(float)) => Data::Float(*float),
+                (Data::Integer(a), Data::Integer(b)) => Data::Integer(pick_int(a, *b)),
+                (Data::Integer(a), Data::Float(b)) => Data::Float(pick_float(a as f32, *b)),
+                (Data::Float(a), Data::Integer(b)) => Data::Float(pick_float(a, *b as f32)),
+                (Data::Float(a), Data::Float(b)) => Data::Float(pick_float(a, *b)),
+                (acc, _) => acc,
+            })
+    }
+}
+
+impl DataFrame {
+    ///one line per distinct combination of the group columns (in order of first occurrence)
+    ///with count, mean, std, min, max and median of the value column,
+    ///the statistics follow Agg, so count counts all non Null cells and the others skip non numeric ones
+    pub fn summary_by(&self, group_columns: &[&str], value_column: &str) -> DataFrame {
+        const SUMMARY: [Agg; 6] = [
+            Agg::Count,
+            Agg::Mean,
+            Agg::Std,
+            Agg::Min,
+            Agg::Max,
+            Agg::Median,
+        ];
+
+        let group_indizes: Vec<usize> = group_columns
+            .iter()
+            .map(|column| column.get_usize(self.header()))
+            .collect();
+        let specs = SUMMARY.map(|agg| (value_column, agg));
+        //the cells of the group columns are the key, agg writes it as a Vector into the first column
+        let summary = self
+            .clone()
+            .group_by(|line| Data::Vector(Box::new(line.select(&group_indizes))))
+            .agg("key", &specs);
+
+        let mut header: Vec<String> = group_columns
+            .iter()
+            .map(|column| column.to_string())
+            .collect();
+        header.extend(SUMMARY.iter().map(|agg| agg.name().to_owned()));
+        let data = summary.iter().map(|line| {
+            let mut cells = line[0]
+                .try_as_vec()
+                .expect("the key is a Vector of the group cells")
+                .clone();
+            cells.extend(line.iter().skip(1).cloned());
+            cells
+        });
+        DataFrame::new(header).append_lines(data)
+    }
+
+    ///one line per distinct value of the column with the number of lines holding it,
+    ///the most frequent value first and equal counts in order of first occurrence
+    pub fn value_counts<I>(&self, index: I) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let mut positions: HashMap<&Data, usize> = HashMap::new();
+        let mut counts: Vec<(&Data, i32)> = Vec::new();
+        for line in self.iter() {
+            let value = line.data(index);
+            let position = *positions.entry(value).or_insert_with(|| {
+                counts.push((value, 0));
+                counts.len() - 1
+            });
+            counts[position].1 += 1;
+        }
+        counts.sort_by_key(|(_value, count)| Reverse(*count));
+
+        let data = counts
+            .drain(..)
+            .map(|(value, count)| vec![value.clone(), Data::Integer(count)]);
+        DataFrame::new(vec!["value", "count"]).append_lines(data)
+    }
+}
+
+#[derive(Default)]
+pub(super) struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    pub(super) fn add(&mut self, value: f64) {
+        let sum = self.sum + value;
+        if self.sum.abs() >= value.abs() {
+            self.compensation += (self.sum - sum) + value;
+        } else {
+            self.compensation += (value - sum) + self.sum;
+        }
+        self.sum = sum;
+    }
+
+    pub(super) fn sum(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn float_sum(values: &[Data]) -> f64 {
+        let (_int_sum, float_sum, _count, _only_integers) =
+            Agg::numeric(values).expect("the values are numeric");
+        float_sum
+    }
+
+    #[test]
+    fn float_sum_keeps_small_values_next_to_large_ones() {
+        //2^53 + 1 is not a f64, so a plain sum loses every one
+        let large = 2f32.powi(53);
+        let values: Vec<Data> = std::iter::once(Data::Float(large))
+            .chain(std::iter::repeat_n(Data::Integer(1), 1000))
+            .chain(std::iter::once(Data::Float(-large)))
+            .collect();
+        assert_eq!(float_sum(&values), 1000.0);
+        assert_eq!(Agg::Sum.apply(&values), Data::Float(1000.0));
+        assert_eq!(
+            Agg::Mean.apply(&values),
+            Data::Float((1000.0 / 1002.0f64) as f32)
+        );
+    }
+
+    #[test]
+    fn float_sum_matches_the_exact_sum() {
+        //every value is a multiple of 1/8, so the sum is exact in eighths as an i128
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let values: Vec<f32> = (0..200_000)
+            .map(|line| match line % 1000 {
+                0 => 2f32.powi(60),
+                500 => -2f32.powi(60),
+                _ => (next() % (1 << 20)) as f32 / 8.0 + 1e5,
+            })
+            .collect();
+        let exact_eighths: i128 = values.iter().map(|value| (*value * 8.0) as i128).sum();
+        let exact = exact_eighths as f64 / 8.0;
+        let naive: f64 = values.iter().map(|value| *value as f64).sum();
+
+        let data: Vec<Data> = values.iter().map(|value| Data::Float(*value)).collect();
+        let sum = float_sum(&data);
+        assert!(
+            (sum - exact).abs() <= exact.abs() * f64::EPSILON,
+            "{sum} != {exact}"
+        );
+        assert!(
+            (naive - exact).abs() > (sum - exact).abs(),
+            "the data needs compensation"
+        );
+    }
+
+    fn prices() -> DataFrame {
+        let text = |string: &str| Data::String(Box::new(string.to_owned()));
+        let rows = [
+            ("a", "x", Data::Integer(1)),
+            ("b", "x", Data::Integer(10)),
+            ("a", "y", Data::Integer(2)),
+            ("a", "x", Data::Integer(4)),
+            ("b", "x", Data::Integer(20)),
+            ("a", "x", Data::Null),
+            ("b", "y", Data::Float(5.5)),
+        ]
+        .into_iter()
+        .map(|(city, shop, price)| vec![text(city), text(shop), price]);
+        DataFrame::new(vec!["city", "shop", "price"]).append_lines(rows)
+    }
+
+    fn assert_close(data: &Data, expected: f64) {
+        let Data::Float(float) = data else {
+            panic!("{data:?} is no Float");
+        };
+        assert!(
+            (*float as f64 - expected).abs() < 1e-5,
+            "{float} != {expected}"
+        );
+    }
+
+    #[test]
+    fn summary_of_two_groups() {
+        let summary = prices().summary_by(&["city"], "price");
+        assert_eq!(
+            summary.header().collect::<Vec<_>>(),
+            ["city", "count", "mean", "std", "min", "max", "median"]
+        );
+        assert_eq!(summary.len(), 2);
+
+        //a: 1, 2, 4 and a Null
+        let a = summary.get(0).expect("two groups");
+        assert_eq!(a["city"], Data::String(Box::new("a".to_owned())));
+        assert_eq!(a["count"], Data::Integer(3));
+        assert_close(&a["mean"], 7.0 / 3.0);
+        assert_close(&a["std"], (7.0f64 / 3.0).sqrt());
+        assert_eq!(a["min"], Data::Integer(1));
+        assert_eq!(a["max"], Data::Integer(4));
+        assert_close(&a["median"], 2.0);
+
+        //b: 10, 20, 5.5
+        let b = summary.get(1).expect("two groups");
+        assert_eq!(b["city"], Data::String(Box::new("b".to_owned())));
+        assert_eq!(b["count"], Data::Integer(3));
+        assert_close(&b["mean"], 35.5 / 3.0);
+        assert_close(&b["std"], (330.5f64 / 6.0).sqrt());
+        assert_eq!(b["min"], Data::Float(5.5));
+        assert_eq!(b["max"], Data::Float(20.0));
+        assert_close(&b["median"], 10.0);
+    }
+
+    #[test]
+    fn summary_by_two_columns_keeps_the_first_occurrence_order() {
+        let summary = prices().summary_by(&["city", "shop"], "price");
+        let keys: Vec<(Data, Data)> = summary
+            .iter()
+            .map(|line| (line["city"].clone(), line["shop"].clone()))
+            .collect();
+        let text = |string: &str| Data::String(Box::new(string.to_owned()));
+        assert_eq!(
+            keys,
+            vec![
+                (text("a"), text("x")),
+                (text("b"), text("x")),
+                (text("a"), text("y")),
+                (text("b"), text("y")),
+            ]
+        );
+        let counts: Vec<Data> = summary.iter().map(|line| line["count"].clone()).collect();
+        assert_eq!(counts, [2, 2, 1, 1].map(Data::Integer));
+        //a single value has no sample standard deviation
+        assert_eq!(summary.get(2).expect("four groups")["std"], Data::Null);
+        assert_close(&summary.get(0).expect("four groups")["std"], 4.5f64.sqrt());
+    }
+}
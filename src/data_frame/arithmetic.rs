@@ -0,0 +1,300 @@
+use std::ops::{Add, Div, Mul, Sub};
+
+use super::{ArithmeticError, Data};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    fn symbol(self) -> &'static str {
+        match self {
+            Op::Add => "+",
+            Op::Sub => "-",
+            Op::Mul => "*",
+            Op::Div => "/",
+        }
+    }
+
+    fn floats(self, a: f32, b: f32) -> f32 {
+        match self {
+            Op::Add => a + b,
+            Op::Sub => a - b,
+            Op::Mul => a * b,
+            Op::Div => a / b,
+        }
+    }
+
+    fn integers(self, a: i32, b: i32) -> Result<i32, ArithmeticError> {
+        let result = match self {
+            Op::Add => a.checked_add(b),
+            Op::Sub => a.checked_sub(b),
+            Op::Mul => a.checked_mul(b),
+            Op::Div if b == 0 => return Err(ArithmeticError::DivisionByZero),
+            Op::Div => a.checked_div(b),
+        };
+        result.ok_or(ArithmeticError::Overflow {
+            op: self.symbol(),
+            left: a,
+            right: b,
+        })
+    }
+}
+
+///the operators return Null where the try_ version returns an error
+///
+///- a Null on either side gives Null
+///- two Integers give an Integer (division truncates), overflow and division by 0 are errors
+///- an Integer and a Float or two Floats give a Float
+///- two Strings are concatenated by add
+///- two Vec2D are added or subtracted element wise
+///- every other pair is an error, this includes every pair with a Date
+///  since there is no Data variant for a duration to add to it
+impl Data {
+    pub fn try_add(&self, other: &Data) -> Result<Data, ArithmeticError> {
+        self.apply(Op::Add, other)
+    }
+
+    pub fn try_sub(&self, other: &Data) -> Result<Data, ArithmeticError> {
+        self.apply(Op::Sub, other)
+    }
+
+    pub fn try_mul(&self, other: &Data) -> Result<Data, ArithmeticError> {
+        self.apply(Op::Mul, other)
+    }
+
+    pub fn try_div(&self, other: &Data) -> Result<Data, ArithmeticError> {
+        self.apply(Op::Div, other)
+    }
+
+    fn apply(&self, op: Op, other: &Data) -> Result<Data, ArithmeticError> {
+        match (self, other) {
+            (Data::Null, _) | (_, Data::Null) => Ok(Data::Null),
+            (Data::Integer(a), Data::Integer(b)) => op.integers(*a, *b).map(Data::Integer),
+            (Data::Integer(a), Data::Float(b)) => Ok(Data::Float(op.floats(*a as f32, *b))),
+            (Data::Float(a), Data::Integer(b)) => Ok(Data::Float(op.floats(*a, *b as f32))),
+            (Data::Float(a), Data::Float(b)) => Ok(Data::Float(op.floats(*a, *b))),
+            (Data::String(a), Data::String(b)) if op == Op::Add => {
+                Ok(Data::String(Box::new(format!("{a}{b}"))))
+            }
+            (Data::Vec2D((ax, ay)), Data::Vec2D((bx, by))) if matches!(op, Op::Add | Op::Sub) => {
+                Ok(Data::Vec2D((op.floats(*ax, *bx), op.floats(*ay, *by))))
+            }
+            (left, right) => Err(ArithmeticError::Unsupported {
+                op: op.symbol(),
+                left: left.clone(),
+                right: right.clone(),
+            }),
+        }
+    }
+}
+
+impl Add for Data {
+    type Output = Data;
+
+    fn add(self, other: Data) -> Data {
+        self.try_add(&other).unwrap_or(Data::Null)
+    }
+}
+
+impl Add for &Data {
+    type Output = Data;
+
+    fn add(self, other: &Data) -> Data {
+        self.try_add(other).unwrap_or(Data::Null)
+    }
+}
+
+impl Sub for Data {
+    type Output = Data;
+
+    fn sub(self, other: Data) -> Data {
+        self.try_sub(&other).unwrap_or(Data::Null)
+    }
+}
+
+impl Sub for &Data {
+    type Output = Data;
+
+    fn sub(self, other: &Data) -> Data {
+        self.try_sub(other).unwrap_or(Data::Null)
+    }
+}
+
+impl Mul for Data {
+    type Output = Data;
+
+    fn mul(self, other: Data) -> Data {
+        self.try_mul(&other).unwrap_or(Data::Null)
+    }
+}
+
+impl Mul for &Data {
+    type Output = Data;
+
+    fn mul(self, other: &Data) -> Data {
+        self.try_mul(other).unwrap_or(Data::Null)
+    }
+}
+
+impl Div for Data {
+    type Output = Data;
+
+    fn div(self, other: Data) -> Data {
+        self.try_div(&other).unwrap_or(Data::Null)
+    }
+}
+
+impl Div for &Data {
+    type Output = Data;
+
+    fn div(self, other: &Data) -> Data {
+        self.try_div(other).unwrap_or(Data::Null)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SimpleDateTime;
+
+    const OPS: [Op; 4] = [Op::Add, Op::Sub, Op::Mul, Op::Div];
+
+    ///one value of every variant
+    fn samples() -> Vec<Data> {
+        let date = chrono::DateTime::from_timestamp(86_400, 0).expect("the time is valid");
+        vec![
+            Data::Null,
+            Data::Integer(7),
+            Data::Float(0.5),
+            Data::String(Box::new("ab".to_owned())),
+            Data::Boolean(true),
+            Data::Date(SimpleDateTime::from_utc(date)),
+            Data::Vec2D((1.0, -2.0)),
+            Data::Vector(Box::new(vec![Data::Integer(1)])),
+        ]
+    }
+
+    ///the variant of the result by the rules on the impl, None for an error
+    fn expected_type(op: Op, left: &str, right: &str) -> Option<&'static str> {
+        match (left, right) {
+            ("Null", _) | (_, "Null") => Some("Null"),
+            ("Integer", "Integer") => Some("Integer"),
+            ("Integer" | "Float", "Integer" | "Float") => Some("Float"),
+            ("String", "String") if op == Op::Add => Some("String"),
+            ("Vec2D", "Vec2D") if matches!(op, Op::Add | Op::Sub) => Some("Vec2D"),
+            _ => None,
+        }
+    }
+
+    fn try_apply(op: Op, left: &Data, right: &Data) -> Result<Data, ArithmeticError> {
+        match op {
+            Op::Add => left.try_add(right),
+            Op::Sub => left.try_sub(right),
+            Op::Mul => left.try_mul(right),
+            Op::Div => left.try_div(right),
+        }
+    }
+
+    fn operator(op: Op, left: &Data, right: &Data) -> (Data, Data) {
+        let (owned_left, owned_right) = (left.clone(), right.clone());
+        match op {
+            Op::Add => (left + right, owned_left + owned_right),
+            Op::Sub => (left - right, owned_left - owned_right),
+            Op::Mul => (left * right, owned_left * owned_right),
+            Op::Div => (left / right, owned_left / owned_right),
+        }
+    }
+
+    #[test]
+    fn every_pair_of_variants_follows_the_rules() {
+        for op in OPS {
+            for left in samples() {
+                for right in samples() {
+                    let pair = format!("{left:?} {} {right:?}", op.symbol());
+                    let result = try_apply(op, &left, &right);
+                    let (by_ref, by_value) = operator(op, &left, &right);
+                    match expected_type(op, left.type_name(), right.type_name()) {
+                        Some(type_name) => {
+                            let result = result.unwrap_or_else(|err| panic!("{pair}: {err}"));
+                            assert_eq!(result.type_name(), type_name, "{pair}");
+                            assert_eq!(by_ref, result, "{pair}");
+                            assert_eq!(by_value, result, "{pair}");
+                        }
+                        None => {
+                            assert_eq!(
+                                result,
+                                Err(ArithmeticError::Unsupported {
+                                    op: op.symbol(),
+                                    left: left.clone(),
+                                    right: right.clone(),
+                                }),
+                                "{pair}"
+                            );
+                            assert_eq!(by_ref, Data::Null, "{pair}");
+                            assert_eq!(by_value, Data::Null, "{pair}");
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn values_of_the_defined_pairs() {
+        let integer = Data::Integer(7);
+        let float = Data::Float(0.5);
+        let results = OPS.map(|op| try_apply(op, &integer, &Data::Integer(2)));
+        assert_eq!(results, [9, 5, 14, 3].map(|int| Ok(Data::Integer(int))));
+        let results = OPS.map(|op| try_apply(op, &integer, &float));
+        assert_eq!(
+            results,
+            [7.5, 6.5, 3.5, 14.0].map(|float| Ok(Data::Float(float)))
+        );
+        let results = OPS.map(|op| try_apply(op, &float, &integer));
+        assert_eq!(
+            results,
+            [7.5, -6.5, 3.5, 0.5 / 7.0].map(|float| Ok(Data::Float(float)))
+        );
+
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        assert_eq!(string("ab") + string("cd"), string("abcd"));
+        assert_eq!(
+            Data::Vec2D((1.0, -2.0)) - Data::Vec2D((0.5, 1.0)),
+            Data::Vec2D((0.5, -3.0))
+        );
+    }
+
+    #[test]
+    fn integer_errors() {
+        assert_eq!(
+            Data::Integer(i32::MAX).try_add(&Data::Integer(1)),
+            Err(ArithmeticError::Overflow {
+                op: "+",
+                left: i32::MAX,
+                right: 1,
+            })
+        );
+        assert_eq!(
+            Data::Integer(i32::MIN).try_div(&Data::Integer(-1)),
+            Err(ArithmeticError::Overflow {
+                op: "/",
+                left: i32::MIN,
+                right: -1,
+            })
+        );
+        assert_eq!(
+            Data::Integer(1).try_div(&Data::Integer(0)),
+            Err(ArithmeticError::DivisionByZero)
+        );
+        assert_eq!(Data::Integer(1) / Data::Integer(0), Data::Null);
+        assert_eq!(
+            Data::Integer(1) / Data::Float(0.0),
+            Data::Float(f32::INFINITY)
+        );
+    }
+}
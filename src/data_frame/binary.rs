@@ -0,0 +1,180 @@
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Error as IoError, ErrorKind, Read, Write};
+use std::path::Path;
+
+use super::{Data, DataFrame, SimpleDateTime};
+
+const MAGIC: &[u8; 8] = b"rundas\0b";
+const VERSION: u8 = 1;
+
+const NULL: u8 = 0;
+const STRING: u8 = 1;
+const INTEGER: u8 = 2;
+const FLOAT: u8 = 3;
+const BOOLEAN: u8 = 4;
+const DATE: u8 = 5;
+const VEC2D: u8 = 6;
+const VECTOR: u8 = 7;
+
+impl DataFrame {
+    ///writes the header and all lines in visible order in a compact binary format
+    ///that keeps the type of every cell but Category, the parse rules are not written
+    pub fn write_binary<W: Write>(&self, writer: &mut W) -> Result<(), IoError> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&[VERSION])?;
+        write_len(writer, self.num_columns())?;
+        for head_elem in self.header() {
+            write_str(writer, head_elem)?;
+        }
+        write_len(writer, self.len())?;
+        for line in self.iter() {
+            for data in line.iter() {
+                write_data(writer, data)?;
+            }
+        }
+        Ok(())
+    }
+
+    ///reads a frame written by write_binary, a different version of the format is an error
+    pub fn read_binary<R: Read>(reader: &mut R) -> Result<DataFrame, IoError> {
+        let mut magic = [0; 8];
+        reader.read_exact(&mut magic)?;
+        if &magic != MAGIC {
+            return Err(invalid("not a rundas binary frame".to_owned()));
+        }
+        let version = read_u8(reader)?;
+        if version != VERSION {
+            return Err(invalid(format!(
+                "binary format version {version} but {VERSION} is supported"
+            )));
+        }
+        let num_columns = read_len(reader)?;
+        let header = (0..num_columns)
+            .map(|_| read_string(reader))
+            .collect::<Result<Vec<_>, _>>()?;
+        let len = read_len(reader)?;
+        let mut data = Vec::new();
+        for _ in 0..len {
+            let line = (0..num_columns)
+                .map(|_| read_data(reader))
+                .collect::<Result<Vec<_>, _>>()?;
+            data.push(line);
+        }
+        Ok(DataFrame::new(header).append_lines(data.into_iter()))
+    }
+
+    pub fn to_binary_file(&self, path: &Path) -> Result<(), IoError> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        self.write_binary(&mut writer)?;
+        writer.flush()
+    }
+
+    pub fn from_binary_file(path: &Path) -> Result<DataFrame, IoError> {
+        DataFrame::read_binary(&mut BufReader::new(File::open(path)?))
+    }
+}
+
+fn invalid(message: String) -> IoError {
+    IoError::new(ErrorKind::InvalidData, message)
+}
+
+fn write_len<W: Write>(writer: &mut W, len: usize) -> Result<(), IoError> {
+    writer.write_all(&(len as u64).to_le_bytes())
+}
+
+fn write_str<W: Write>(writer: &mut W, string: &str) -> Result<(), IoError> {
+    write_len(writer, string.len())?;
+    writer.write_all(string.as_bytes())
+}
+
+fn write_data<W: Write>(writer: &mut W, data: &Data) -> Result<(), IoError> {
+    match data {
+        Data::Null => writer.write_all(&[NULL]),
+        Data::String(string) => {
+            writer.write_all(&[STRING])?;
+            write_str(writer, string)
+        }
+        //there is no cache to intern into when reading, so a Category comes back as String
+        Data::Category(category) => {
+            writer.write_all(&[STRING])?;
+            write_str(writer, category)
+        }
+        Data::Integer(int) => {
+            writer.write_all(&[INTEGER])?;
+            writer.write_all(&int.to_le_bytes())
+        }
+        Data::Float(float) => {
+            writer.write_all(&[FLOAT])?;
+            writer.write_all(&float.to_le_bytes())
+        }
+        Data::Boolean(boolean) => writer.write_all(&[BOOLEAN, *boolean as u8]),
+        Data::Date(date) => {
+            writer.write_all(&[DATE])?;
+            write_str(writer, &date.to_string())
+        }
+        Data::Vec2D((x, y)) => {
+            writer.write_all(&[VEC2D])?;
+            writer.write_all(&x.to_le_bytes())?;
+            writer.write_all(&y.to_le_bytes())
+        }
+        Data::Vector(vec) => {
+            writer.write_all(&[VECTOR])?;
+            write_len(writer, vec.len())?;
+            vec.iter().try_for_each(|data| write_data(writer, data))
+        }
+    }
+}
+
+fn read_array<R: Read, const N: usize>(reader: &mut R) -> Result<[u8; N], IoError> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+fn read_u8<R: Read>(reader: &mut R) -> Result<u8, IoError> {
+    read_array::<R, 1>(reader).map(|[byte]| byte)
+}
+
+fn read_len<R: Read>(reader: &mut R) -> Result<usize, IoError> {
+    let len = u64::from_le_bytes(read_array(reader)?);
+    usize::try_from(len).map_err(|_| invalid(format!("the lenght {len} does not fit into usize")))
+}
+
+fn read_string<R: Read>(reader: &mut R) -> Result<String, IoError> {
+    let len = read_len(reader)?;
+    let mut bytes = Vec::new();
+    reader.take(len as u64).read_to_end(&mut bytes)?;
+    if bytes.len() != len {
+        return Err(IoError::from(ErrorKind::UnexpectedEof));
+    }
+    String::from_utf8(bytes).map_err(|err| invalid(err.to_string()))
+}
+
+fn read_data<R: Read>(reader: &mut R) -> Result<Data, IoError> {
+    Ok(match read_u8(reader)? {
+        NULL => Data::Null,
+        STRING => Data::String(Box::new(read_string(reader)?)),
+        INTEGER => Data::Integer(i32::from_le_bytes(read_array(reader)?)),
+        FLOAT => Data::Float(f32::from_le_bytes(read_array(reader)?)),
+        BOOLEAN => Data::Boolean(read_u8(reader)? != 0),
+        DATE => {
+            let string = read_string(reader)?;
+            let date: SimpleDateTime = string
+                .parse()
+                .map_err(|err| invalid(format!("{string:?} is no date: {err}")))?;
+            Data::Date(date)
+        }
+        VEC2D => Data::Vec2D((
+            f32::from_le_bytes(read_array(reader)?),
+            f32::from_le_bytes(read_array(reader)?),
+        )),
+        VECTOR => {
+            let len = read_len(reader)?;
+            let vec = (0..len)
+                .map(|_| read_data(reader))
+                .collect::<Result<Vec<_>, _>>()?;
+            Data::Vector(Box::new(vec))
+        }
+        tag => return Err(invalid(format!("unknown cell tag {tag}"))),
+    })
+}
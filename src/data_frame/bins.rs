@@ -0,0 +1,257 @@
+use super::quantile::quantile_sorted;
+use super::{BinError, Data, DataFrame, DataFrameColumnIndex};
+
+///the edges and labels of bins, computed by DataFrame::qcut and reusable for other frames
+///
+///the first bin contains both of its edges, every other bin only its upper edge
+#[derive(Debug, Clone, PartialEq)]
+pub struct Binning {
+    ///increasing, one more than labels (only equal if all values were equal)
+    edges: Vec<f64>,
+    labels: Vec<Data>,
+}
+
+impl Binning {
+    ///the edges at the quantiles 0, 1/q, ..., 1 of the numeric cells of the column
+    ///(interpolated linearly between two values)
+    ///
+    ///if ties make an edge equal to the one before, the bin between them is empty and dropped
+    ///together with its label, so there may be less than q bins. ties at the minimum keep the
+    ///first bin (it contains its lower edge) and drop the empty bins above it instead.
+    ///without labels the label of a bin is its position among the q bins as an Integer,
+    ///which keeps the numbering of the remaining bins unchanged
+    pub fn quantiles<I>(
+        df: &DataFrame,
+        index: I,
+        q: usize,
+        labels: Option<&[&str]>,
+    ) -> Result<Binning, BinError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        if q == 0 {
+            return Err(BinError::NoBins);
+        }
+        if let Some(labels) = labels {
+            if labels.len() != q {
+                return Err(BinError::LabelCount {
+                    expected: q,
+                    found: labels.len(),
+                });
+            }
+        }
+
+        let mut values = Vec::with_capacity(df.len());
+        for (line, data) in df.column(index).enumerate() {
+            if let Some(value) = numeric(line, data)? {
+                values.push(value);
+            }
+        }
+        if values.is_empty() {
+            return Err(BinError::NoValues);
+        }
+        values.sort_by(f64::total_cmp);
+
+        let label = |i: usize| match labels {
+            Some(labels) => Data::String(Box::new(labels[i].to_owned())),
+            None => Data::Integer(i as i32),
+        };
+        let mut edges = vec![values[0]];
+        let mut bin_labels = Vec::with_capacity(q);
+        //the bin ending at the next edge
+        let mut bin = 0;
+        for i in 1..=q {
+            let edge = if i == q {
+                values[values.len() - 1]
+            } else {
+                quantile_sorted(&values, i as f64 / q as f64)
+            };
+            if edge > edges[edges.len() - 1] {
+                edges.push(edge);
+                bin_labels.push(label(bin));
+                bin = i;
+            } else if edges.len() > 1 {
+                bin = i;
+            }
+        }
+        //all values are equal, the single remaining bin is [value, value]
+        if bin_labels.is_empty() {
+            edges.push(values[0]);
+            bin_labels.push(label(0));
+        }
+
+        Ok(Binning {
+            edges,
+            labels: bin_labels,
+        })
+    }
+
+    pub fn edges(&self) -> &[f64] {
+        &self.edges
+    }
+
+    ///one label per bin
+    pub fn labels(&self) -> &[Data] {
+        &self.labels
+    }
+
+    ///the label of the bin containing value, None if it is outside of all bins
+    pub fn bin(&self, value: f64) -> Option<&Data> {
+        if value.is_nan() || value < self.edges[0] || value > self.edges[self.edges.len() - 1] {
+            return None;
+        }
+        let bin = self.edges[1..].partition_point(|upper| *upper < value);
+        Some(&self.labels[bin])
+    }
+
+    ///appends a column with the label of the bin of every line of the column,
+    ///Null, NaN and values outside of all bins get Null
+    pub fn apply<I>(&self, df: DataFrame, index: I, out_name: &str) -> Result<DataFrame, BinError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let mut column = Vec::with_capacity(df.len());
+        for (line, data) in df.column(index).enumerate() {
+            let label = numeric(line, data)?.and_then(|value| self.bin(value));
+            column.push(label.cloned().unwrap_or(Data::Null));
+        }
+        Ok(df.append_column(out_name, column))
+    }
+}
+
+///None for Null and NaN, every other cell that is neither Integer nor Float is an error
+fn numeric(line: usize, data: &Data) -> Result<Option<f64>, BinError> {
+    match data {
+        Data::Integer(int) => Ok(Some(*int as f64)),
+        Data::Float(float) if float.is_nan() => Ok(None),
+        Data::Float(float) => Ok(Some(*float as f64)),
+        Data::Null => Ok(None),
+        data => Err(BinError::NotNumeric {
+            line,
+            found: data.clone(),
+        }),
+    }
+}
+
+impl DataFrame {
+    ///appends a column assigning every line to one of q bins holding about the same number
+    ///of lines, see Binning::quantiles for how ties are handled.
+    ///the returned Binning puts the lines of another frame into the same bins
+    pub fn qcut<I>(
+        self,
+        index: I,
+        q: usize,
+        labels: Option<&[&str]>,
+        out_name: &str,
+    ) -> Result<(DataFrame, Binning), BinError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let binning = Binning::quantiles(&self, index, q, labels)?;
+        let df = binning.apply(self, index, out_name)?;
+        Ok((df, binning))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn values(values: &[Data]) -> DataFrame {
+        let rows = values.iter().map(|value| vec![value.clone()]);
+        DataFrame::new(vec!["value"]).append_lines(rows)
+    }
+
+    fn integers(values: &[i32]) -> DataFrame {
+        let values: Vec<Data> = values.iter().copied().map(Data::Integer).collect();
+        self::values(&values)
+    }
+
+    fn bins(df: &DataFrame) -> Vec<Data> {
+        df.column("bin").cloned().collect()
+    }
+
+    #[test]
+    fn ties_collapse_bins() {
+        let (df, binning) = integers(&[1, 1, 1, 1, 1, 1, 2, 3])
+            .qcut("value", 4, None, "bin")
+            .expect("the column is numeric");
+        //the edges at 1/4 and 2/4 are the minimum, so the bins 1 and 2 are empty
+        assert_eq!(binning.edges(), [1.0, 1.25, 3.0]);
+        assert_eq!(binning.labels(), [Data::Integer(0), Data::Integer(3)]);
+        assert_eq!(bins(&df), [0, 0, 0, 0, 0, 0, 3, 3].map(Data::Integer));
+
+        //the bins of the first frame are used for another one
+        let other = values(&[
+            Data::Float(0.5),
+            Data::Integer(1),
+            Data::Float(1.1),
+            Data::Integer(5),
+            Data::Null,
+        ]);
+        let other = binning
+            .apply(other, "value", "bin")
+            .expect("the column is numeric");
+        assert_eq!(
+            bins(&other),
+            [
+                Data::Null,
+                Data::Integer(0),
+                Data::Integer(0),
+                Data::Null,
+                Data::Null
+            ]
+        );
+
+        let (df, binning) = integers(&[4, 4, 4])
+            .qcut("value", 3, None, "bin")
+            .expect("the column is numeric");
+        assert_eq!(binning.edges(), [4.0, 4.0]);
+        assert_eq!(bins(&df), [0, 0, 0].map(Data::Integer));
+    }
+
+    #[test]
+    fn more_bins_than_distinct_values() {
+        let df = integers(&[10, 20, 20, 30]);
+        let (binned, binning) = df
+            .clone()
+            .qcut("value", 6, None, "bin")
+            .expect("the column is numeric");
+        assert_eq!(binning.edges(), [10.0, 15.0, 20.0, 25.0, 30.0]);
+        assert_eq!(bins(&binned), [0, 1, 1, 5].map(Data::Integer));
+
+        let labels = ["a", "b", "c", "d", "e", "f"];
+        let (binned, binning) = df
+            .qcut("value", 6, Some(&labels), "bin")
+            .expect("the column is numeric");
+        let kept: Vec<String> = binning
+            .labels()
+            .iter()
+            .map(|label| label.to_string())
+            .collect();
+        assert_eq!(kept, ["a", "b", "e", "f"]);
+        let bins: Vec<String> = bins(&binned).iter().map(|bin| bin.to_string()).collect();
+        assert_eq!(bins, ["a", "b", "b", "f"]);
+    }
+
+    #[test]
+    fn invalid_arguments_are_errors() {
+        let df = integers(&[1, 2, 3]);
+        assert_eq!(
+            df.clone().qcut("value", 0, None, "bin").err(),
+            Some(BinError::NoBins)
+        );
+        assert_eq!(
+            df.qcut("value", 2, Some(&["low"]), "bin").err(),
+            Some(BinError::LabelCount {
+                expected: 2,
+                found: 1
+            })
+        );
+        assert_eq!(
+            values(&[Data::Null]).qcut("value", 2, None, "bin").err(),
+            Some(BinError::NoValues)
+        );
+    }
+}
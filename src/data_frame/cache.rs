@@ -0,0 +1,377 @@
+use std::collections::HashSet;
+use std::fs::{read_to_string, rename, File};
+use std::hash::{Hash, Hasher};
+use std::io::{BufReader, BufWriter, Error as IoError, Read, Write};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+use super::{observed, Data, DataFrame, GroupCacheError, Groups, IndexMap, InnerDataFrame};
+
+const INDEX_MAGIC: &str = "# rundas group index";
+const CHECKPOINT_MAGIC: &[u8; 8] = b"rundas\0c";
+const CHECKPOINT_VERSION: u8 = 1;
+
+impl DataFrame {
+    ///a hash of the header and all cells in visible order that is the same in every run,
+    ///so it can be stored to check later that a frame did not change
+    pub fn content_hash(&self) -> u64 {
+        let mut hasher = Fnv1a::default();
+        self.len().hash(&mut hasher);
+        for head_elem in self.header() {
+            head_elem.hash(&mut hasher);
+        }
+        for line in self.iter() {
+            for data in line.iter() {
+                data.hash(&mut hasher);
+            }
+        }
+        hasher.finish()
+    }
+
+    ///saves the frame as dir/{key}.rundas in the binary format together with the content hash
+    ///of the frame and key, if the file already holds a frame with that hash it is loaded instead.
+    ///a file with another hash, format version or that can not be read is replaced,
+    ///the op reported to the observer is checkpoint_hit or checkpoint_miss
+    pub fn checkpoint(self, dir: &Path, key: &str) -> Result<DataFrame, IoError> {
+        let path = dir.join(format!("{key}.rundas"));
+        let mut hasher = Fnv1a::default();
+        self.content_hash().hash(&mut hasher);
+        key.hash(&mut hasher);
+        let hash = hasher.finish();
+
+        let cached = File::open(&path).ok().and_then(|file| {
+            let mut reader = BufReader::new(file);
+            let mut magic = [0; 8];
+            reader.read_exact(&mut magic).ok()?;
+            let mut version = [0];
+            reader.read_exact(&mut version).ok()?;
+            let mut saved_hash = [0; 8];
+            reader.read_exact(&mut saved_hash).ok()?;
+            let matches = &magic == CHECKPOINT_MAGIC
+                && version[0] == CHECKPOINT_VERSION
+                && u64::from_le_bytes(saved_hash) == hash;
+            matches.then_some(reader)
+        });
+        if let Some(mut reader) = cached {
+            let rows_in = self.len();
+            let loaded = observed(
+                "checkpoint_hit",
+                rows_in,
+                |result: &Option<DataFrame>| result.as_ref().map_or(0, DataFrame::len),
+                move || DataFrame::read_binary(&mut reader).ok(),
+            );
+            if let Some(df) = loaded {
+                return Ok(df);
+            }
+        }
+
+        observed(
+            "checkpoint_miss",
+            self.len(),
+            |result: &Result<DataFrame, IoError>| result.as_ref().map_or(0, DataFrame::len),
+            move || {
+                let df = self.materialize();
+                //written next to the file first so a crash never leaves half a checkpoint
+                let tmp_path = dir.join(format!("{key}.rundas.tmp"));
+                let mut writer = BufWriter::new(File::create(&tmp_path)?);
+                writer.write_all(CHECKPOINT_MAGIC)?;
+                writer.write_all(&[CHECKPOINT_VERSION])?;
+                writer.write_all(&hash.to_le_bytes())?;
+                df.write_binary(&mut writer)?;
+                writer.flush()?;
+                drop(writer);
+                rename(&tmp_path, &path)?;
+                Ok(df)
+            },
+        )
+    }
+}
+
+impl<G: Eq + Hash> Groups<G> {
+    ///writes the keys and the lines of each group together with the content hash of the
+    ///frame they were grouped from, all groups have to be views of the same frame
+    ///(as they are after group_by)
+    pub fn save_index(&self, path: &Path) -> Result<(), IoError>
+    where
+        G: Into<Data> + Clone,
+    {
+        let mut frame: Option<&DataFrame> = None;
+        let mut data = Vec::with_capacity(self.len());
+        for (key, group) in self.iter() {
+            let InnerDataFrame::LineReorder { df, index_map } = group.inner.deref() else {
+                return Err(IoError::other("a group is not a view of the grouped frame"));
+            };
+            if frame.is_some_and(|frame| !Arc::ptr_eq(&frame.inner, &df.inner)) {
+                return Err(IoError::other("the groups are views of different frames"));
+            }
+            frame = Some(df);
+            let lines = index_map
+                .iter()
+                .map(|index| i32::try_from(index).map(Data::Integer))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| IoError::other("a line index does not fit into an Integer"))?;
+            data.push(vec![key.clone().into(), Data::Vector(Box::new(lines))]);
+        }
+        let frame = frame
+            .ok_or_else(|| IoError::other("an empty Groups has no frame to save the index for"))?;
+
+        let index = DataFrame::new(vec!["key", "lines"]).append_lines(data.drain(..));
+        let mut file = File::create(path)?;
+        writeln!(
+            file,
+            "{INDEX_MAGIC} {:016x} {}",
+            frame.content_hash(),
+            frame.len()
+        )?;
+        file.write_all(index.to_csv_string().as_bytes())
+    }
+
+    ///rebuilds the groups saved by save_index as views of df without looking at its lines
+    ///except for checking its content hash
+    pub fn load_index(path: &Path, df: DataFrame) -> Result<Groups<G>, GroupCacheError>
+    where
+        G: TryFrom<Data>,
+    {
+        let text = read_to_string(path)?;
+        let format_error = || GroupCacheError::Format(format!("{path:?} is no group index"));
+        let (first_line, rest) = text.split_once('\n').ok_or_else(format_error)?;
+        let mut words = first_line
+            .strip_prefix(INDEX_MAGIC)
+            .ok_or_else(format_error)?
+            .split_whitespace();
+        let saved_hash = words
+            .next()
+            .and_then(|word| u64::from_str_radix(word, 16).ok())
+            .ok_or_else(format_error)?;
+        let saved_len: usize = words
+            .next()
+            .and_then(|word| word.parse().ok())
+            .ok_or_else(format_error)?;
+
+        let frame_hash = df.content_hash();
+        if saved_hash != frame_hash || saved_len != df.len() {
+            return Err(GroupCacheError::HashMismatch {
+                saved: saved_hash,
+                frame: frame_hash,
+            });
+        }
+
+        let index = DataFrame::from_string(rest.to_owned(), None)?;
+        let mut groups = Vec::with_capacity(index.len());
+        for line in index.iter() {
+            let key =
+                G::try_from(line[0].clone()).map_err(|_| GroupCacheError::Key(line[0].clone()))?;
+            let lines = line[1]
+                .try_as_vec()
+                .ok_or_else(format_error)?
+                .iter()
+                .map(|data| match data {
+                    Data::Integer(index) if (*index as usize) < df.len() && *index >= 0 => {
+                        Ok(*index as usize)
+                    }
+                    _ => Err(format_error()),
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            let group: DataFrame = InnerDataFrame::LineReorder {
+                df: df.clone(),
+                index_map: IndexMap::from(lines),
+            }
+            .into();
+            groups.push((key, group));
+        }
+
+        let mut keys = HashSet::new();
+        if !groups.iter().all(|(key, _group)| keys.insert(key)) {
+            return Err(format_error());
+        }
+        Ok(Groups::new(groups))
+    }
+}
+
+///64 bit FNV-1a, unlike the hasher of HashMap its keys are fixed
+struct Fnv1a(u64);
+
+impl Default for Fnv1a {
+    fn default() -> Self {
+        Fnv1a(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for Fnv1a {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= *byte as u64;
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+    use std::mem::discriminant;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::SortOptions;
+
+    fn sessions() -> DataFrame {
+        let rows = [
+            Data::String(Box::new("1".to_owned())),
+            Data::Integer(1),
+            Data::String(Box::new("1".to_owned())),
+            Data::Float(1.5),
+            Data::Integer(1),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(index, session)| vec![session, Data::Integer(index as i32)])
+        .collect();
+        DataFrame::from_rows(vec!["session", "line"], rows).expect("the rows fit the header")
+    }
+
+    fn temp_path(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("rundas_{name}_{}", std::process::id()))
+    }
+
+    fn lines(df: &DataFrame) -> Vec<Data> {
+        df.iter().map(|line| line[1].clone()).collect()
+    }
+
+    #[test]
+    fn keys_keep_their_type() {
+        let path = temp_path("index_keys");
+        let groups = sessions().group_by(|line| line[0].clone());
+        groups.save_index(&path).expect("the index is written");
+
+        let loaded = Groups::<Data>::load_index(&path, sessions()).expect("the index is read");
+        let keys: Vec<Data> = loaded.iter().map(|(key, _group)| key.clone()).collect();
+        assert!(keys.iter().eq(groups.iter().map(|(key, _group)| key)));
+        //the String "1" is not read back as an Integer
+        let kinds: HashSet<_> = keys.iter().map(discriminant).collect();
+        assert_eq!(kinds.len(), 3);
+        for (key, group) in groups.iter() {
+            assert_eq!(lines(&loaded[key.clone()]), lines(group));
+        }
+        std::fs::remove_file(&path).expect("the index can be removed");
+    }
+
+    #[test]
+    fn stale_hash_is_rejected() {
+        let path = temp_path("index_stale");
+        let df = sessions();
+        let saved_hash = df.content_hash();
+        df.group_by(|line| line[0].clone())
+            .save_index(&path)
+            .expect("the index is written");
+
+        let changed = sessions().append_line(vec![Data::Integer(2), Data::Integer(5)]);
+        let frame_hash = changed.content_hash();
+        let Err(err) = Groups::<Data>::load_index(&path, changed) else {
+            panic!("the index was saved for another frame");
+        };
+        assert!(matches!(
+            err,
+            GroupCacheError::HashMismatch { saved, frame } if saved == saved_hash && frame == frame_hash
+        ));
+
+        let reordered = sessions().sort_by_column("line", SortOptions::descending());
+        assert!(matches!(
+            Groups::<Data>::load_index(&path, reordered),
+            Err(GroupCacheError::HashMismatch { .. })
+        ));
+
+        std::fs::write(&path, sessions().to_csv_string()).expect("the frame is written");
+        assert!(matches!(
+            Groups::<Data>::load_index(&path, sessions()),
+            Err(GroupCacheError::Format(_))
+        ));
+        std::fs::remove_file(&path).expect("the index can be removed");
+    }
+
+    ///the checkpoint ops reported on this thread while f runs
+    fn checkpoint_ops<T>(f: impl FnOnce() -> T) -> (T, Vec<&'static str>) {
+        let ops = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let recorded = ops.clone();
+        crate::set_op_observer(Box::new(move |event| {
+            if event.op.starts_with("checkpoint") {
+                recorded.borrow_mut().push(event.op);
+            }
+        }));
+        let result = f();
+        crate::clear_op_observer();
+        let ops = ops.borrow().clone();
+        (result, ops)
+    }
+
+    fn filtered() -> DataFrame {
+        sessions().filter(|line| line[1] != Data::Integer(2))
+    }
+
+    #[test]
+    fn checkpoint_misses_then_hits() {
+        let dir = temp_path("checkpoint_hit");
+        std::fs::create_dir_all(&dir).expect("the temp dir is writable");
+
+        let (first, ops) = checkpoint_ops(|| filtered().checkpoint(&dir, "filtered"));
+        let first = first.expect("the checkpoint is written");
+        assert_eq!(ops, vec!["checkpoint_miss"]);
+        assert!(dir.join("filtered.rundas").exists());
+        assert!(!dir.join("filtered.rundas.tmp").exists());
+
+        let (second, ops) = checkpoint_ops(|| filtered().checkpoint(&dir, "filtered"));
+        let second = second.expect("the checkpoint is read");
+        assert_eq!(ops, vec!["checkpoint_hit"]);
+        assert_eq!(second.content_hash(), filtered().content_hash());
+        assert_eq!(second.content_hash(), first.content_hash());
+        assert_eq!(second.column_to_vec(0)[0].type_name(), "String");
+
+        //another key does not see the file
+        let (_other, ops) = checkpoint_ops(|| filtered().checkpoint(&dir, "other"));
+        assert_eq!(ops, vec!["checkpoint_miss"]);
+        std::fs::remove_dir_all(&dir).expect("the temp dir is removable");
+    }
+
+    #[test]
+    fn checkpoint_recomputes_a_changed_input_or_a_broken_file() {
+        let dir = temp_path("checkpoint_miss");
+        std::fs::create_dir_all(&dir).expect("the temp dir is writable");
+        let path = dir.join("filtered.rundas");
+        let (_df, ops) = checkpoint_ops(|| filtered().checkpoint(&dir, "filtered"));
+        assert_eq!(ops, vec!["checkpoint_miss"]);
+
+        //the input differs, so the saved result is replaced
+        let (df, ops) = checkpoint_ops(|| sessions().checkpoint(&dir, "filtered"));
+        assert_eq!(ops, vec!["checkpoint_miss"]);
+        assert_eq!(
+            df.expect("the checkpoint is written").content_hash(),
+            sessions().content_hash()
+        );
+        let (_df, ops) = checkpoint_ops(|| sessions().checkpoint(&dir, "filtered"));
+        assert_eq!(ops, vec!["checkpoint_hit"]);
+
+        //a file of another format version
+        let mut bytes = std::fs::read(&path).expect("the checkpoint exists");
+        bytes[CHECKPOINT_MAGIC.len()] = CHECKPOINT_VERSION + 1;
+        std::fs::write(&path, &bytes).expect("the temp dir is writable");
+        let (_df, ops) = checkpoint_ops(|| sessions().checkpoint(&dir, "filtered"));
+        assert_eq!(ops, vec!["checkpoint_miss"]);
+
+        //a file cut off behind the hash is a hit that fails to load and is written again
+        let bytes = std::fs::read(&path).expect("the checkpoint exists");
+        std::fs::write(&path, &bytes[..bytes.len() - 4]).expect("the temp dir is writable");
+        let (df, ops) = checkpoint_ops(|| sessions().checkpoint(&dir, "filtered"));
+        assert_eq!(ops, vec!["checkpoint_hit", "checkpoint_miss"]);
+        assert_eq!(
+            df.expect("the checkpoint is written").content_hash(),
+            sessions().content_hash()
+        );
+        assert_eq!(std::fs::read(&path).expect("the checkpoint exists"), bytes);
+        std::fs::remove_dir_all(&dir).expect("the temp dir is removable");
+    }
+}
@@ -1,10 +1,19 @@
-use chrono::{DateTime, Datelike, Local, Timelike};
-use serde::{Deserialize, Serialize};
+use chrono::{
+    DateTime, Datelike, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeDelta, TimeZone,
+    Timelike, Utc,
+};
+use serde::de::{self, MapAccess, Visitor};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::hash::{Hash, Hasher};
+use std::mem::discriminant;
 use std::str::FromStr;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+use super::display::group_digits;
+use super::{Category, ParseRules};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum Data {
     String(Box<String>),
     Integer(i32),
@@ -13,18 +22,50 @@ pub enum Data {
     Date(SimpleDateTime),
     Vector(Box<Vec<Data>>),
     Vec2D((f32, f32)),
+    Null,
+    ///a string stored in a StringCache, see DataFrame::categorize
+    Category(Category),
 }
 
 impl Data {
+    pub fn is_null(&self) -> bool {
+        matches!(self, Data::Null)
+    }
+
+    ///the name of the variant, e.g. "Integer"
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Data::String(_) => "String",
+            Data::Category(_) => "Category",
+            Data::Integer(_) => "Integer",
+            Data::Float(_) => "Float",
+            Data::Boolean(_) => "Boolean",
+            Data::Date(_) => "Date",
+            Data::Vector(_) => "Vector",
+            Data::Vec2D(_) => "Vec2D",
+            Data::Null => "Null",
+        }
+    }
+
     pub fn as_string(&self) -> String {
         format!("{}", self)
     }
 
+    ///like as_string but Vectors are cut according to config
+    pub fn as_string_capped(&self, config: &DataDisplayConfig) -> String {
+        self.display_capped(config).to_string()
+    }
+
+    ///formats like Display but Vectors are cut according to config
+    pub fn display_capped<'a>(&'a self, config: &'a DataDisplayConfig) -> CappedData<'a> {
+        CappedData { data: self, config }
+    }
+
     pub fn as_integer(&self) -> i32 {
         if let Data::Integer(int) = self {
             *int
         } else {
-            panic!("cannot convert {} to integer", self)
+            panic!("cannot convert {:?} to integer", self)
         }
     }
 
@@ -32,7 +73,7 @@ impl Data {
         if let Data::Float(float) = self {
             *float
         } else {
-            panic!("cannot convert {} to float", self)
+            panic!("cannot convert {:?} to float", self)
         }
     }
 
@@ -40,7 +81,7 @@ impl Data {
         if let Data::Boolean(boolean) = self {
             *boolean
         } else {
-            panic!("cannot convert {} to boolean", self)
+            panic!("cannot convert {:?} to boolean", self)
         }
     }
 
@@ -48,7 +89,7 @@ impl Data {
         if let Data::Date(time_date) = self {
             *time_date
         } else {
-            panic!("cannot convert {} to date", self)
+            panic!("cannot convert {:?} to date", self)
         }
     }
 
@@ -56,7 +97,7 @@ impl Data {
         if let Data::Vector(ref vec) = self {
             vec
         } else {
-            panic!("cannot convert {} to Vec", self)
+            panic!("cannot convert {:?} to Vec", self)
         }
     }
 
@@ -64,7 +105,7 @@ impl Data {
         if let Data::Vec2D(vec2d) = self {
             *vec2d
         } else {
-            panic!("cannot convert {} to Vec2", self)
+            panic!("cannot convert {:?} to Vec2", self)
         }
     }
 
@@ -115,12 +156,87 @@ impl Data {
             None
         }
     }
+}
+
+///floats are compared by their bits so that Data can be used as a key,
+///all NaNs are equal to each other and 0.0 equals -0.0
+fn float_key(float: f32) -> u32 {
+    if float.is_nan() {
+        f32::NAN.to_bits()
+    } else if float == 0.0 {
+        0
+    } else {
+        float.to_bits()
+    }
+}
 
+impl PartialEq for Data {
+    fn eq(&self, other: &Self) -> bool {
+        use Data::{Boolean, Category, Date, Float, Integer, Null, String, Vec2D, Vector};
+        match (self, other) {
+            (String(a), String(b)) => a == b,
+            (Integer(a), Integer(b)) => a == b,
+            (Float(a), Float(b)) => float_key(*a) == float_key(*b),
+            (Boolean(a), Boolean(b)) => a == b,
+            (Date(a), Date(b)) => a == b,
+            (Vector(a), Vector(b)) => a == b,
+            (Vec2D((ax, ay)), Vec2D((bx, by))) => {
+                float_key(*ax) == float_key(*bx) && float_key(*ay) == float_key(*by)
+            }
+            (Null, Null) => true,
+            (Category(a), Category(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Data {}
+
+impl Hash for Data {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        use Data::{Boolean, Category, Date, Float, Integer, Null, String, Vec2D, Vector};
+        discriminant(self).hash(state);
+        match self {
+            String(string) => string.hash(state),
+            Integer(integer) => integer.hash(state),
+            Float(float) => float_key(*float).hash(state),
+            Boolean(boolean) => boolean.hash(state),
+            Date(dt) => dt.hash(state),
+            Vector(vec) => vec.hash(state),
+            Vec2D((x, y)) => {
+                float_key(*x).hash(state);
+                float_key(*y).hash(state);
+            }
+            Null => {}
+            Category(category) => category.hash(state),
+        }
+    }
+}
+
+///only values of the same variant are ordered, consistent with PartialEq
+impl PartialOrd for Data {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        use Data::{Boolean, Date, Float, Integer, Null, String, Vec2D, Vector};
+        if self == other {
+            return Some(Ordering::Equal);
+        }
+        match (self, other) {
+            (String(a), String(b)) => a.partial_cmp(b),
+            (Integer(a), Integer(b)) => a.partial_cmp(b),
+            (Float(a), Float(b)) => a.partial_cmp(b),
+            (Boolean(a), Boolean(b)) => a.partial_cmp(b),
+            (Date(a), Date(b)) => a.partial_cmp(b),
+            (Vector(a), Vector(b)) => a.partial_cmp(b),
+            (Vec2D(a), Vec2D(b)) => a.partial_cmp(b),
+            (Null, Null) => Some(Ordering::Equal),
+            _ => None,
+        }
+    }
 }
 
 impl Display for Data {
     fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        use Data::{Boolean, Date, Float, Integer, String, Vec2D, Vector};
+        use Data::{Boolean, Category, Date, Float, Integer, Null, String, Vec2D, Vector};
         match self {
             String(string) => write!(f, "{}", string),
             Integer(integer) => write!(f, "{}", integer),
@@ -133,24 +249,115 @@ impl Display for Data {
                     dt.hour, dt.minute, dt.second, dt.day, dt.month, dt.year
                 )
             }
-            Vector(vec) => {
+            Vector(_) => write_vector(f, self, &DataDisplayConfig::default()),
+            Vec2D((x, y)) => write!(f, "({} | {})", x, y),
+            Null => Ok(()),
+            Category(category) => write!(f, "{}", category),
+        }
+    }
+}
+
+///how many elements and levels of nested Vectors are formatted by Data::display_capped,
+///both are unlimited by default (which is what Display does)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct DataDisplayConfig {
+    max_elements: Option<usize>,
+    max_depth: Option<usize>,
+}
+
+impl DataDisplayConfig {
+    pub fn new() -> DataDisplayConfig {
+        DataDisplayConfig::default()
+    }
+
+    ///the first elements of every Vector followed by "… (+1_000 more)" for the rest
+    pub fn max_elements(mut self, max_elements: usize) -> DataDisplayConfig {
+        self.max_elements = Some(max_elements);
+        self
+    }
+
+    ///Vectors nested deeper than max_depth are written as "[…]", the outermost one has depth 1
+    pub fn max_depth(mut self, max_depth: usize) -> DataDisplayConfig {
+        self.max_depth = Some(max_depth);
+        self
+    }
+}
+
+///the Display of a Data cut according to a DataDisplayConfig, see Data::display_capped
+pub struct CappedData<'a> {
+    data: &'a Data,
+    config: &'a DataDisplayConfig,
+}
+
+impl Display for CappedData<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self.data {
+            Data::Vector(_) => write_vector(f, self.data, self.config),
+            data => write!(f, "{}", data),
+        }
+    }
+}
+
+///writes data with an explicit stack instead of recursion, so deeply nested Vectors
+///can not overflow the stack
+fn write_vector(f: &mut Formatter<'_>, data: &Data, config: &DataDisplayConfig) -> FmtResult {
+    //the open Vectors and the position of their next element
+    let mut stack: Vec<(&[Data], usize)> = Vec::new();
+    let mut next = Some(data);
+    loop {
+        match next.take() {
+            Some(Data::Vector(_)) if config.max_depth.is_some_and(|depth| stack.len() >= depth) => {
+                write!(f, "[…]")?
+            }
+            Some(Data::Vector(vec)) => {
                 write!(f, "[ ")?;
-                for data in vec.iter().rev().skip(1).rev() {
-                    write!(f, "{}, ", data)?;
-                }
-                if let Some(last) = vec.last() {
-                    write!(f, "{} ]", last)?;
-                }
-                Ok(())
+                stack.push((vec, 0));
             }
-            Vec2D((x, y)) => write!(f, "({} | {})", x, y),
+            Some(data) => write!(f, "{}", data)?,
+            None => {}
+        }
+
+        let Some((vec, position)) = stack.last_mut() else {
+            return Ok(());
+        };
+        let shown = config
+            .max_elements
+            .map_or(vec.len(), |max| max.min(vec.len()));
+        if *position < shown {
+            if *position > 0 {
+                write!(f, ", ")?;
+            }
+            next = Some(&vec[*position]);
+            *position += 1;
+            continue;
         }
+        if shown < vec.len() {
+            if shown > 0 {
+                write!(f, ", ")?;
+            }
+            let more = group_digits(&(vec.len() - shown).to_string(), '_');
+            write!(f, "… (+{more} more)")?;
+        }
+        if !vec.is_empty() {
+            write!(f, " ]")?;
+        }
+        stack.pop();
     }
 }
 
 impl From<String> for Data {
     fn from(string: String) -> Self {
+        Data::infer(string, &ParseRules::default())
+    }
+}
+
+impl Data {
+    ///infers the type of a single value that is not a group
+    pub(super) fn infer(string: String, rules: &ParseRules) -> Data {
         use Data::{Boolean, Date, Float, Integer, String, Vec2D};
+        if !rules.infer_types {
+            return String(Box::new(string));
+        }
         if let Ok(boolean) = bool::from_str(&string) {
             return Boolean(boolean);
         }
@@ -160,10 +367,19 @@ impl From<String> for Data {
         if let Ok(float) = f32::from_str(&string) {
             return Float(float);
         }
-        if let Ok(date) = DateTime::from_str(&string) {
-            return Date(date.into());
+        //a date with an offset is converted to UTC, the time zone a Date is written in,
+        //so the result does not depend on the time zone of this machine
+        if let Ok(date) = DateTime::<FixedOffset>::from_str(&string) {
+            return Date(SimpleDateTime::from_utc(date.with_timezone(&Utc)));
+        }
+        let is_word = |words: &[&str]| words.iter().any(|word| word.eq_ignore_ascii_case(&string));
+        if is_word(rules.truthy) {
+            return Boolean(true);
+        }
+        if is_word(rules.falsy) {
+            return Boolean(false);
         }
-        if string.contains(' ') && string.split(' ').count() == 2 {
+        if rules.vec2d && string.contains(' ') && string.split(' ').count() == 2 {
             let mut iter = string.split(' ').map(f32::from_str);
             if let (Some(Ok(x)), Some(Ok(y))) = (iter.next(), iter.next()) {
                 return Vec2D((x, y));
@@ -191,7 +407,21 @@ impl From<f32> for Data {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, Hash)]
+///gives the Data back if it is not an Integer
+impl TryFrom<Data> for i32 {
+    type Error = Data;
+
+    fn try_from(data: Data) -> Result<Self, Self::Error> {
+        match data {
+            Data::Integer(int) => Ok(int),
+            data => Err(data),
+        }
+    }
+}
+
+///serialized as an ISO 8601 string like "2023-05-04T13:05:07",
+///deserializing also accepts the struct with the six fields older versions wrote
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct SimpleDateTime {
     year: i32,
     month: u8,
@@ -232,8 +462,133 @@ impl Ord for SimpleDateTime {
     }
 }
 
+impl SimpleDateTime {
+    ///RFC 3339 with the offset of UTC, which is also how a Date is parsed,
+    ///so it reads back as the same time on any machine
+    pub(super) fn to_rfc3339(self) -> String {
+        format!("{self}Z")
+    }
+}
+
+impl Display for SimpleDateTime {
+    ///ISO 8601 without a time zone
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}",
+            self.year, self.month, self.day, self.hour, self.minute, self.second
+        )
+    }
+}
+
+impl FromStr for SimpleDateTime {
+    type Err = chrono::ParseError;
+
+    fn from_str(string: &str) -> Result<Self, Self::Err> {
+        let date_time = NaiveDateTime::parse_from_str(string, "%Y-%m-%dT%H:%M:%S")?;
+        Ok(SimpleDateTime {
+            year: date_time.year(),
+            month: date_time.month() as u8,
+            day: date_time.day() as u8,
+            hour: date_time.hour() as u8,
+            minute: date_time.minute() as u8,
+            second: date_time.second() as u8,
+        })
+    }
+}
+
+impl Serialize for SimpleDateTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for SimpleDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_any(SimpleDateTimeVisitor)
+        } else {
+            deserializer.deserialize_str(SimpleDateTimeVisitor)
+        }
+    }
+}
+
+struct SimpleDateTimeVisitor;
+
+impl<'de> Visitor<'de> for SimpleDateTimeVisitor {
+    type Value = SimpleDateTime;
+
+    fn expecting(&self, f: &mut Formatter) -> FmtResult {
+        write!(
+            f,
+            "a date like \"2023-05-04T13:05:07\" or a SimpleDateTime struct"
+        )
+    }
+
+    fn visit_str<E: de::Error>(self, string: &str) -> Result<SimpleDateTime, E> {
+        SimpleDateTime::from_str(string).map_err(E::custom)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<SimpleDateTime, A::Error> {
+        let mut fields: [Option<i32>; 6] = [None; 6];
+        const NAMES: [&str; 6] = ["year", "month", "day", "hour", "minute", "second"];
+        while let Some(key) = map.next_key::<String>()? {
+            match NAMES.iter().position(|name| *name == key) {
+                Some(position) => fields[position] = Some(map.next_value()?),
+                None => {
+                    map.next_value::<de::IgnoredAny>()?;
+                }
+            }
+        }
+        let mut values = [0; 6];
+        for (i, field) in fields.iter().enumerate() {
+            values[i] = field.ok_or_else(|| de::Error::missing_field(NAMES[i]))?;
+        }
+        let small = |i: usize| {
+            u8::try_from(values[i])
+                .map_err(|_| de::Error::custom(format!("{} out of range", NAMES[i])))
+        };
+        Ok(SimpleDateTime {
+            year: values[0],
+            month: small(1)?,
+            day: small(2)?,
+            hour: small(3)?,
+            minute: small(4)?,
+            second: small(5)?,
+        })
+    }
+}
+
+///same as SimpleDateTime::from_local
 impl From<DateTime<Local>> for SimpleDateTime {
     fn from(date_time: DateTime<Local>) -> Self {
+        SimpleDateTime::from_local(date_time)
+    }
+}
+
+impl SimpleDateTime {
+    ///the wall clock time in the time zone of this machine
+    pub fn from_local(date_time: DateTime<Local>) -> SimpleDateTime {
+        SimpleDateTime::from_wall_clock(&date_time)
+    }
+
+    ///the time in UTC, independent of the time zone of this machine
+    pub fn from_utc(date_time: DateTime<Utc>) -> SimpleDateTime {
+        SimpleDateTime::from_wall_clock(&date_time)
+    }
+
+    ///the time seconds later (or earlier if negative), across days, months and years
+    pub fn add_seconds(self, seconds: i64) -> SimpleDateTime {
+        let date_time = NaiveDate::from_ymd_opt(self.year, self.month as u32, self.day as u32)
+            .and_then(|date| {
+                date.and_hms_opt(self.hour as u32, self.minute as u32, self.second as u32)
+            })
+            .expect("a SimpleDateTime is a valid date")
+            + TimeDelta::seconds(seconds);
+        SimpleDateTime::from_wall_clock(&date_time.and_utc())
+    }
+
+    fn from_wall_clock<Tz: TimeZone>(date_time: &DateTime<Tz>) -> SimpleDateTime {
         SimpleDateTime {
             year: date_time.year(),
             month: date_time.month() as u8,
@@ -244,3 +599,182 @@ impl From<DateTime<Local>> for SimpleDateTime {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ZONES: [&str; 5] = [
+        "UTC",
+        "Europe/Berlin",
+        "America/St_Johns",
+        "Asia/Kathmandu",
+        "Pacific/Kiritimati",
+    ];
+
+    fn date(string: &str) -> SimpleDateTime {
+        string.parse().expect("the date is valid")
+    }
+
+    fn parse(string: &str) -> Data {
+        Data::parse_with(&ParseRules::default(), string)
+    }
+
+    ///the checks that must not depend on the time zone of the machine
+    fn check_dates() {
+        assert_eq!(
+            parse("2021-05-01T12:00:00+02:00"),
+            Data::Date(date("2021-05-01T10:00:00"))
+        );
+        assert_eq!(
+            parse("2021-05-01T20:00:00-07:30"),
+            Data::Date(date("2021-05-02T03:30:00"))
+        );
+        let df = crate::DataFrame::from_string("t\n2024-01-01T12:00:00+02:00\n".to_owned(), None)
+            .expect("the text is valid");
+        assert_eq!(df.to_csv_string(), "t\n2024-01-01T10:00:00Z\n");
+        assert_eq!(
+            parse("2021-12-31T23:59:59Z"),
+            Data::Date(date("2021-12-31T23:59:59"))
+        );
+        assert_eq!(
+            date("2021-12-31T23:59:30").add_seconds(45),
+            date("2022-01-01T00:00:15")
+        );
+        assert_eq!(
+            date("2024-03-01T00:00:10").add_seconds(-20),
+            date("2024-02-29T23:59:50")
+        );
+
+        //in a gap or an overlap of daylight saving time in some zone
+        for string in [
+            "2021-03-28T02:30:00",
+            "2021-10-31T02:30:00",
+            "2021-03-14T02:30:00",
+            "2021-11-07T01:30:00",
+            "1995-01-01T00:00:00",
+        ] {
+            let written = Data::Date(date(string)).to_csv_string(',');
+            assert_eq!(written, format!("{string}Z"));
+            assert_eq!(parse(&written), Data::Date(date(string)));
+        }
+
+        let utc = Utc.with_ymd_and_hms(2021, 5, 1, 22, 30, 0).unwrap();
+        assert_eq!(SimpleDateTime::from_utc(utc), date("2021-05-01T22:30:00"));
+        let offset = FixedOffset::east_opt(5 * 3600).unwrap();
+        let shifted = utc.with_timezone(&offset);
+        assert_eq!(
+            SimpleDateTime::from_wall_clock(&shifted),
+            date("2021-05-02T03:30:00")
+        );
+    }
+
+    #[test]
+    fn dates_do_not_depend_on_the_time_zone() {
+        check_dates();
+    }
+
+    ///runs dates_do_not_depend_on_the_time_zone again in a process for every zone in ZONES
+    #[test]
+    fn dates_do_not_depend_on_the_time_zone_in_other_zones() {
+        let exe = std::env::current_exe().expect("the test binary exists");
+        let name = "data_frame::data::tests::dates_do_not_depend_on_the_time_zone";
+        for zone in ZONES {
+            let status = std::process::Command::new(&exe)
+                .args([name, "--exact", "--quiet"])
+                .env("TZ", zone)
+                .stdout(std::process::Stdio::null())
+                .status()
+                .expect("the test binary runs");
+            assert!(status.success(), "failed with TZ={zone}");
+        }
+    }
+
+    fn serde_date() -> SimpleDateTime {
+        date("2023-05-04T13:05:07")
+    }
+
+    #[test]
+    fn dates_are_written_as_iso_strings() {
+        let json = serde_json::to_string(&serde_date()).expect("a date is serializable");
+        assert_eq!(json, r#""2023-05-04T13:05:07""#);
+        let json =
+            serde_json::to_string(&Data::Date(serde_date())).expect("a cell is serializable");
+        assert_eq!(json, r#"{"Date":"2023-05-04T13:05:07"}"#);
+    }
+
+    #[test]
+    fn both_forms_are_read() {
+        let from_string: SimpleDateTime =
+            serde_json::from_str(r#""2023-05-04T13:05:07""#).expect("the string form is read");
+        assert_eq!(from_string, serde_date());
+
+        let legacy = r#"{"year":2023,"month":5,"day":4,"hour":13,"minute":5,"second":7}"#;
+        let from_struct: SimpleDateTime =
+            serde_json::from_str(legacy).expect("the struct form is read");
+        assert_eq!(from_struct, serde_date());
+
+        let cell: Data = serde_json::from_str(&format!(r#"{{"Date":{legacy}}}"#))
+            .expect("the struct form is read inside a cell");
+        assert_eq!(cell, Data::Date(serde_date()));
+        let json = serde_json::to_string(&cell).expect("a cell is serializable");
+        assert_eq!(
+            serde_json::from_str::<Data>(&json).expect("the string form is read"),
+            cell
+        );
+    }
+
+    #[test]
+    fn broken_forms_are_errors() {
+        let missing = r#"{"year":2023,"month":5,"day":4,"hour":13,"minute":5}"#;
+        let err = serde_json::from_str::<SimpleDateTime>(missing).expect_err("second is missing");
+        assert!(err.to_string().contains("missing field `second`"), "{err}");
+
+        let large = r#"{"year":2023,"month":500,"day":4,"hour":13,"minute":5,"second":7}"#;
+        let err = serde_json::from_str::<SimpleDateTime>(large).expect_err("month is too large");
+        assert!(err.to_string().contains("month out of range"), "{err}");
+
+        assert!(serde_json::from_str::<SimpleDateTime>(r#""2023-05-04""#).is_err());
+    }
+
+    ///[ 1, [ 2, [ 3, ... [ depth ] ... ] ] ]
+    fn nested(depth: i32) -> Data {
+        (1..depth).rev().fold(
+            Data::Vector(Box::new(vec![Data::Integer(depth)])),
+            |inner, level| Data::Vector(Box::new(vec![Data::Integer(level), inner])),
+        )
+    }
+
+    #[test]
+    fn nesting_is_cut_at_max_depth() {
+        let data = nested(5);
+        assert_eq!(data.to_string(), "[ 1, [ 2, [ 3, [ 4, [ 5 ] ] ] ] ]");
+        let config = DataDisplayConfig::new().max_depth(5);
+        assert_eq!(data.as_string_capped(&config), data.to_string());
+        let config = DataDisplayConfig::new().max_depth(3);
+        assert_eq!(data.as_string_capped(&config), "[ 1, [ 2, [ 3, […] ] ] ]");
+        let config = DataDisplayConfig::new().max_depth(0);
+        assert_eq!(data.as_string_capped(&config), "[…]");
+        //deep nesting is written without recursion
+        let deep = nested(1_000);
+        assert_eq!(deep.as_string_capped(&config), "[…]");
+        assert_eq!(deep.to_string().matches('[').count(), 1_000);
+    }
+
+    #[test]
+    fn a_million_elements_format_in_bounded_time_and_size() {
+        let data = Data::Vector(Box::new((0..1_000_000).map(Data::Integer).collect()));
+        let config = DataDisplayConfig::new().max_elements(3);
+        let start = std::time::Instant::now();
+        let text = data.as_string_capped(&config);
+        assert!(start.elapsed() < std::time::Duration::from_millis(100));
+        assert_eq!(text, "[ 0, 1, 2, … (+999_997 more) ]");
+        assert_eq!(
+            data.display_capped(&DataDisplayConfig::new().max_elements(0))
+                .to_string(),
+            "[ … (+1_000_000 more) ]"
+        );
+        //without a config everything is written
+        assert_eq!(data.to_string().matches(", ").count(), 999_999);
+    }
+}
@@ -0,0 +1,426 @@
+use std::collections::HashSet;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use super::{BaseDataFrame, Data, DataFrame, InnerDataFrame, Line, ShapeError};
+
+///a column added by with_derived
+#[derive(Clone)]
+pub(super) struct Derivation {
+    name: String,
+    depends_on: Vec<String>,
+    f: Arc<dyn Fn(Line) -> Data + Send + Sync>,
+}
+
+impl DataFrame {
+    ///like with_computed_column but the column is registered as derived from depends_on:
+    ///materialize evaluates it again on the lines that are left and a dependency can not be
+    ///dropped while it is kept
+    ///
+    ///a derived column can only depend on columns that already exist, so the only possible
+    ///cycle is a column depending on itself, which is an error like a missing dependency
+    ///
+    ///frames copied into a new base by other operations (e.g. append_line) keep the values
+    ///but forget the registration
+    pub fn with_derived(
+        self,
+        name: &str,
+        depends_on: &[&str],
+        f: Arc<dyn Fn(Line) -> Data + Send + Sync>,
+    ) -> Result<DataFrame, ShapeError> {
+        if depends_on.contains(&name) {
+            return Err(ShapeError::DependencyCycle {
+                name: name.to_owned(),
+            });
+        }
+        let header: Vec<&str> = self.header().collect();
+        if header.contains(&name) {
+            return Err(ShapeError::DuplicateColumn {
+                name: name.to_owned(),
+            });
+        }
+        let missing: Vec<String> = depends_on
+            .iter()
+            .filter(|column| !header.contains(column))
+            .map(|column| format!("'{column}'"))
+            .collect();
+        if !missing.is_empty() {
+            return Err(ShapeError::MissingColumns { columns: missing });
+        }
+
+        let depends_on = depends_on.iter().map(|column| column.to_string()).collect();
+        Ok(self.push_computed(name, f, Some(depends_on)))
+    }
+
+    ///evaluates the derived column name and every derived column depending on it again
+    ///(lazily like with_computed_column), panics if name is not a derived column
+    pub fn invalidate_derived(self, name: &str) -> DataFrame {
+        let derivations = self.derivations();
+        assert!(
+            derivations.iter().any(|derivation| derivation.name == name),
+            "'{name}' is not a derived column"
+        );
+        let mut invalid = HashSet::from([name.to_owned()]);
+        let stale = derivations
+            .into_iter()
+            .filter(|derivation| {
+                let stale = derivation.name == name
+                    || derivation
+                        .depends_on
+                        .iter()
+                        .any(|column| invalid.contains(column));
+                if stale {
+                    invalid.insert(derivation.name.clone());
+                }
+                stale
+            })
+            .collect();
+        self.rederive(stale, false)
+    }
+
+    ///the derived columns of the header in the order they were added (so every column comes
+    ///after the ones it depends on)
+    pub(super) fn derivations(&self) -> Vec<Derivation> {
+        let mut derivations = Vec::new();
+        self.collect_derivations(&mut derivations);
+
+        let header: HashSet<&str> = self.header().collect();
+        let mut seen = HashSet::new();
+        //a later registration of a name hides an earlier one whose column was dropped
+        let mut derivations: Vec<Derivation> = derivations
+            .into_iter()
+            .rev()
+            .filter(|derivation| {
+                header.contains(derivation.name.as_str()) && seen.insert(derivation.name.clone())
+            })
+            .collect();
+        derivations.reverse();
+        derivations
+    }
+
+    fn collect_derivations(&self, derivations: &mut Vec<Derivation>) {
+        match self.inner.deref() {
+            InnerDataFrame::Base { .. } => {}
+            InnerDataFrame::ColumnReorder { df, .. } | InnerDataFrame::LineReorder { df, .. } => {
+                df.collect_derivations(derivations)
+            }
+            InnerDataFrame::Computed {
+                df,
+                header,
+                f,
+                depends_on,
+                ..
+            } => {
+                df.collect_derivations(derivations);
+                if let Some(depends_on) = depends_on {
+                    derivations.push(Derivation {
+                        name: header
+                            .last()
+                            .expect("a computed frame has a column")
+                            .clone(),
+                        depends_on: depends_on.clone(),
+                        f: f.clone(),
+                    });
+                }
+            }
+            InnerDataFrame::Concat { parts, .. } => parts[0].collect_derivations(derivations),
+        }
+    }
+
+    ///an error if the columns at index_map keep a derived column but not one it depends on
+    pub(super) fn check_dependencies(&self, index_map: &[usize]) -> Result<(), ShapeError> {
+        let derivations = self.derivations();
+        if derivations.is_empty() {
+            return Ok(());
+        }
+        let header: Vec<&str> = self.header().collect();
+        let kept: HashSet<&str> = index_map.iter().map(|index| header[*index]).collect();
+        for derivation in derivations.iter() {
+            if !kept.contains(derivation.name.as_str()) {
+                continue;
+            }
+            if let Some(column) = derivation
+                .depends_on
+                .iter()
+                .find(|column| !kept.contains(column.as_str()))
+            {
+                return Err(ShapeError::DroppedDependency {
+                    column: column.clone(),
+                    dependent: derivation.name.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    ///drops the columns of derivations and adds them again behind the others,
+    ///with copy all derived columns are left out of the frame that is copied into a base frame
+    ///(so their stale cells are not evaluated) and all lines are evaluated
+    pub(super) fn rederive(self, derivations: Vec<Derivation>, copy: bool) -> DataFrame {
+        let df = if copy { self.without_derived().0 } else { self };
+        let header: Vec<String> = df.header().map(|string| string.to_owned()).collect();
+        let keep: Vec<usize> = (0..header.len())
+            .filter(|index| {
+                !derivations
+                    .iter()
+                    .any(|derivation| derivation.name == header[*index])
+            })
+            .collect();
+
+        let mut df = if keep.len() == header.len() {
+            df
+        } else {
+            df.reorder_columns(keep)
+        };
+        if copy {
+            df = InnerDataFrame::Base {
+                df: BaseDataFrame::from(df),
+            }
+            .into();
+        }
+        let first_derived = df.num_columns();
+        for derivation in derivations {
+            df = df.push_computed(&derivation.name, derivation.f, Some(derivation.depends_on));
+        }
+        if copy {
+            //the cells are computed on first access
+            for line in df.iter() {
+                for index in first_derived..line.len() {
+                    let _ = &line[index];
+                }
+            }
+        }
+        df
+    }
+
+    ///the views of self rebuilt without the layers of derived columns and where each column
+    ///of self went (None if it was left out). a column computed by with_computed_column
+    ///above a derived one sees its lines, so the frame below it is kept as it is
+    fn without_derived(&self) -> (DataFrame, Vec<Option<usize>>) {
+        let unchanged = || (self.clone(), (0..self.num_columns()).map(Some).collect());
+        match self.inner.deref() {
+            InnerDataFrame::Base { .. } => unchanged(),
+            InnerDataFrame::LineReorder { df, index_map } => {
+                let (df, columns) = df.without_derived();
+                let index_map = index_map.clone();
+                (
+                    InnerDataFrame::LineReorder { df, index_map }.into(),
+                    columns,
+                )
+            }
+            InnerDataFrame::ColumnReorder { df, index_map, .. } => {
+                let (df, inner_columns) = df.without_derived();
+                let keep: Vec<usize> = index_map
+                    .iter()
+                    .filter_map(|index| inner_columns[*index])
+                    .collect();
+                let mut kept = 0..;
+                let columns = index_map
+                    .iter()
+                    .map(|index| inner_columns[*index].and_then(|_| kept.next()))
+                    .collect();
+                (df.reorder_columns(keep), columns)
+            }
+            InnerDataFrame::Computed {
+                df,
+                header,
+                f,
+                depends_on,
+                ..
+            } => {
+                let (inner, mut columns) = df.without_derived();
+                match depends_on {
+                    Some(_) => {
+                        columns.push(None);
+                        (inner, columns)
+                    }
+                    None if columns.iter().all(Option::is_some) => {
+                        columns.push(Some(columns.len()));
+                        let name = header.last().expect("a computed frame has a column");
+                        (inner.push_computed(name, f.clone(), None), columns)
+                    }
+                    None => unchanged(),
+                }
+            }
+            InnerDataFrame::Concat { parts, .. } => {
+                let mut stripped = parts.iter().map(DataFrame::without_derived);
+                let (first, columns) = stripped.next().expect("a concatenation has a part");
+                let mut df = first;
+                for (part, part_columns) in stripped {
+                    if part_columns != columns {
+                        return unchanged();
+                    }
+                    df = df.concat(part);
+                }
+                (df, columns)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+
+    fn positions() -> DataFrame {
+        let rows = (0..10)
+            .map(|line| {
+                vec![
+                    Data::Integer(line),
+                    Data::Float(line as f32 * line as f32),
+                    Data::Integer(line % 3),
+                ]
+            })
+            .collect();
+        DataFrame::from_rows(vec!["time", "position", "lane"], rows)
+            .expect("the rows fit the header")
+    }
+
+    fn cells(df: &DataFrame, name: &str) -> Vec<Data> {
+        df.iter().map(|line| line[name].clone()).collect()
+    }
+
+    ///position per time, counting the evaluations in calls
+    fn with_speed(df: DataFrame, calls: &Arc<AtomicUsize>) -> DataFrame {
+        let calls = calls.clone();
+        let speed = Arc::new(move |line: Line| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            line["position"]
+                .try_div(&line["time"])
+                .unwrap_or(Data::Null)
+        });
+        df.with_derived("speed", &["position", "time"], speed)
+            .expect("the dependencies exist")
+    }
+
+    #[test]
+    fn materialize_evaluates_the_filtered_lines_only() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = with_speed(positions(), &calls);
+        //every cell of speed is evaluated once
+        assert_eq!(cells(&df, "speed").len(), 10);
+        let df = df
+            .filter(|line| line["lane"] == Data::Integer(1))
+            .drop_column("lane");
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 10);
+
+        let df = df.materialize();
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 3);
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            vec!["time", "position", "speed"]
+        );
+        assert_eq!(
+            cells(&df, "speed"),
+            vec![Data::Float(1.0), Data::Float(4.0), Data::Float(7.0)]
+        );
+        assert_eq!(df.derivations().len(), 1);
+
+        //the stale cells of lines that were never read are not evaluated
+        let df = with_speed(positions(), &calls).tail(3).materialize();
+        assert_eq!(calls.swap(0, Ordering::SeqCst), 3);
+        assert_eq!(cells(&df, "speed")[0], Data::Float(7.0));
+    }
+
+    #[test]
+    fn materialize_keeps_computed_columns_and_concatenations() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = with_speed(positions(), &calls);
+        let (first, second) = (df.clone().head(4), df.range(4, 10));
+        let df = second
+            .concat(first)
+            .with_computed_column("lane_name", Arc::new(|line| line["lane"].clone()))
+            .sort_by_column("time", crate::SortOptions::descending())
+            .head(5);
+        //the cells of speed are not read by the sort
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+
+        let df = df.materialize();
+        assert_eq!(calls.load(Ordering::SeqCst), 5);
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            vec!["time", "position", "lane", "lane_name", "speed"]
+        );
+        assert_eq!(cells(&df, "lane_name"), cells(&df, "lane"));
+        assert_eq!(cells(&df, "speed")[0], Data::Float(9.0));
+    }
+
+    #[test]
+    fn a_cycle_is_rejected() {
+        let f: Arc<dyn Fn(Line) -> Data + Send + Sync> = Arc::new(|_line| Data::Null);
+        let Err(err) = positions().with_derived("speed", &["time", "speed"], f.clone()) else {
+            panic!("speed can not depend on itself");
+        };
+        assert_eq!(
+            err,
+            ShapeError::DependencyCycle {
+                name: "speed".to_owned()
+            }
+        );
+        let Err(err) = positions().with_derived("speed", &["distance"], f) else {
+            panic!("distance does not exist");
+        };
+        assert!(matches!(err, ShapeError::MissingColumns { .. }));
+    }
+
+    #[test]
+    fn select_rejects_dropping_a_dependency() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let Err(err) = with_speed(positions(), &calls).select(&["time", "speed"], |_line| true)
+        else {
+            panic!("speed depends on position");
+        };
+        assert_eq!(
+            err,
+            ShapeError::DroppedDependency {
+                column: "position".to_owned(),
+                dependent: "speed".to_owned(),
+            }
+        );
+        assert!(with_speed(positions(), &calls)
+            .select(&["time", "position", "speed"], |_line| true)
+            .is_ok());
+        let df = with_speed(positions(), &calls).drop_columns(&["speed", "position"]);
+        assert_eq!(df.header().collect::<Vec<_>>(), vec!["time", "lane"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot drop 'time' since the derived column 'speed' depends on it")]
+    fn drop_column_rejects_dropping_a_dependency() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let _ = with_speed(positions(), &calls).drop_column("time");
+    }
+
+    #[test]
+    #[should_panic(
+        expected = "cannot drop 'position' since the derived column 'speed' depends on it"
+    )]
+    fn drop_all_column_except_rejects_dropping_a_dependency() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let _ = with_speed(positions(), &calls).drop_all_column_except(&["speed", "time"]);
+    }
+
+    #[test]
+    fn invalidate_derived_evaluates_the_dependents_again() {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let df = with_speed(positions(), &calls)
+            .with_derived(
+                "fast",
+                &["speed"],
+                Arc::new(|line| Data::Boolean(line["speed"].as_float() > 4.0)),
+            )
+            .expect("speed exists");
+        assert_eq!(cells(&df, "fast")[5], Data::Boolean(true));
+        assert_eq!(calls.load(Ordering::SeqCst), 10);
+
+        let df = df.invalidate_derived("speed");
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            vec!["time", "position", "lane", "speed", "fast"]
+        );
+        assert_eq!(cells(&df, "fast")[5], Data::Boolean(true));
+        assert_eq!(calls.load(Ordering::SeqCst), 20);
+    }
+}
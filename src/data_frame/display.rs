@@ -1,20 +1,280 @@
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::fmt::{Display, Formatter, Result as FmtResult, Write};
+use std::ops::Range;
 
-use super::DataFrame;
+use super::{Data, DataDisplayConfig, DataFrame, DataFrameColumnIndex};
 
-impl Display for DataFrame {
-    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
-        let lines = self.iter().enumerate();
-        let mut print_table = vec![vec!["#".into()]];
+const PREVIEW_CELL_WIDTH: usize = 40;
+///longer cells are always cut in tables, a misparsed line can put a whole file into one cell
+const MAX_CELL_CHARS: usize = 10_000;
+const MAX_VECTOR_ELEMENTS: usize = 50;
+const MAX_VECTOR_DEPTH: usize = 3;
+
+impl DataFrame {
+    ///an owned copy of the first lines and columns with long strings cut,
+    ///it shares no data with self so it does not keep a large frame alive
+    pub fn preview(&self, max_lines: usize, max_columns: usize) -> DataFrame {
+        let header: Vec<_> = self.header().take(max_columns).collect();
+        let data = self.iter().take(max_lines).map(|line| {
+            line.iter()
+                .take(max_columns)
+                .map(|data| match data {
+                    Data::String(string) if string.chars().nth(PREVIEW_CELL_WIDTH).is_some() => {
+                        let mut cut: String = string.chars().take(PREVIEW_CELL_WIDTH - 1).collect();
+                        cut.push('…');
+                        Data::String(Box::new(cut))
+                    }
+                    data => data.clone(),
+                })
+                .collect()
+        });
+        DataFrame::new(header).append_lines(data)
+    }
+}
+
+///which columns DataFrame::display_with shows and how wide the table may get
+#[derive(Debug, Clone, Default)]
+pub struct DisplayOptions {
+    columns: Option<Vec<String>>,
+    frozen: Option<String>,
+    max_width: Option<usize>,
+    thousands_separator: Option<char>,
+    si_suffix: bool,
+    vectors: Option<DataDisplayConfig>,
+}
+
+impl DisplayOptions {
+    pub fn new() -> DisplayOptions {
+        DisplayOptions::default()
+    }
+
+    ///only these columns are shown, in this order
+    pub fn columns(mut self, columns: &[&str]) -> DisplayOptions {
+        self.columns = Some(columns.iter().map(|column| column.to_string()).collect());
+        self
+    }
+
+    ///this column is always shown left of all others, even if max_width drops columns
+    pub fn freeze_first(mut self, column: &str) -> DisplayOptions {
+        self.frozen = Some(column.to_owned());
+        self
+    }
+
+    ///trailing columns are dropped until every line of the table fits into width chars
+    pub fn max_width(mut self, width: usize) -> DisplayOptions {
+        self.max_width = Some(width);
+        self
+    }
+
+    ///groups the digits of Integers and of the whole part of Floats of at least 1000
+    ///(and of the line count below an empty table) like 1_234_567
+    pub fn thousands_separator(mut self, separator: char) -> DisplayOptions {
+        self.thousands_separator = Some(separator);
+        self
+    }
+
+    ///numbers of at least 1000 are shown with three digits and a suffix like 1.23M,
+    ///this wins over thousands_separator
+    pub fn si_suffix(mut self, si_suffix: bool) -> DisplayOptions {
+        self.si_suffix = si_suffix;
+        self
+    }
+
+    ///how much of Vector cells is formatted,
+    ///by default 50 elements and 3 levels of nesting
+    pub fn vectors(mut self, config: DataDisplayConfig) -> DisplayOptions {
+        self.vectors = Some(config);
+        self
+    }
+
+    ///the text of an Integer or Float cell, None for every other cell or if no option is set
+    fn format_number(&self, data: &Data) -> Option<String> {
+        let value = match data {
+            Data::Integer(int) => *int as f64,
+            Data::Float(float) if float.is_finite() => *float as f64,
+            _ => return None,
+        };
+        if self.si_suffix && value.abs() >= 1000.0 {
+            return Some(si_text(value));
+        }
+        let separator = self.thousands_separator?;
+        match data {
+            Data::Integer(int) => Some(group_digits(&int.to_string(), separator)),
+            Data::Float(float) if float.abs() >= 1000.0 => {
+                Some(group_digits(&float.to_string(), separator))
+            }
+            _ => None,
+        }
+    }
+}
+
+///inserts separator between every three digits of the whole part of a number as Display writes it
+pub(super) fn group_digits(text: &str, separator: char) -> String {
+    let (sign, unsigned) = match text.strip_prefix('-') {
+        Some(unsigned) => ("-", unsigned),
+        None => ("", text),
+    };
+    let (whole, fraction) = match unsigned.find('.') {
+        Some(dot) => unsigned.split_at(dot),
+        None => (unsigned, ""),
+    };
+    let mut grouped = String::from(sign);
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(separator);
+        }
+        grouped.push(digit);
+    }
+    grouped.push_str(fraction);
+    grouped
+}
+
+///value (at least 1000 in magnitude) with three significant digits and k, M, G, T, P or E
+fn si_text(value: f64) -> String {
+    const SUFFIXES: [&str; 6] = ["k", "M", "G", "T", "P", "E"];
+    let mut exponent = ((value.abs().log10() / 3.0).floor() as usize).clamp(1, SUFFIXES.len());
+    loop {
+        let scaled = value / 1000f64.powi(exponent as i32);
+        let decimals = if scaled.abs() < 10.0 {
+            2
+        } else if scaled.abs() < 100.0 {
+            1
+        } else {
+            0
+        };
+        let text = format!("{scaled:.decimals$}");
+        //rounding can reach the next suffix, 999_999 is 1.00M and not 1000k
+        if text.trim_start_matches('-').len() > 3 + (decimals > 0) as usize + decimals
+            && exponent < SUFFIXES.len()
+        {
+            exponent += 1;
+            continue;
+        }
+        return format!("{text}{}", SUFFIXES[exponent - 1]);
+    }
+}
 
-        for head_elem in self.header() {
-            print_table.push(vec![format!("{head_elem}")]);
+impl DataFrame {
+    pub fn display_with(&self, options: &DisplayOptions) -> String {
+        let mut string = String::new();
+        self.write_table(&mut string, options, 0..self.len())
+            .expect("writing to a String does not fail");
+        string
+    }
+
+    ///prints the first n lines as a table to stdout, self is only borrowed
+    ///
+    ///```
+    ///use rundas::prelude::*;
+    ///
+    ///let csv = "sensor,value\na,1.5\nb,2\nc,\n".to_owned();
+    ///let df = DataFrame::from_string(csv, None).expect("the csv is valid");
+    ///df.print_head(2);
+    ///df.print_tail(1);
+    /////the frame is still there
+    ///assert_eq!(df.len(), 3);
+    ///```
+    pub fn print_head(&self, n: usize) {
+        self.print_lines(0..n.min(self.len()));
+    }
+
+    ///prints the last n lines as a table to stdout, numbered by their position in self
+    pub fn print_tail(&self, n: usize) {
+        self.print_lines(self.len().saturating_sub(n)..self.len());
+    }
+
+    fn print_lines(&self, lines: Range<usize>) {
+        let mut string = String::new();
+        self.write_table(&mut string, &DisplayOptions::default(), lines)
+            .expect("writing to a String does not fail");
+        print!("{string}");
+    }
+
+    ///one line per column with its name, the most common type of its non Null cells
+    ///(Null if there is none), the number of Null cells and the first three other cells
+    pub fn schema(&self) -> DataFrame {
+        let data = self.header().enumerate().map(|(index, name)| {
+            let examples: Vec<String> = self
+                .column(index)
+                .filter(|data| !data.is_null())
+                .take(3)
+                .map(|data| data.to_string())
+                .collect();
+            vec![
+                Data::String(Box::new(name.to_owned())),
+                Data::String(Box::new(
+                    self.dominant_type(index).unwrap_or("Null").to_owned(),
+                )),
+                Data::Integer(self.column(index).filter(|data| data.is_null()).count() as i32),
+                Data::String(Box::new(examples.join(", "))),
+            ]
+        });
+        DataFrame::new(vec!["column", "type", "nulls", "examples"]).append_lines(data)
+    }
+
+    ///prints schema as a table to stdout
+    ///
+    ///```
+    ///use rundas::prelude::*;
+    ///
+    ///let csv = "sensor,value\na,1.5\nb,2\nc,\n".to_owned();
+    ///let df = DataFrame::from_string(csv, None).expect("the csv is valid");
+    ///df.print_schema();
+    ///
+    ///let schema = df.schema();
+    ///let line = schema.get(1).expect("the frame has two columns");
+    ///assert_eq!(line["column"].as_string(), "value");
+    ///assert_eq!(line["nulls"], Data::Integer(1));
+    ///assert_eq!(line["examples"].as_string(), "1.5, 2");
+    ///```
+    pub fn print_schema(&self) {
+        print!("{}", self.schema());
+    }
+
+    ///writes the lines in the range numbered by their position in self,
+    ///the number of lines left out is stated below the table
+    fn write_table(
+        &self,
+        f: &mut impl Write,
+        options: &DisplayOptions,
+        lines: Range<usize>,
+    ) -> FmtResult {
+        let mut columns: Vec<usize> = match &options.columns {
+            Some(columns) => columns
+                .iter()
+                .map(|column| column.as_str().get_usize(self.header()))
+                .collect(),
+            None => (0..self.num_columns()).collect(),
+        };
+        let frozen = options.frozen.as_ref().map(|column| {
+            let frozen = column.as_str().get_usize(self.header());
+            columns.retain(|index| *index != frozen);
+            columns.insert(0, frozen);
+            frozen
+        });
+
+        let vectors = options.vectors.unwrap_or_else(|| {
+            DataDisplayConfig::new()
+                .max_elements(MAX_VECTOR_ELEMENTS)
+                .max_depth(MAX_VECTOR_DEPTH)
+        });
+        let mut print_table = vec![vec!["#".into()]];
+        let header: Vec<&str> = self.header().collect();
+        for index in columns.iter() {
+            print_table.push(vec![capped(header[*index])]);
         }
 
-        for (line_number, line) in lines {
+        let omitted = self.len() - lines.len();
+        for line_number in lines {
+            let line = self.get(line_number).expect("the range is inside of self");
             print_table[0].push(format!("{line_number}"));
-            for (i, elem) in line.iter().enumerate() {
-                print_table[i + 1].push(format!("{elem}"));
+            for (i, index) in columns.iter().enumerate() {
+                let cell = match &line[*index] {
+                    Data::String(string) => capped(string),
+                    data => options
+                        .format_number(data)
+                        .unwrap_or_else(|| capped(&data.as_string_capped(&vectors))),
+                };
+                print_table[i + 1].push(cell);
             }
         }
 
@@ -28,8 +288,23 @@ impl Display for DataFrame {
             );
         }
 
+        //keep columns from the left as long as they fit, the frozen column is always kept
+        let mut shown = print_table.len();
+        if let Some(budget) = options.max_width {
+            let mut used = max_width[0] + 2;
+            shown = 1;
+            for (row_index, width) in max_width.iter().enumerate().skip(1) {
+                let is_frozen = row_index == 1 && frozen.is_some();
+                if used + width + 2 > budget && !is_frozen {
+                    break;
+                }
+                used += width + 2;
+                shown += 1;
+            }
+        }
+
         for line_index in 0..print_table[0].len() {
-            for (row_index, row) in print_table.iter().enumerate() {
+            for (row_index, row) in print_table.iter().take(shown).enumerate() {
                 write!(
                     f,
                     "{:<width$}",
@@ -40,6 +315,357 @@ impl Display for DataFrame {
             writeln!(f)?;
         }
 
+        if shown < print_table.len() {
+            writeln!(f, "… (+{} more columns)", print_table.len() - shown)?;
+        }
+        if omitted > 0 {
+            writeln!(f, "… (+{omitted} more lines)")?;
+        }
+
+        //a table without lines or columns is easy to misread so state its shape
+        if self.is_empty() || self.num_columns() == 0 {
+            let lines = match options.thousands_separator {
+                Some(separator) => group_digits(&self.len().to_string(), separator),
+                None => self.len().to_string(),
+            };
+            writeln!(f, "[{lines} lines x {} columns]", self.num_columns())?;
+        }
+
         Ok(())
     }
 }
+
+///the text cut after MAX_CELL_CHARS chars with the size of the rest, e.g. "…[+1.9MB]"
+fn capped(text: &str) -> String {
+    match text.char_indices().nth(MAX_CELL_CHARS) {
+        None => text.to_owned(),
+        Some((end, _char)) => {
+            let rest = (text.len() - end) as f64;
+            let size = if rest >= 1e6 {
+                format!("{:.1}MB", rest / 1e6)
+            } else if rest >= 1e3 {
+                format!("{:.1}kB", rest / 1e3)
+            } else {
+                format!("{rest}B")
+            };
+            format!("{}…[+{size}]", &text[..end])
+        }
+    }
+}
+
+impl DataFrame {
+    ///logs label, shape, header and the first 3 lines (to tracing with the feature, else stderr)
+    ///and returns self, so it can sit in the middle of a chain
+    pub fn peek(self, label: &str) -> DataFrame {
+        let message = self.peek_message(label);
+        #[cfg(feature = "tracing")]
+        tracing::info!("{message}");
+        #[cfg(not(feature = "tracing"))]
+        eprintln!("{message}");
+        self
+    }
+
+    ///calls f with self and returns self
+    pub fn peek_with<F>(self, f: F) -> DataFrame
+    where
+        F: FnOnce(&DataFrame),
+    {
+        f(&self);
+        self
+    }
+
+    fn peek_message(&self, label: &str) -> String {
+        let header: Vec<&str> = self.header().collect();
+        format!(
+            "{label}: [{} lines x {} columns] {header:?}\n{}",
+            self.len(),
+            self.num_columns(),
+            self.preview(3, self.num_columns())
+        )
+    }
+}
+
+impl Display for DataFrame {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        self.write_table(f, &DisplayOptions::default(), 0..self.len())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use super::*;
+
+    fn frame(lines: usize) -> DataFrame {
+        let rows = (0..lines as i32).map(|line| {
+            vec![
+                Data::Integer(line),
+                Data::String(Box::new("x".repeat(100))),
+                Data::Float(line as f32),
+            ]
+        });
+        DataFrame::new(vec!["id", "text", "value"]).append_lines(rows)
+    }
+
+    #[test]
+    fn preview_does_not_retain_the_source() {
+        let source = frame(1000);
+        let view = source.clone().filter(|line| line[0] != Data::Integer(3));
+        assert_eq!(Arc::strong_count(&source.inner), 2);
+
+        let preview = view.preview(5, 2);
+        assert_eq!(Arc::strong_count(&source.inner), 2);
+        assert_eq!(Arc::strong_count(&preview.inner), 1);
+
+        let weak = Arc::downgrade(&source.inner);
+        drop(view);
+        drop(source);
+        assert!(weak.upgrade().is_none());
+        assert_eq!(preview.shape(), (5, 2));
+    }
+
+    #[test]
+    fn preview_cuts_long_strings() {
+        let preview = frame(3).preview(10, 3);
+        assert_eq!(preview.shape(), (3, 3));
+        let Data::String(text) = &preview.get(0).expect("the preview has lines")[1] else {
+            panic!("the text column holds strings");
+        };
+        assert_eq!(text.chars().count(), PREVIEW_CELL_WIDTH);
+        assert!(text.ends_with('…'));
+    }
+
+    ///an id column followed by 20 columns c0 to c19 and a speed column
+    fn wide_frame() -> DataFrame {
+        let mut header = vec!["id".to_owned()];
+        header.extend((0..20).map(|column| format!("c{column}")));
+        header.push("speed".to_owned());
+        let rows = (0..3).map(|line| {
+            let mut row = vec![Data::String(Box::new(format!("key-{line}")))];
+            row.extend((0..20).map(|column| Data::Integer(line * 1000 + column)));
+            row.push(Data::Float(line as f32 * 1.5));
+            row
+        });
+        DataFrame::new(header).append_lines(rows)
+    }
+
+    fn header_line(table: &str) -> Vec<&str> {
+        table
+            .lines()
+            .next()
+            .expect("the table has a header")
+            .split_whitespace()
+            .collect()
+    }
+
+    #[test]
+    fn column_subset_is_shown_in_the_given_order() {
+        let table = wide_frame().display_with(&DisplayOptions::new().columns(&["speed", "id"]));
+        assert_eq!(header_line(&table), vec!["#", "speed", "id"]);
+        assert_eq!(table.lines().count(), 4);
+        assert!(!table.contains("more columns"));
+    }
+
+    #[test]
+    fn table_fits_into_the_width_budget() {
+        for budget in [20, 40, 80] {
+            let table = wide_frame().display_with(&DisplayOptions::new().max_width(budget));
+            for line in table.lines() {
+                assert!(
+                    line.chars().count() <= budget,
+                    "{line:?} is wider than {budget}"
+                );
+            }
+            let shown = header_line(&table).len() - 1;
+            let note = table.lines().last().expect("the table has lines");
+            assert_eq!(note, format!("… (+{} more columns)", 22 - shown));
+        }
+    }
+
+    #[test]
+    fn frozen_column_is_kept_left_when_columns_are_dropped() {
+        let options = DisplayOptions::new().freeze_first("speed").max_width(40);
+        let table = wide_frame().display_with(&options);
+        let header = header_line(&table);
+        assert_eq!(header[..3], ["#", "speed", "id"]);
+        for line in table.lines() {
+            assert!(line.chars().count() <= 40, "{line:?} is wider than 40");
+        }
+        assert!(table.ends_with("more columns)\n"));
+    }
+
+    #[test]
+    fn enormous_cells_are_cut() {
+        //1.2MB of two byte chars, the cut must not split one of them
+        let huge = "é".repeat(600_000);
+        let df = DataFrame::new(vec!["id", "swallowed"])
+            .append_lines([vec![Data::Integer(1), Data::String(Box::new(huge))]].into_iter());
+
+        let start = Instant::now();
+        let tables = [df.to_string(), df.display_with(&DisplayOptions::new())];
+        assert!(start.elapsed() < Duration::from_secs(1));
+        for table in tables {
+            //the header line is padded to the width of the cut cell
+            let chars = table.chars().count();
+            assert!(chars < 3 * MAX_CELL_CHARS, "{chars} chars");
+            let cell = format!("{}…[+1.2MB]", "é".repeat(MAX_CELL_CHARS));
+            assert!(table.contains(&cell));
+        }
+
+        assert_eq!(capped("short"), "short");
+        let exact = "x".repeat(MAX_CELL_CHARS);
+        assert_eq!(capped(&exact), exact);
+        let over = "x".repeat(MAX_CELL_CHARS + 1500);
+        assert_eq!(capped(&over), format!("{exact}…[+1.5kB]"));
+    }
+
+    ///collects the messages of all events
+    #[cfg(feature = "tracing")]
+    #[derive(Default)]
+    struct MockLogger {
+        messages: std::sync::Mutex<Vec<String>>,
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::field::Visit for &MockLogger {
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "message" {
+                let mut messages = self.messages.lock().expect("the logger is not poisoned");
+                messages.push(format!("{value:?}"));
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    impl tracing::Subscriber for MockLogger {
+        fn enabled(&self, _: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, _: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+
+        fn record(&self, _: &tracing::span::Id, _: &tracing::span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &tracing::span::Id, _: &tracing::span::Id) {}
+
+        fn event(&self, event: &tracing::Event<'_>) {
+            event.record(&mut &*self);
+        }
+
+        fn enter(&self, _: &tracing::span::Id) {}
+
+        fn exit(&self, _: &tracing::span::Id) {}
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn peek_logs_the_shape_and_returns_the_frame() {
+        let logger = Arc::new(MockLogger::default());
+        let df = tracing::subscriber::with_default(logger.clone(), || {
+            frame(10)
+                .filter(|line| line[0] != Data::Integer(3))
+                .peek("after filter")
+        });
+        assert_eq!(df.shape(), (9, 3));
+        let messages = logger.messages.lock().expect("the logger is not poisoned");
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0]
+            .starts_with("after filter: [9 lines x 3 columns] [\"id\", \"text\", \"value\"]"));
+        assert_eq!(messages[0].lines().count(), 5);
+    }
+
+    #[test]
+    fn peek_with_sees_the_frame() {
+        let mut seen = None;
+        let df = frame(4).peek_with(|df| seen = Some(df.shape()));
+        assert_eq!(seen, Some((4, 3)));
+        assert_eq!(df.shape(), (4, 3));
+    }
+
+    #[test]
+    fn numbers_are_grouped_and_suffixed() {
+        assert_eq!(group_digits("1234567", '_'), "1_234_567");
+        assert_eq!(group_digits("-123456", ','), "-123,456");
+        assert_eq!(group_digits("999", '_'), "999");
+        assert_eq!(group_digits("-1234.5", '_'), "-1_234.5");
+
+        assert_eq!(si_text(1234567.0), "1.23M");
+        assert_eq!(si_text(-1234.0), "-1.23k");
+        assert_eq!(si_text(45_600.0), "45.6k");
+        assert_eq!(si_text(999_999.0), "1.00M");
+        assert_eq!(si_text(-2_147_483_648.0), "-2.15G");
+
+        let grouped = DisplayOptions::new().thousands_separator('_');
+        assert_eq!(
+            grouped.format_number(&Data::Integer(-1234567)),
+            Some("-1_234_567".to_owned())
+        );
+        assert_eq!(
+            grouped.format_number(&Data::Integer(12)),
+            Some("12".to_owned())
+        );
+        assert_eq!(
+            grouped.format_number(&Data::Float(12345.5)),
+            Some("12_345.5".to_owned())
+        );
+        assert_eq!(grouped.format_number(&Data::Float(999.5)), None);
+        assert_eq!(grouped.format_number(&Data::Float(f32::NAN)), None);
+        let si = grouped.si_suffix(true);
+        assert_eq!(
+            si.format_number(&Data::Integer(1234567)),
+            Some("1.23M".to_owned())
+        );
+        assert_eq!(
+            si.format_number(&Data::Integer(-999)),
+            Some("-999".to_owned())
+        );
+    }
+
+    #[test]
+    fn formatted_numbers_set_the_column_width() {
+        let rows = [1234567, -98765, 42]
+            .into_iter()
+            .map(|int| vec![Data::Integer(int)]);
+        let df = DataFrame::new(vec!["n"]).append_lines(rows);
+        let table = df.display_with(&DisplayOptions::new().thousands_separator(','));
+        assert!(table.contains("1,234,567"));
+        assert!(table.contains("-98,765"));
+        assert!(!table.contains("1234567"));
+        let widths: Vec<usize> = table.lines().map(|line| line.chars().count()).collect();
+        assert!(widths.windows(2).all(|pair| pair[0] == pair[1]), "{table}");
+
+        let table = df.display_with(&DisplayOptions::new().si_suffix(true));
+        assert!(table.contains("1.23M"));
+        assert!(table.contains("-98.8k"));
+        //machine writers are never formatted
+        assert!(df.to_csv_string().contains("1234567"));
+
+        let empty = DataFrame::new(vec!["n"]);
+        let table = empty.display_with(&DisplayOptions::new().thousands_separator('_'));
+        assert!(table.contains("[0 lines x 1 columns]"));
+        let no_columns =
+            DataFrame::new(Vec::<&str>::new()).append_lines((0..1234).map(|_line| Vec::new()));
+        let table = no_columns.display_with(&DisplayOptions::new().thousands_separator('_'));
+        assert!(table.contains("[1_234 lines x 0 columns]"), "{table}");
+    }
+
+    #[test]
+    fn huge_vectors_are_cut_in_tables() {
+        let vector = Data::Vector(Box::new((0..1_000_000).map(Data::Integer).collect()));
+        let df = DataFrame::new(vec!["v"]).append_lines(std::iter::once(vec![vector]));
+        let table = df.to_string();
+        assert!(table.len() < 1000, "{} bytes", table.len());
+        assert!(table.contains("49, … (+999_950 more) ]"));
+
+        let options = DisplayOptions::new().vectors(DataDisplayConfig::new().max_elements(2));
+        assert!(df
+            .display_with(&options)
+            .contains("[ 0, 1, … (+999_998 more) ]"));
+        //writers are never cut
+        assert!(df.to_csv_string().len() > 1_000_000);
+    }
+}
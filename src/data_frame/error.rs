@@ -0,0 +1,401 @@
+use std::error::Error;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::io::Error as IoError;
+
+use super::Data;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ShapeError {
+    LineLength {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+    NotAVector {
+        line: usize,
+        found: Data,
+    },
+    SplitParts {
+        line: usize,
+        expected: usize,
+        found: usize,
+        value: Data,
+    },
+    ColumnLength {
+        expected: usize,
+        found: usize,
+    },
+    DuplicateColumn {
+        name: String,
+    },
+    ///the header does not contain these columns
+    MissingColumns {
+        columns: Vec<String>,
+    },
+    ///a derived column would depend on itself
+    DependencyCycle {
+        name: String,
+    },
+    ///a column would be dropped while the derived column dependent is kept
+    DroppedDependency {
+        column: String,
+        dependent: String,
+    },
+    ///two lines fall into the same cell of a pivot
+    DuplicateCell {
+        line: usize,
+        row: Data,
+        column: String,
+    },
+}
+
+impl Display for ShapeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ShapeError::LineLength {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "Line {line} has {found} entries but {expected} were expected"
+            ),
+            ShapeError::NotAVector { line, found } => {
+                write!(f, "Line {line} contains {found:?} instead of a Vector")
+            }
+            ShapeError::SplitParts {
+                line,
+                expected,
+                found,
+                value,
+            } => write!(
+                f,
+                "Line {line} contains {value:?} which splits into {found} parts but {expected} were expected"
+            ),
+            ShapeError::ColumnLength { expected, found } => write!(
+                f,
+                "the column has {found} entries but the frame has {expected} lines"
+            ),
+            ShapeError::DuplicateColumn { name } => {
+                write!(f, "the header would contain '{name}' more than once")
+            }
+            ShapeError::MissingColumns { columns } => {
+                write!(f, "the header does not contain {}", columns.join(", "))
+            }
+            ShapeError::DependencyCycle { name } => {
+                write!(f, "the derived column '{name}' would depend on itself")
+            }
+            ShapeError::DroppedDependency { column, dependent } => write!(
+                f,
+                "cannot drop '{column}' since the derived column '{dependent}' depends on it"
+            ),
+            ShapeError::DuplicateCell { line, row, column } => write!(
+                f,
+                "Line {line} falls into the cell of {row:?} and '{column}' which is already filled"
+            ),
+        }
+    }
+}
+
+impl Error for ShapeError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct DataTypeError {
+    pub line: usize,
+    pub expected: &'static str,
+    pub found: Data,
+}
+
+impl Display for DataTypeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "Line {} contains {:?} but {} was expected",
+            self.line, self.found, self.expected
+        )
+    }
+}
+
+impl Error for DataTypeError {}
+
+///like DataTypeError but names the column as well
+#[derive(Debug, Clone, PartialEq)]
+pub struct ColumnCastError {
+    pub column: String,
+    pub line: usize,
+    pub expected: &'static str,
+    pub found: Data,
+}
+
+impl Display for ColumnCastError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "Line {} of column '{}' contains {:?} but {} was expected",
+            self.line, self.column, self.found, self.expected
+        )
+    }
+}
+
+impl Error for ColumnCastError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoinError {
+    TooManyRows {
+        rows: Option<usize>,
+        max_rows: usize,
+    },
+    ///both sides have a column with this name and JoinNaming::Error was chosen
+    DuplicateColumn { name: String },
+}
+
+impl Display for JoinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            JoinError::TooManyRows {
+                rows: Some(rows),
+                max_rows,
+            } => write!(
+                f,
+                "the join would produce {rows} lines but at most {max_rows} are allowed"
+            ),
+            JoinError::TooManyRows {
+                rows: None,
+                max_rows,
+            } => write!(
+                f,
+                "the join would produce more than usize::MAX lines but at most {max_rows} are allowed"
+            ),
+            JoinError::DuplicateColumn { name } => {
+                write!(f, "both sides of the join have a column named '{name}'")
+            }
+        }
+    }
+}
+
+impl Error for JoinError {}
+
+#[derive(Debug)]
+pub enum WriteError {
+    Io(IoError),
+    ///the file already has a different header
+    HeaderMismatch {
+        file: Vec<String>,
+        frame: Vec<String>,
+    },
+}
+
+impl Display for WriteError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            WriteError::Io(err) => write!(f, "{err}"),
+            WriteError::HeaderMismatch { file, frame } => {
+                writeln!(
+                    f,
+                    "the header of the file does not match the one of the frame"
+                )?;
+                for i in 0..file.len().max(frame.len()) {
+                    match (file.get(i), frame.get(i)) {
+                        (Some(file), Some(frame)) if file == frame => {}
+                        (file, frame) => {
+                            let describe = |name: Option<&String>| {
+                                name.map_or("nothing".to_owned(), |name| format!("{name:?}"))
+                            };
+                            writeln!(
+                                f,
+                                "column {i}: file has {}, frame has {}",
+                                describe(file),
+                                describe(frame)
+                            )?
+                        }
+                    }
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl Error for WriteError {}
+
+impl From<IoError> for WriteError {
+    fn from(err: IoError) -> Self {
+        WriteError::Io(err)
+    }
+}
+
+#[derive(Debug)]
+pub enum GroupCacheError {
+    Io(IoError),
+    ///the file is not a group index written by Groups::save_index
+    Format(String),
+    ///the frame is not the one the index was saved for
+    HashMismatch {
+        saved: u64,
+        frame: u64,
+    },
+    ///a saved key can not be converted back into the key type
+    Key(Data),
+}
+
+impl Display for GroupCacheError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            GroupCacheError::Io(err) => write!(f, "{err}"),
+            GroupCacheError::Format(message) => write!(f, "{message}"),
+            GroupCacheError::HashMismatch { saved, frame } => write!(
+                f,
+                "the group index was saved for a frame with hash {saved:016x} but the frame has hash {frame:016x}"
+            ),
+            GroupCacheError::Key(key) => {
+                write!(f, "the saved key {key:?} has the wrong type")
+            }
+        }
+    }
+}
+
+impl Error for GroupCacheError {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AppendError {
+    HeaderMismatch {
+        left: Vec<String>,
+        right: Vec<String>,
+    },
+    ///the dominant types of the column in both frames can not be widened to one type
+    IncompatibleTypes {
+        column: String,
+        left: &'static str,
+        right: &'static str,
+    },
+}
+
+impl Display for AppendError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            AppendError::HeaderMismatch { left, right } => {
+                write!(f, "the headers {left:?} and {right:?} differ")
+            }
+            AppendError::IncompatibleTypes {
+                column,
+                left,
+                right,
+            } => write!(
+                f,
+                "column '{column}' is {left} in one frame and {right} in the other"
+            ),
+        }
+    }
+}
+
+impl Error for AppendError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArithmeticError {
+    ///the operation is not defined for these variants
+    Unsupported {
+        op: &'static str,
+        left: Data,
+        right: Data,
+    },
+    ///the result of two Integers does not fit into an Integer
+    Overflow {
+        op: &'static str,
+        left: i32,
+        right: i32,
+    },
+    ///an Integer divided by the Integer 0
+    DivisionByZero,
+}
+
+impl Display for ArithmeticError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ArithmeticError::Unsupported { op, left, right } => {
+                write!(f, "{left:?} {op} {right:?} is not defined")
+            }
+            ArithmeticError::Overflow { op, left, right } => {
+                write!(f, "{left} {op} {right} overflows an Integer")
+            }
+            ArithmeticError::DivisionByZero => write!(f, "Integer division by 0"),
+        }
+    }
+}
+
+impl Error for ArithmeticError {}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum BinError {
+    ///q was 0
+    NoBins,
+    LabelCount {
+        expected: usize,
+        found: usize,
+    },
+    ///the column has no Integer or Float cell to compute the edges from
+    NoValues,
+    NotNumeric {
+        line: usize,
+        found: Data,
+    },
+}
+
+impl Display for BinError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            BinError::NoBins => write!(f, "at least one bin is needed"),
+            BinError::LabelCount { expected, found } => {
+                write!(f, "{found} labels were given for {expected} bins")
+            }
+            BinError::NoValues => write!(f, "the column contains no numeric values"),
+            BinError::NotNumeric { line, found } => {
+                write!(f, "Line {line} contains {found:?} instead of a number")
+            }
+        }
+    }
+}
+
+impl Error for BinError {}
+
+///a line whose time is before the latest time of the lines above it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BackwardStep {
+    pub line: usize,
+    pub time: Data,
+    ///the latest time of the lines above
+    pub latest: Data,
+}
+
+///the lines of a time column that go backwards, see DataFrame::validate_monotonic
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonotonicReport {
+    pub column: String,
+    pub steps: Vec<BackwardStep>,
+}
+
+impl Display for MonotonicReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(
+            f,
+            "column '{}' goes backwards in {} lines:",
+            self.column,
+            self.steps.len()
+        )?;
+        for step in &self.steps {
+            write!(
+                f,
+                "\nLine {}: {} after {}",
+                step.line, step.time, step.latest
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for MonotonicReport {}
+
+impl From<IoError> for GroupCacheError {
+    fn from(err: IoError) -> Self {
+        GroupCacheError::Io(err)
+    }
+}
@@ -1,16 +1,32 @@
 use std::fmt::Write;
 
-use super::{BaseDataFrame, Data, DataFrame, InnerDataFrame};
+use super::read_options::ColumnParser;
+use super::{observed, BaseDataFrame, Data, DataFrame, InnerDataFrame, ParseRules, ReadOptions};
 use std::{
     fs::File,
-    io::{BufRead, BufReader, Error as IoError},
+    io::{BufRead, BufReader, Error as IoError, ErrorKind, Read},
     path::Path,
 };
 
 impl DataFrame {
     pub fn from_file(path: &Path, seperator: Option<char>) -> Result<DataFrame, IoError> {
-        let base = BaseDataFrame::from_file(path, seperator)?;
-        Ok(InnerDataFrame::Base { df: base }.into())
+        let options = ReadOptions::new().seperator(seperator.unwrap_or(','));
+        DataFrame::from_file_with_options(path, options)
+    }
+
+    pub fn from_file_with_options(
+        path: &Path,
+        mut options: ReadOptions,
+    ) -> Result<DataFrame, IoError> {
+        observed(
+            "from_file",
+            0,
+            |result: &Result<DataFrame, IoError>| result.as_ref().map_or(0, DataFrame::len),
+            move || {
+                let base = BaseDataFrame::from_file(path, &mut options)?;
+                Ok(InnerDataFrame::Base { df: base }.into())
+            },
+        )
     }
 
     pub fn append_file(
@@ -25,34 +41,95 @@ impl DataFrame {
     }
 
     pub fn from_string(string: String, seperator: Option<char>) -> Result<DataFrame, IoError> {
-        let base = BaseDataFrame::from_string(string, seperator)?;
+        let options = ReadOptions::new().seperator(seperator.unwrap_or(','));
+        DataFrame::from_string_with_options(string, options)
+    }
+
+    pub fn from_string_with_options(
+        string: String,
+        mut options: ReadOptions,
+    ) -> Result<DataFrame, IoError> {
+        let base = BaseDataFrame::from_string(string, &mut options)?;
+        Ok(InnerDataFrame::Base { df: base }.into())
+    }
+
+    ///parses a single line against the current header with the parse rules of the frame
+    ///and appends it, seperator overrides the seperator of those rules
+    pub fn append_csv_row(self, raw: &str, seperator: Option<char>) -> Result<DataFrame, IoError> {
+        self.append_csv_rows(std::iter::once(raw), seperator)
+    }
+
+    ///removes lines whose cells are exactly the names of the header (ignoring surrounding whitespace),
+    ///for frames read without ReadOptions::drop_repeated_headers
+    pub fn drop_header_like_rows(self) -> DataFrame {
+        let header: Vec<String> = self
+            .header()
+            .map(|head_elem| head_elem.trim().to_owned())
+            .collect();
+        self.filter(|line| {
+            !line
+                .iter()
+                .zip(header.iter())
+                .all(|(data, head_elem)| data.as_string().trim() == head_elem)
+        })
+    }
+
+    ///nothing is appended if any of the lines fails to parse
+    pub fn append_csv_rows<'s>(
+        self,
+        lines: impl Iterator<Item = &'s str>,
+        seperator: Option<char>,
+    ) -> Result<DataFrame, IoError> {
+        let mut base = BaseDataFrame::from(self);
+        base.append_csv_rows(lines, seperator)?;
         Ok(InnerDataFrame::Base { df: base }.into())
     }
 }
 
 impl BaseDataFrame {
-    fn from_file(path: &Path, seperator: Option<char>) -> Result<BaseDataFrame, IoError> {
-        let seperator = seperator.unwrap_or(',');
-        let file = File::open(&path)?;
-        let reader = BufReader::new(file);
-
-        let mut line_iter = reader.lines().enumerate();
+    fn from_file(path: &Path, options: &mut ReadOptions) -> Result<BaseDataFrame, IoError> {
+        let rules = options.rules;
+        let mut record_iter = RecordIter::new(read_lines(path, options.utf16)?, rules);
 
-        let (_i, raw_header) = line_iter
+        let (_i, raw_header) = record_iter
             .next()
             .ok_or_else(|| IoError::other("File is empty"))?;
 
         //trim an invisible char thats exel adds as an encoding hint
         let raw_header = raw_header.map(|string| string.trim_matches('\u{feff}').to_owned());
-        let header = BaseDataFrame::try_build_header(ChunkIter::from_str(&raw_header?, seperator))?;
+        let header = BaseDataFrame::try_build_header(ChunkIter::from_str(&raw_header?, rules))?;
 
-        let data = BaseDataFrame::get_data_from_file(&header, line_iter, seperator)?;
+        let mut parsers = options.take_parsers(&header)?;
+        let na_values = options.take_na_values(&header)?;
+        let data = BaseDataFrame::get_data_from_file(
+            &header,
+            record_iter,
+            &rules,
+            &mut parsers,
+            &na_values,
+            options.drop_repeated_headers,
+        )?;
 
-        Ok(BaseDataFrame {
+        let mut base = BaseDataFrame {
             identity_index_map: (0..header.len()).collect(),
             header,
             data,
-        })
+            string_cache: None,
+            parse_rules: rules,
+        };
+        if let Some(cache) = &options.string_cache {
+            base.categorize_strings(cache);
+        }
+        Ok(base)
+    }
+
+    ///only the header of the file at path
+    pub(super) fn read_header(path: &Path, rules: ParseRules) -> Result<Vec<String>, IoError> {
+        let (_i, raw_header) = RecordIter::new(read_lines(path, false)?, rules)
+            .next()
+            .ok_or_else(|| IoError::other("File is empty"))?;
+        let raw_header = raw_header.map(|string| string.trim_matches('\u{feff}').to_owned());
+        BaseDataFrame::try_build_header(ChunkIter::from_str(&raw_header?, rules))
     }
 
     fn append_file(
@@ -61,69 +138,173 @@ impl BaseDataFrame {
         seperator: Option<char>,
         skip_first_line: bool,
     ) -> Result<(), IoError> {
-        let seperator = seperator.unwrap_or(',');
-        let file = File::open(&path)?;
-        let reader = BufReader::new(file);
-
-        let line_iter = reader
-            .lines()
-            .enumerate()
+        let rules = self.parse_rules_with(seperator);
+        let record_iter = RecordIter::new(read_lines(path, false)?, rules)
             .skip(if skip_first_line { 1 } else { 0 });
-        let mut data = BaseDataFrame::get_data_from_file(&self.header, line_iter, seperator)?;
+        let mut data = BaseDataFrame::get_data_from_file(
+            &self.header,
+            record_iter,
+            &rules,
+            &mut [],
+            &[],
+            false,
+        )?;
         self.append_lines(data.drain(..));
         Ok(())
     }
 
-    fn from_string(string: String, seperator: Option<char>) -> Result<BaseDataFrame, IoError> {
-        let seperator = seperator.unwrap_or(',');
-        let mut line_iter = string.lines().enumerate();
-        let (_i, raw_header) = line_iter
+    fn from_string(string: String, options: &mut ReadOptions) -> Result<BaseDataFrame, IoError> {
+        let rules = options.rules;
+        let mut record_iter = RecordIter::new(
+            string.split_inclusive('\n').map(|line| Ok(line.to_owned())),
+            rules,
+        );
+        let (_i, raw_header) = record_iter
             .next()
             .ok_or_else(|| IoError::other("String is empty"))?;
-        let header = BaseDataFrame::try_build_header(ChunkIter::from_str(raw_header, seperator))?;
+        let header = BaseDataFrame::try_build_header(ChunkIter::from_str(&raw_header?, rules))?;
 
-        let mut data = Vec::new();
-        for (i, line) in line_iter {
-            let chunk_iter = ChunkIter::from_str(line, seperator);
-            let line_data: Vec<Data> = chunk_iter.collect();
+        let mut parsers = options.take_parsers(&header)?;
+        let na_values = options.take_na_values(&header)?;
+        let data = BaseDataFrame::get_data_from_file(
+            &header,
+            record_iter,
+            &rules,
+            &mut parsers,
+            &na_values,
+            options.drop_repeated_headers,
+        )?;
 
-            if line_data.len() != header.len() {
-                return Err(Self::create_error(i, &line_data, &header));
-            }
-            data.push(line_data);
-        }
-
-        Ok(BaseDataFrame {
+        let mut base = BaseDataFrame {
             identity_index_map: (0..header.len()).collect(),
             header,
             data,
-        })
+            string_cache: None,
+            parse_rules: rules,
+        };
+        if let Some(cache) = &options.string_cache {
+            base.categorize_strings(cache);
+        }
+        Ok(base)
     }
 
     fn get_data_from_file(
         header: &[String],
-        line_iter: impl Iterator<Item = (usize, Result<String, IoError>)>,
-        seperator: char,
+        record_iter: impl Iterator<Item = (usize, Result<String, IoError>)>,
+        rules: &ParseRules,
+        parsers: &mut [Option<ColumnParser>],
+        na_values: &[Vec<String>],
+        drop_repeated_headers: bool,
     ) -> Result<Vec<Vec<Data>>, IoError> {
         let mut data = Vec::new();
-        for (i, line_res) in line_iter {
-            let line = line_res?;
-            let chunk_iter = ChunkIter::from_str(&line, seperator);
-            let line_data: Vec<Data> = chunk_iter.collect();
+        for (i, record) in record_iter {
+            let record = record?;
+            if drop_repeated_headers && Self::is_header_line(header, &record, rules) {
+                continue;
+            }
+            data.push(BaseDataFrame::parse_line(
+                header, i, &record, rules, parsers, na_values,
+            )?);
+        }
+        Ok(data)
+    }
 
-            if line_data.len() != header.len() {
-                return Err(Self::create_error(i, &line_data, header));
+    ///compares the raw chunks ignoring surrounding whitespace, quotes and a byte order mark
+    fn is_header_line(header: &[String], record: &str, rules: &ParseRules) -> bool {
+        let mut chunk_iter = ChunkIter::from_str(record.trim_matches('\u{feff}'), *rules);
+        let mut header_iter = header.iter();
+        loop {
+            match (chunk_iter.next_raw(), header_iter.next()) {
+                (Some(Ok(chunk)), Some(head_elem)) => {
+                    let chunk = chunk.trim();
+                    let chunk = chunk
+                        .strip_prefix('"')
+                        .and_then(|chunk| chunk.strip_suffix('"'))
+                        .map_or(chunk.to_owned(), |chunk| chunk.replace("\"\"", "\""));
+                    if chunk.trim() != head_elem.trim() {
+                        return false;
+                    }
+                }
+                (None, None) => return true,
+                _ => return false,
             }
+        }
+    }
+
+    fn append_csv_rows<'s>(
+        &mut self,
+        lines: impl Iterator<Item = &'s str>,
+        seperator: Option<char>,
+    ) -> Result<(), IoError> {
+        let rules = self.parse_rules_with(seperator);
+        //parse everything first so a bad line leaves the frame unchanged
+        let mut data = Vec::new();
+        for (i, line) in lines.enumerate() {
+            let line_data = BaseDataFrame::parse_line(&self.header, i, line, &rules, &mut [], &[])
+                .map_err(|err| IoError::other(format!("{err}\nraw line: '{line}'")))?;
             data.push(line_data);
         }
-        Ok(data)
+        self.append_lines(data.drain(..));
+        Ok(())
+    }
+
+    fn parse_line(
+        header: &[String],
+        line_index: usize,
+        line: &str,
+        rules: &ParseRules,
+        parsers: &mut [Option<ColumnParser>],
+        na_values: &[Vec<String>],
+    ) -> Result<Vec<Data>, IoError> {
+        let mut chunk_iter = ChunkIter::from_str(line, *rules);
+        let mut line_data = Vec::with_capacity(header.len());
+        let locate = |err: IoError| IoError::other(format!("Line {}: {err}", line_index + 1));
+        while let Some(chunk) = chunk_iter.next_raw() {
+            let chunk = chunk.map_err(locate)?;
+            let is_na = na_values
+                .get(line_data.len())
+                .is_some_and(|values| values.iter().any(|value| value == chunk.trim()));
+            let data = match parsers.get_mut(line_data.len()).and_then(Option::as_mut) {
+                _ if is_na => Data::Null,
+                Some(parser) => parser(chunk.trim()).map_err(|err| {
+                    IoError::other(format!(
+                        "Line {} column '{}': {err}",
+                        line_index + 1,
+                        header[line_data.len()]
+                    ))
+                })?,
+                None => chunk_iter.parse_chunk(chunk).map_err(locate)?,
+            };
+            line_data.push(data);
+        }
+
+        //the empty line a writer produces for a Null in a file with a single column
+        if line_data.is_empty() && header.len() == 1 {
+            line_data.push(Data::Null);
+        }
+        if line_data.len() != header.len() {
+            return Err(Self::create_error(line_index, &line_data, header));
+        }
+        Ok(line_data)
+    }
+
+    fn parse_rules_with(&self, seperator: Option<char>) -> ParseRules {
+        let mut rules = self.parse_rules;
+        if let Some(seperator) = seperator {
+            rules.seperator = seperator;
+        }
+        rules
     }
 
     fn try_build_header(raw_header: ChunkIter) -> Result<Vec<String>, IoError> {
         let mut header = Vec::new();
         for data in raw_header {
+            let data = data.map_err(|err| IoError::other(format!("Header: {err}")))?;
             if let Data::String(string) = data {
                 header.push(Box::<String>::into_inner(string));
+            } else if data.is_null() {
+                //e.g. a trailing seperator, the lines have an empty field there as well
+                header.push(String::new());
             } else {
                 return Err(IoError::other("File has no valid Header"));
             }
@@ -174,6 +355,149 @@ impl BaseDataFrame {
     }
 }
 
+type Lines = Box<dyn Iterator<Item = Result<String, IoError>>>;
+
+///the lines of the file with their line endings, a UTF-16 file (recognized by its byte order mark)
+///is an error naming the encoding unless transcode_utf16 is set
+fn read_lines(path: &Path, transcode_utf16: bool) -> Result<Lines, IoError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let start = reader.fill_buf()?;
+    let encoding = if start.starts_with(&[0xff, 0xfe]) {
+        Some(("utf-16le", u16::from_le_bytes as fn([u8; 2]) -> u16))
+    } else if start.starts_with(&[0xfe, 0xff]) {
+        Some(("utf-16be", u16::from_be_bytes as fn([u8; 2]) -> u16))
+    } else {
+        None
+    };
+
+    match encoding {
+        None => Ok(Box::new(lines_with_endings(reader))),
+        Some((name, _from_bytes)) if !transcode_utf16 => Err(IoError::new(
+            ErrorKind::InvalidData,
+            format!("the file is encoded as {name}, ReadOptions::utf16 reads it"),
+        )),
+        Some((name, from_bytes)) => {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes)?;
+            let units: Vec<u16> = bytes[2..]
+                .chunks(2)
+                .map(|pair| from_bytes([pair[0], *pair.get(1).unwrap_or(&0)]))
+                .collect();
+            let text = String::from_utf16(&units).map_err(|err| {
+                IoError::new(ErrorKind::InvalidData, format!("invalid {name}: {err}"))
+            })?;
+            let lines: Vec<_> = text
+                .split_inclusive('\n')
+                .map(|line| Ok(line.to_owned()))
+                .collect();
+            Ok(Box::new(lines.into_iter()))
+        }
+    }
+}
+
+///the lines of reader with their line endings
+fn lines_with_endings(mut reader: impl BufRead) -> impl Iterator<Item = Result<String, IoError>> {
+    std::iter::from_fn(move || {
+        let mut line = String::new();
+        match reader.read_line(&mut line) {
+            Ok(0) => None,
+            Ok(_len) => Some(Ok(line)),
+            Err(err) => Some(Err(err)),
+        }
+    })
+}
+
+///joins physical lines (with their line endings) into records as long as a quoted field
+///is still open at the end of a line, yields the index of the first physical line of each record
+///
+///the line endings inside a quoted field are kept as they are, the one ending the record is dropped
+///
+///a record is split into chunks like a line is parsed, so only a '"' at the start of a field
+///opens a quoted field and 5" screen stays on its line
+///
+///a byte order mark at the start of a record is dropped, exporters put it in front of
+///the first line and concatenated exports contain it in front of later lines as well
+struct RecordIter<L> {
+    lines: L,
+    line_index: usize,
+    rules: ParseRules,
+}
+
+impl<L> RecordIter<L>
+where
+    L: Iterator<Item = Result<String, IoError>>,
+{
+    fn new(lines: L, rules: ParseRules) -> RecordIter<L> {
+        RecordIter {
+            lines,
+            line_index: 0,
+            rules,
+        }
+    }
+
+    ///whether the record ends inside a quoted field that was opened at the start of a field
+    fn ends_in_quote(&self, record: &str) -> bool {
+        let mut chunk_iter = ChunkIter::from_str(record, self.rules);
+        loop {
+            match chunk_iter.next_raw() {
+                Some(Ok(_chunk)) => {}
+                Some(Err(_err)) => return chunk_iter.open_quote,
+                None => return false,
+            }
+        }
+    }
+}
+
+impl<L> Iterator for RecordIter<L>
+where
+    L: Iterator<Item = Result<String, IoError>>,
+{
+    type Item = (usize, Result<String, IoError>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let start_index = self.line_index;
+        let mut record = match self.lines.next()? {
+            Ok(line) => match line.strip_prefix('\u{feff}') {
+                Some(stripped) => stripped.to_owned(),
+                None => line,
+            },
+            Err(err) => {
+                self.line_index += 1;
+                return Some((start_index, Err(err)));
+            }
+        };
+        self.line_index += 1;
+
+        let mut open_quote = self.ends_in_quote(without_line_ending(&record));
+        while open_quote {
+            match self.lines.next() {
+                Some(Ok(line)) => {
+                    self.line_index += 1;
+                    record.push_str(&line);
+                    open_quote = self.ends_in_quote(without_line_ending(&record));
+                }
+                Some(Err(err)) => {
+                    self.line_index += 1;
+                    return Some((start_index, Err(err)));
+                }
+                //the unmatched quote is reported by ChunkIter
+                None => break,
+            }
+        }
+
+        record.truncate(without_line_ending(&record).len());
+        Some((start_index, Ok(record)))
+    }
+}
+
+///line without a trailing "\n" or "\r\n"
+fn without_line_ending(line: &str) -> &str {
+    match line.strip_suffix('\n') {
+        Some(line) => line.strip_suffix('\r').unwrap_or(line),
+        None => line,
+    }
+}
+
 const GROUPING_SYMBOLE: [(char, char); 6] = [
     ('(', ')'),
     ('{', '}'),
@@ -183,57 +507,514 @@ const GROUPING_SYMBOLE: [(char, char); 6] = [
     ('\'', '\''),
 ];
 
+///a stray closing symbole is not special, it stays part of the chunk it is in
 struct ChunkIter<'s> {
     string: &'s str,
-    seperator: char,
+    ///the last error was a '"' that is not closed in the rest of the string
+    open_quote: bool,
+    ///the whole line string is a part of, to locate errors
+    line: &'s str,
+    ///a seperator was consumed, so one more (maybe empty) chunk follows
+    field_pending: bool,
+    rules: ParseRules,
 }
 
 impl<'s> ChunkIter<'s> {
-    fn from_str(string: &'s str, seperator: char) -> ChunkIter {
-        ChunkIter { string, seperator }
+    fn from_str(string: &'s str, rules: ParseRules) -> ChunkIter<'s> {
+        ChunkIter {
+            string,
+            open_quote: false,
+            line: string,
+            field_pending: false,
+            rules,
+        }
+    }
+
+    ///a whitespace seperator (e.g. a tab) is not trimmed, it still ends an empty field
+    fn trim_start<'a>(&self, string: &'a str) -> &'a str {
+        string.trim_start_matches(|elem: char| elem.is_whitespace() && elem != self.rules.seperator)
+    }
+
+    ///1 based char position of the start of the remaining string in the line
+    fn column(&self) -> usize {
+        let offset = self.string.as_ptr() as usize - self.line.as_ptr() as usize;
+        self.line[..offset].chars().count() + 1
     }
 }
 
-impl<'s> Iterator for ChunkIter<'s> {
-    type Item = Data;
+impl<'s> ChunkIter<'s> {
+    ///the next chunk as written in the line, a group still contains its grouping symboles,
+    ///an empty field (also one after a trailing seperator) is an empty chunk
+    fn next_raw(&mut self) -> Option<Result<&'s str, IoError>> {
+        self.string = self.trim_start(self.string);
+        let mut chars = self.string.char_indices();
+        let Some((_start_index, first)) = chars.next() else {
+            return std::mem::take(&mut self.field_pending).then_some(Ok(self.string));
+        };
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let trimed = self.string.trim_start();
-        let mut chars = trimed.char_indices().peekable();
+        if let Some((start, end)) = GROUPING_SYMBOLE
+            .iter()
+            .find(|(start, _end)| *start == first)
+        {
+            let end_index = Self::group_end(self.string, *start, *end, self.rules.seperator);
+            let Ok(end_index) = end_index else {
+                self.open_quote = end_index == Err(true);
+                let err = IoError::other(format!(
+                    "unmatched '{start}' at column {} has no closing '{end}'",
+                    self.column()
+                ));
+                self.string = &self.string[0..0];
+                return Some(Err(err));
+            };
+
+            let chunk = &self.string[..end_index + end.len_utf8()];
+            //skip the seperator following the group so it does not start an empty chunk
+            let rest = self.trim_start(&self.string[chunk.len()..]);
+            self.field_pending = rest.starts_with(self.rules.seperator);
+            self.string = rest.strip_prefix(self.rules.seperator).unwrap_or(rest);
+            Some(Ok(chunk))
+        } else if let Some(end_index) = self.string.find(self.rules.seperator) {
+            let chunk = &self.string[..end_index];
+            self.string = &self.string[end_index + self.rules.seperator.len_utf8()..];
+            self.field_pending = true;
+            Some(Ok(chunk))
+        } else {
+            let chunk = self.string;
+            self.string = &self.string[0..0];
+            self.field_pending = false;
+            Some(Ok(chunk))
+        }
+    }
+
+    ///byte index of the symbole closing the group that starts the string,
+    ///a group nested in a bracket group is only recognized at the start of an element,
+    ///the error is true if a '"' is not closed, so the record may go on in the next line
+    fn group_end(string: &str, start: char, end: char, seperator: char) -> Result<usize, bool> {
+        if start == end {
+            return Self::quote_end(string, end).ok_or(end == '"');
+        }
+
+        let mut chars = string.char_indices().skip(1).peekable();
+        let mut open_groups = vec![end];
+        let mut element_start = true;
+        while let Some((index, elem)) = chars.next() {
+            if element_start && !elem.is_whitespace() {
+                element_start = false;
+                match GROUPING_SYMBOLE.iter().find(|(start, _end)| *start == elem) {
+                    Some((start, end)) if start == end => {
+                        let close =
+                            index + Self::quote_end(&string[index..], *end).ok_or(*end == '"')?;
+                        while chars.next_if(|(index, _elem)| *index <= close).is_some() {}
+                        continue;
+                    }
+                    Some((_start, end)) => {
+                        open_groups.push(*end);
+                        element_start = true;
+                        continue;
+                    }
+                    None => {}
+                }
+            }
+            if open_groups.last() == Some(&elem) {
+                open_groups.pop();
+                if open_groups.is_empty() {
+                    return Ok(index);
+                }
+            } else if elem == seperator {
+                element_start = true;
+            }
+        }
+        Err(false)
+    }
+
+    ///byte index of the quote closing the one that starts the string,
+    ///inside '"' a doubled "" is an escaped quote and does not close the group
+    fn quote_end(string: &str, quote: char) -> Option<usize> {
+        let mut chars = string.char_indices().skip(1).peekable();
+        while let Some((index, elem)) = chars.next() {
+            if elem == quote
+                && (quote != '"' || chars.next_if(|(_index, elem)| *elem == '"').is_none())
+            {
+                return Some(index);
+            }
+        }
+        None
+    }
 
-        if let Some(&(start_index, first)) = chars.peek() {
-            if let Some((_start, end)) = GROUPING_SYMBOLE
+    fn parse_chunk(&self, chunk: &str) -> Result<Data, IoError> {
+        let group = chunk.chars().next().and_then(|first| {
+            GROUPING_SYMBOLE
                 .iter()
                 .find(|(start, _end)| *start == first)
-            {
-                let (end_index, _end_symbole) = chars
-                    .find(|(_index, elem)| elem == end)
-                    .expect("Line contains a start but no matching end grouping symbole");
-
-                let trimed_start_index = self.string.ceil_char_boundary(start_index + 1);
-                let inner_iter = ChunkIter::from_str(
-                    &self.string[trimed_start_index..end_index],
-                    self.seperator,
-                );
-
-                let item = Data::Vector(Box::new(inner_iter.collect()));
-                let trimed_end_index = self.string.ceil_char_boundary(end_index + 1);
-                self.string = &self.string[trimed_end_index..];
-                Some(item)
-            } else if let Some((end_index, _seperator)) =
-                chars.find(|(_index, elem)| *elem == self.seperator)
-            {
-                let item = Data::from(self.string[start_index..end_index].to_owned());
-                let trimed_end_index = self.string.ceil_char_boundary(end_index + 1);
-                self.string = &self.string[trimed_end_index..];
-                Some(item)
-            } else {
-                let item = Data::from(self.string[start_index..].to_owned());
-                self.string = &self.string[0..0];
-                Some(item)
+        });
+
+        match group {
+            //a quoted group is a String without any inference
+            Some(('"', _end)) => Ok(Data::String(Box::new(
+                chunk[1..chunk.len() - 1].replace("\"\"", "\""),
+            ))),
+            Some((start, end)) if self.rules.vectors => {
+                let inner = &chunk[start.len_utf8()..chunk.len() - end.len_utf8()];
+                //[] has no element but in [ ] the only element is empty like the last one in [1, ]
+                ChunkIter {
+                    string: inner,
+                    open_quote: false,
+                    line: self.line,
+                    field_pending: !inner.is_empty(),
+                    rules: self.rules,
+                }
+                .collect::<Result<_, _>>()
+                .map(|vec| Data::Vector(Box::new(vec)))
             }
+            Some(_group) => Ok(Data::String(Box::new(chunk.to_owned()))),
+            None if chunk.trim().is_empty() => Ok(Data::Null),
+            None => Ok(Data::infer(chunk.to_owned(), &self.rules)),
+        }
+    }
+}
+
+impl Data {
+    ///parses a single cell the same way a frame read with rules would,
+    ///a malformed group stays a String
+    pub fn parse_with(rules: &ParseRules, string: &str) -> Data {
+        let chunk = string.trim();
+        let mut chunk_iter = ChunkIter::from_str(chunk, *rules);
+        match chunk_iter.next_raw() {
+            Some(Ok(raw)) if raw.len() == chunk.len() => chunk_iter
+                .parse_chunk(raw)
+                .unwrap_or_else(|_err| Data::String(Box::new(chunk.to_owned()))),
+            Some(Ok(_)) | Some(Err(_)) => Data::String(Box::new(chunk.to_owned())),
+            None => Data::Null,
+        }
+    }
+}
+
+impl Data {
+    ///the canonical text of a cell, parse_with reads it back to the same Data
+    ///if the default rules with this seperator are used
+    ///
+    ///floats always contain a '.' or an exponent, a Vec2D is written as "x y",
+    ///a Vector as [a, b, ..] (one holding only a Null as [ ]) and a String that would be read
+    ///differently is quoted
+    pub fn to_csv_string(&self, seperator: char) -> String {
+        let mut string = String::new();
+        self.write_csv(&mut string, seperator);
+        string
+    }
+
+    fn write_csv(&self, out: &mut String, seperator: char) {
+        match self {
+            Data::String(string) => Self::write_text(out, string, seperator),
+            Data::Category(category) => Self::write_text(out, category, seperator),
+            Data::Integer(int) => write!(out, "{int}"),
+            Data::Float(float) => write!(out, "{float:?}"),
+            Data::Boolean(boolean) => write!(out, "{boolean}"),
+            Data::Date(date) => write!(out, "{}", date.to_rfc3339()),
+            Data::Vector(vec) if vec.len() == 1 && vec[0].is_null() => write!(out, "[ ]"),
+            Data::Vector(vec) => {
+                out.push('[');
+                for (i, data) in vec.iter().enumerate() {
+                    if i != 0 {
+                        out.push(seperator);
+                        out.push(' ');
+                    }
+                    data.write_csv(out, seperator);
+                }
+                out.push(']');
+                Ok(())
+            }
+            Data::Vec2D((x, y)) => write!(out, "{x:?} {y:?}"),
+            Data::Null => Ok(()),
+        }
+        .expect("writing to a String does not fail")
+    }
+
+    fn write_text(out: &mut String, string: &str, seperator: char) -> std::fmt::Result {
+        if Self::needs_quotes(string, seperator) {
+            write!(out, "\"{}\"", string.replace('"', "\"\""))
         } else {
-            None
+            write!(out, "{string}")
+        }
+    }
+
+    fn needs_quotes(string: &str, seperator: char) -> bool {
+        let rules = ParseRules {
+            seperator,
+            ..ParseRules::default()
+        };
+        string.is_empty()
+            || string.trim() != string
+            || string.contains(|elem: char| {
+                elem == seperator
+                    || elem == '\n'
+                    || GROUPING_SYMBOLE
+                        .iter()
+                        .any(|(start, end)| elem == *start || elem == *end)
+            })
+            || !matches!(Data::infer(string.to_owned(), &rules), Data::String(_))
+    }
+}
+
+impl<'s> Iterator for ChunkIter<'s> {
+    type Item = Result<Data, IoError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_raw()
+            .map(|chunk| chunk.and_then(|chunk| self.parse_chunk(chunk)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn text(string: &str) -> Data {
+        Data::String(Box::new(string.to_owned()))
+    }
+
+    #[test]
+    fn quote_inside_a_field_does_not_join_lines() {
+        let df = DataFrame::from_string("id,size\n1,5\" screen\n2,7\" screen\n".to_owned(), None)
+            .expect("the text is valid");
+        assert_eq!(df.len(), 2);
+        assert_eq!(df.get(1).expect("two lines")[1], text("7\" screen"));
+    }
+
+    #[test]
+    fn quoted_field_keeps_its_line_endings() {
+        let text_with_crlf = "id,text\r\n1,\"a\r\nb\"\r\n2,\"c\nd\"\n";
+        let df =
+            DataFrame::from_string(text_with_crlf.to_owned(), None).expect("the text is valid");
+        assert_eq!(df.len(), 2);
+        let quoted = |line: usize| df.get(line).expect("two lines")[1].clone();
+        assert_eq!(quoted(0), text("a\r\nb"));
+        assert_eq!(quoted(1), text("c\nd"));
+
+        //also inside a bracket group
+        let df = DataFrame::from_string("id,text\n1,[\"a\nb\", 2]\n".to_owned(), None)
+            .expect("the text is valid");
+        assert_eq!(df.len(), 1);
+        assert_eq!(
+            df.get(0).expect("one line")[1],
+            Data::Vector(Box::new(vec![text("a\nb"), Data::Integer(2)]))
+        );
+    }
+
+    #[test]
+    fn quoted_fields_are_single_strings() {
+        let df = DataFrame::from_string(
+            "name,full_name,age\nname,\"Smith, John\",42\nx,\"say \"\"hi\"\"\",7\ny,\"1 2\",8\n"
+                .to_owned(),
+            None,
+        )
+        .expect("the text is valid");
+        assert_eq!(df.shape(), (3, 3));
+        let first = df.get(0).expect("three lines");
+        assert_eq!(first[0], text("name"));
+        assert_eq!(first[1], text("Smith, John"));
+        assert_eq!(first[2], Data::Integer(42));
+        assert_eq!(df.get(1).expect("three lines")[1], text("say \"hi\""));
+        //no Vec2D is read from a quoted field
+        assert_eq!(df.get(2).expect("three lines")[1], text("1 2"));
+    }
+
+    #[test]
+    fn empty_fields_are_null() {
+        let df = DataFrame::from_string("a,b,c\n1,,3\n,2,\n".to_owned(), None)
+            .expect("the text is valid");
+        assert_eq!(df.shape(), (2, 3));
+        let cells = |line: usize| -> Vec<Data> {
+            df.get(line).expect("two lines").iter().cloned().collect()
+        };
+        assert_eq!(cells(0), [Data::Integer(1), Data::Null, Data::Integer(3)]);
+        assert_eq!(cells(1), [Data::Null, Data::Integer(2), Data::Null]);
+        //a quoted empty field is an empty String
+        let df =
+            DataFrame::from_string("a,b\n\"\",\n".to_owned(), None).expect("the text is valid");
+        assert_eq!(df.get(0).expect("one line")[0], text(""));
+        assert_eq!(df.get(0).expect("one line")[1], Data::Null);
+    }
+
+    #[test]
+    fn vector_of_a_single_null_round_trips() {
+        let rules = ParseRules::default();
+        let vector = Data::Vector(Box::new(vec![Data::Null]));
+        assert_eq!(vector.to_csv_string(','), "[ ]");
+        assert_eq!(Data::parse_with(&rules, "[ ]"), vector);
+        assert_eq!(Data::parse_with(&rules, "[]"), Data::Vector(Box::default()));
+    }
+
+    #[test]
+    fn empty_line_is_a_null_in_a_single_column() {
+        let df = DataFrame::from_string("a\n1\n\n2\n".to_owned(), None).expect("the text is valid");
+        let column: Vec<Data> = df.iter().map(|line| line[0].clone()).collect();
+        assert_eq!(column, vec![Data::Integer(1), Data::Null, Data::Integer(2)]);
+    }
+
+    fn read_error(text: &str) -> String {
+        match DataFrame::from_string(text.to_owned(), None) {
+            Ok(df) => panic!("{text:?} is read as\n{df}"),
+            Err(err) => err.to_string(),
+        }
+    }
+
+    #[test]
+    fn unmatched_symbols_are_errors() {
+        assert_eq!(
+            read_error("id,value\n1,2\n2,(3\n"),
+            "Line 3: unmatched '(' at column 3 has no closing ')'"
+        );
+        assert_eq!(
+            read_error("id,value\n1,2\n2,[\"3]\n"),
+            "Line 3: unmatched '[' at column 3 has no closing ']'"
+        );
+        assert_eq!(
+            read_error("id,value\n1,2\n2,\"3\n"),
+            "Line 3: unmatched '\"' at column 3 has no closing '\"'"
+        );
+        assert_eq!(
+            read_error("id,(value\n1,2\n"),
+            "Header: unmatched '(' at column 4 has no closing ')'"
+        );
+        assert_eq!(
+            read_error("id,\"value\n1,2\n"),
+            "Header: unmatched '\"' at column 4 has no closing '\"'"
+        );
+        //a stray closing symbole is part of the field
+        let df =
+            DataFrame::from_string("id,value\n1,3)\n".to_owned(), None).expect("the text is valid");
+        assert_eq!(df.get(0).expect("one line")[1], text("3)"));
+    }
+
+    #[test]
+    fn space_separated_numbers_stay_strings_without_vec2d() {
+        let text = "zip,version\n1 2,3 4\n10 20,5\n".to_owned();
+        let df = DataFrame::from_string(text.clone(), None).expect("the text is valid");
+        assert_eq!(df.get(0).expect("two lines")[0], Data::Vec2D((1.0, 2.0)));
+
+        let rules = ParseRules {
+            vec2d: false,
+            ..ParseRules::default()
+        };
+        let df = DataFrame::from_string_with_options(text, ReadOptions::new().parse_rules(rules))
+            .expect("the text is valid");
+        let zips: Vec<Data> = df.iter().map(|line| line["zip"].clone()).collect();
+        assert_eq!(
+            zips,
+            vec![
+                Data::String(Box::new("1 2".to_owned())),
+                Data::String(Box::new("10 20".to_owned())),
+            ]
+        );
+        assert_eq!(df.get(1).expect("two lines")[1], Data::Integer(5));
+
+        //the frame keeps the rules for appended rows
+        let df = df.append_csv_row("3 4,6", None).expect("the row fits");
+        assert_eq!(
+            df.get(2).expect("three lines")[0],
+            Data::String(Box::new("3 4".to_owned()))
+        );
+        assert_eq!(
+            Data::parse_with(&rules, "1 2"),
+            Data::String(Box::new("1 2".to_owned()))
+        );
+    }
+
+    #[test]
+    fn brackets_stay_strings_without_vectors() {
+        let rules = ParseRules {
+            vectors: false,
+            ..ParseRules::default()
+        };
+        assert_eq!(
+            Data::parse_with(&rules, "[1,2]"),
+            Data::String(Box::new("[1,2]".to_owned()))
+        );
+        assert_eq!(
+            Data::parse_with(&ParseRules::default(), "[1,2]"),
+            Data::Vector(Box::new(vec![Data::Integer(1), Data::Integer(2)]))
+        );
+        //Vec2D is still inferred
+        assert_eq!(Data::parse_with(&rules, "1 2"), Data::Vec2D((1.0, 2.0)));
+    }
+
+    fn nested_cells() -> Vec<Data> {
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        let vector = |cells: Vec<Data>| Data::Vector(Box::new(cells));
+        let leaves = vec![
+            Data::Null,
+            Data::Integer(-4),
+            Data::Float(1.5),
+            Data::Float(-0.0),
+            Data::Float(1e-7),
+            Data::Float(f32::MAX),
+            Data::Boolean(true),
+            Data::Vec2D((1.5, 2.25)),
+            Data::Vec2D((-1.0, 1e10)),
+            string("a b"),
+            string("1 2"),
+            string("1"),
+            string("true"),
+            string(""),
+            string(" padded "),
+            string("[x]"),
+            string("(y)"),
+            string("a,b"),
+            string("say \"hi\""),
+        ];
+        let mut cells = leaves.clone();
+        cells.push(vector(Vec::new()));
+        cells.push(vector(vec![vector(vec![vector(Vec::new())])]));
+        //[(1 2), [3, "a b"], 4]
+        cells.push(vector(vec![
+            vector(vec![Data::Vec2D((1.0, 2.0))]),
+            vector(vec![Data::Integer(3), string("a b")]),
+            Data::Integer(4),
+        ]));
+        for leaf in leaves.iter() {
+            cells.push(vector(vec![leaf.clone()]));
+            cells.push(vector(vec![leaf.clone(), leaf.clone()]));
+            cells.push(vector(vec![
+                Data::Integer(1),
+                vector(vec![leaf.clone(), vector(vec![leaf.clone()])]),
+            ]));
+        }
+        cells
+    }
+
+    #[test]
+    fn nested_cells_round_trip() {
+        let rules = ParseRules::default();
+        for cell in nested_cells() {
+            let text = cell.to_csv_string(rules.seperator);
+            assert_eq!(Data::parse_with(&rules, &text), cell, "{text}");
+        }
+    }
+
+    fn column(df: &DataFrame, index: usize) -> Vec<Data> {
+        df.iter().map(|line| line[index].clone()).collect()
+    }
+
+    #[test]
+    fn nested_cells_round_trip_through_a_frame() {
+        let cells = nested_cells();
+        let rows = cells
+            .iter()
+            .map(|cell| vec![cell.clone(), Data::Integer(0), cell.clone()]);
+        let df = DataFrame::new(vec!["a", "b", "c"]).append_lines(rows);
+        let read = DataFrame::from_string(df.to_csv_string(), None).expect("the text is valid");
+        assert_eq!(column(&read, 0), cells);
+        assert_eq!(column(&read, 2), cells);
+
+        let path = std::env::temp_dir().join(format!("rundas_nested_{}.csv", std::process::id()));
+        for seperator in [';', '\t', '|'] {
+            df.to_file(&path, Some(seperator))
+                .expect("the temp dir is writable");
+            let read =
+                DataFrame::from_file(&path, Some(seperator)).expect("the file was just written");
+            assert_eq!(column(&read, 0), cells, "{seperator:?}");
+            assert_eq!(column(&read, 2), cells, "{seperator:?}");
         }
+        std::fs::remove_file(&path).expect("the file was just written");
     }
 }
@@ -1,4 +1,21 @@
-use super::{BaseDataFrame, Data, DataFrame, InnerDataFrame};
+use std::collections::{HashMap, HashSet};
+
+use super::string_cache::intern_categories;
+use super::{
+    AppendError, BaseDataFrame, Data, DataFrame, DataFrameColumnIndex, InnerDataFrame, JoinError,
+    JoinNaming, Line, ShapeError,
+};
+
+///a column whose cells were cast by append_data_frame_coerced
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Coercion {
+    pub column: String,
+    ///the dominant type of the column in self
+    pub left: &'static str,
+    ///the dominant type of the column in the appended frame
+    pub right: &'static str,
+    pub to: &'static str,
+}
 
 impl DataFrame {
     pub fn append_line(self, line: Vec<Data>) -> DataFrame {
@@ -19,11 +36,219 @@ impl DataFrame {
         InnerDataFrame::Base { df: base }.into()
     }
 
+    ///puts the columns of other right of the columns of self, line by line in visible order,
+    ///both frames need the same number of lines and names both have are handled like in a join
+    pub fn concat_columns(
+        self,
+        other: DataFrame,
+        naming: JoinNaming,
+    ) -> Result<DataFrame, ShapeError> {
+        if self.len() != other.len() {
+            return Err(ShapeError::ColumnLength {
+                expected: self.len(),
+                found: other.len(),
+            });
+        }
+        let left: Vec<&str> = self.header().collect();
+        let right: Vec<&str> = other.header().collect();
+        let layout = naming
+            .layout(&left, &right, None)
+            .map_err(|err| match err {
+                JoinError::DuplicateColumn { name } => ShapeError::DuplicateColumn { name },
+                JoinError::TooManyRows { .. } => unreachable!("layout only checks names"),
+            })?;
+
+        let data = self.iter().zip(other.iter()).map(|(left, right)| {
+            let mut line = left.select(&layout.left);
+            line.extend(right.select(&layout.right));
+            line
+        });
+        Ok(DataFrame::new(layout.header.clone()).append_lines(data))
+    }
+
+    ///like append_data_frame but a column whose dominant type differs between the frames is
+    ///widened first: Integer and Float become Float, any other pair becomes String if
+    ///fallback_to_string is set and is an error otherwise
+    ///
+    ///only cells of the widened type are cast, so a column that was mixed in one frame stays mixed
+    pub fn append_data_frame_coerced(
+        self,
+        other: DataFrame,
+        fallback_to_string: bool,
+    ) -> Result<(DataFrame, Vec<Coercion>), AppendError> {
+        let header: Vec<String> = self.header().map(|string| string.to_owned()).collect();
+        if !other
+            .header()
+            .eq(header.iter().map(|string| string.as_str()))
+        {
+            return Err(AppendError::HeaderMismatch {
+                left: header,
+                right: other.header().map(|string| string.to_owned()).collect(),
+            });
+        }
+
+        let mut coercions = Vec::new();
+        for (index, column) in header.iter().enumerate() {
+            let (Some(left), Some(right)) = (self.dominant_type(index), other.dominant_type(index))
+            else {
+                continue;
+            };
+            let to = match (left, right) {
+                _ if left == right => continue,
+                //a Category is a String stored in a StringCache
+                ("Category", "String") | ("String", "Category") => continue,
+                ("Integer", "Float") | ("Float", "Integer") => "Float",
+                _ if fallback_to_string => "String",
+                _ => {
+                    return Err(AppendError::IncompatibleTypes {
+                        column: column.clone(),
+                        left,
+                        right,
+                    })
+                }
+            };
+            coercions.push(Coercion {
+                column: column.clone(),
+                left,
+                right,
+                to,
+            });
+        }
+
+        let coerce = |df: DataFrame| -> DataFrame {
+            if coercions.is_empty() {
+                return df;
+            }
+            let mut base = BaseDataFrame::from(df);
+            for coercion in coercions.iter() {
+                let index = header
+                    .iter()
+                    .position(|column| *column == coercion.column)
+                    .expect("unreachable since the coercions are built from the header");
+                for line in base.data.iter_mut() {
+                    line[index] = match (coercion.to, &line[index]) {
+                        ("Float", Data::Integer(int)) => Data::Float(*int as f32),
+                        ("String", Data::Null | Data::String(_) | Data::Category(_))
+                        | ("Float", _) => {
+                            continue;
+                        }
+                        (_, data) => Data::String(Box::new(data.as_string())),
+                    };
+                }
+            }
+            InnerDataFrame::Base { df: base }.into()
+        };
+        let df = coerce(self).append_data_frame(coerce(other));
+        Ok((df, coercions))
+    }
+
+    ///the most common type of the non Null cells of the column, ties go to the smaller name
+    pub(super) fn dominant_type(&self, index: usize) -> Option<&'static str> {
+        let mut counts: HashMap<&'static str, usize> = HashMap::new();
+        for line in self.iter() {
+            if !line[index].is_null() {
+                *counts.entry(line[index].type_name()).or_default() += 1;
+            }
+        }
+        counts
+            .into_iter()
+            .max_by(|(a_name, a_count), (b_name, b_count)| {
+                a_count.cmp(b_count).then(b_name.cmp(a_name))
+            })
+            .map(|(name, _count)| name)
+    }
+
     pub fn append_column(self, header: &str, column: Vec<Data>) -> DataFrame {
         let mut base = BaseDataFrame::from(self);
         base.append_column(header, column);
         InnerDataFrame::Base { df: base }.into()
     }
+
+    ///like append_column but a column of the wrong lenght is an error,
+    ///the values belong to the lines in the order they are visible in self
+    pub fn add_column(
+        self,
+        name: impl Into<String>,
+        values: Vec<Data>,
+    ) -> Result<DataFrame, ShapeError> {
+        if values.len() != self.len() {
+            return Err(ShapeError::ColumnLength {
+                expected: self.len(),
+                found: values.len(),
+            });
+        }
+        Ok(self.append_column(&name.into(), values))
+    }
+
+    ///appends a copy of a column of other whose lines belong to the lines of self in visible order,
+    ///named new_name or like in other. a name self already has is an error
+    pub fn adopt_column<I>(
+        self,
+        other: &DataFrame,
+        other_column: I,
+        new_name: Option<&str>,
+    ) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = other_column.get_usize(other.header());
+        let name = match new_name {
+            Some(name) => name,
+            None => other.get_on_header(index).expect("index out of bound"),
+        };
+        if self.header().any(|head_elem| head_elem == name) {
+            return Err(ShapeError::DuplicateColumn {
+                name: name.to_owned(),
+            });
+        }
+        let values = other.column(index).cloned().collect();
+        self.add_column(name, values)
+    }
+
+    ///a name that another column already has is an error
+    pub fn rename_column<I>(
+        self,
+        index: I,
+        new_name: impl Into<String>,
+    ) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let new_name = new_name.into();
+        self.rename_columns(&[(index, new_name.as_str())])
+    }
+
+    ///all pairs of (column, new name) are applied at once, so names can be swapped,
+    ///but the resulting header must not contain a name twice
+    pub fn rename_columns<I>(self, renames: &[(I, &str)]) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let mut header: Vec<String> = self.header().map(|string| string.to_owned()).collect();
+        for (index, new_name) in renames {
+            let index = index.get_usize(self.header());
+            header[index] = new_name.to_string();
+        }
+        let mut seen = HashSet::new();
+        if let Some(name) = header.iter().find(|name| !seen.insert(*name)) {
+            return Err(ShapeError::DuplicateColumn { name: name.clone() });
+        }
+
+        let mut base = BaseDataFrame::from(self);
+        base.header = header;
+        Ok(InnerDataFrame::Base { df: base }.into())
+    }
+
+    ///appends a column with f evaluated once for every line in visible order,
+    ///f sees the columns of this view, see with_computed_column for a lazy version
+    pub fn with_column<F>(self, name: &str, f: F) -> DataFrame
+    where
+        F: FnMut(Line) -> Data,
+    {
+        let column: Vec<Data> = self.iter().map(f).collect();
+        self.append_column(name, column)
+    }
 }
 
 impl BaseDataFrame {
@@ -36,9 +261,18 @@ impl BaseDataFrame {
         lines.for_each(|line| self.append_line(line));
     }
 
+    ///Categories of other are copied into the cache of self if they are stored in another one
     pub(super) fn append_data_frame(&mut self, other: DataFrame) {
         assert!(self.has_same_header(&other));
-        self.append_lines(BaseDataFrame::from(other).data.drain(..));
+        let mut other = BaseDataFrame::from(other);
+        match (&self.string_cache, &other.string_cache) {
+            (Some(cache), Some(other_cache)) if !cache.same_as(other_cache) => {
+                intern_categories(&mut other.data, cache)
+            }
+            (None, Some(other_cache)) => self.string_cache = Some(other_cache.clone()),
+            _ => {}
+        }
+        self.append_lines(other.data.drain(..));
     }
 
     fn append_column(&mut self, header: &str, mut column: Vec<Data>) {
@@ -51,6 +285,14 @@ impl BaseDataFrame {
         self.identity_index_map.push(self.identity_index_map.len());
     }
 
+    pub(super) fn replace_column(&mut self, index: usize, column: Vec<Data>) {
+        assert_eq!(self.data.len(), column.len());
+        self.data
+            .iter_mut()
+            .zip(column)
+            .for_each(|(line, new_elem)| line[index] = new_elem);
+    }
+
     fn has_same_header(&self, other: &DataFrame) -> bool {
         let mut self_header_iter = self.header.iter();
         let mut other_header_iter = other.header();
@@ -73,3 +315,230 @@ impl BaseDataFrame {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StringCache;
+
+    fn frame() -> DataFrame {
+        let rows = (0..6).map(|line| vec![Data::Integer(line), Data::Integer(line * 10)]);
+        DataFrame::new(vec!["id", "value"]).append_lines(rows)
+    }
+
+    #[test]
+    fn added_column_follows_the_visible_lines() {
+        let df = frame()
+            .filter(|line| line["id"].as_integer() % 2 == 1)
+            .sort(|line| -line["id"].as_integer())
+            .drop_column("value");
+        let names = ["five", "three", "one"].map(|name| Data::String(Box::new(name.to_owned())));
+        let df = df
+            .add_column("name", names.to_vec())
+            .expect("the column fits");
+
+        assert_eq!(df.header().collect::<Vec<_>>(), ["id", "name"]);
+        let lines: Vec<(i32, Data)> = df
+            .iter()
+            .map(|line| (line["id"].as_integer(), line["name"].clone()))
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (5, names[0].clone()),
+                (3, names[1].clone()),
+                (1, names[2].clone())
+            ]
+        );
+    }
+
+    fn words(words: &[&str]) -> DataFrame {
+        let rows = words
+            .iter()
+            .map(|word| vec![Data::String(Box::new((*word).to_owned()))]);
+        DataFrame::new(vec!["value"]).append_lines(rows)
+    }
+
+    #[test]
+    fn concat_columns_reads_both_halves_in_visible_order() {
+        let left = frame().filter(|line| line["id"].as_integer() < 3);
+        let right = words(&["z", "y", "x"]).sort(|line| line[0].as_string());
+        let df = left
+            .concat_columns(right, JoinNaming::default())
+            .expect("both frames have three lines");
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            ["id", "value", "value_right"]
+        );
+        let lines: Vec<(i32, i32, String)> = df
+            .iter()
+            .map(|line| {
+                let (id, value) = (line["id"].as_integer(), line["value"].as_integer());
+                (id, value, line["value_right"].as_string())
+            })
+            .collect();
+        assert_eq!(
+            lines,
+            [
+                (0, 0, "x".to_owned()),
+                (1, 10, "y".to_owned()),
+                (2, 20, "z".to_owned())
+            ]
+        );
+
+        let df = frame()
+            .head(3)
+            .concat_columns(words(&["a", "b", "c"]), JoinNaming::PreferRight)
+            .expect("both frames have three lines");
+        assert_eq!(df.header().collect::<Vec<_>>(), ["id", "value"]);
+        assert_eq!(
+            df.get(2).expect("the frame has three lines")[1].as_string(),
+            "c"
+        );
+    }
+
+    #[test]
+    fn concat_columns_checks_lenght_and_names() {
+        let err = frame().concat_columns(words(&["a"]), JoinNaming::default());
+        assert_eq!(
+            err.err(),
+            Some(ShapeError::ColumnLength {
+                expected: 6,
+                found: 1
+            })
+        );
+        let err = frame()
+            .head(1)
+            .concat_columns(words(&["a"]), JoinNaming::Error);
+        assert_eq!(
+            err.err(),
+            Some(ShapeError::DuplicateColumn {
+                name: "value".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn adopted_column_outlives_its_source() {
+        let source = words(&["f", "e", "d", "c", "b", "a"]).sort(|line| line[0].as_string());
+        let weak = std::sync::Arc::downgrade(&source.inner);
+        let df = frame()
+            .adopt_column(&source, "value", Some("word"))
+            .expect("both frames have six lines");
+        drop(source);
+        assert!(weak.upgrade().is_none());
+
+        assert_eq!(df.header().collect::<Vec<_>>(), ["id", "value", "word"]);
+        let adopted: Vec<String> = df.column("word").map(|data| data.as_string()).collect();
+        assert_eq!(adopted, ["a", "b", "c", "d", "e", "f"]);
+
+        let err = frame().adopt_column(&words(&["a"]), 0, Some("word"));
+        assert_eq!(
+            err.err(),
+            Some(ShapeError::ColumnLength {
+                expected: 6,
+                found: 1
+            })
+        );
+        let err = frame().adopt_column(&frame(), "value", None);
+        assert_eq!(
+            err.err(),
+            Some(ShapeError::DuplicateColumn {
+                name: "value".to_owned()
+            })
+        );
+    }
+
+    #[test]
+    fn column_of_the_wrong_lenght_is_an_error() {
+        let Err(err) = frame().add_column("new_col", vec![Data::Null; 5]) else {
+            panic!("the column is too short");
+        };
+        assert_eq!(
+            err,
+            ShapeError::ColumnLength {
+                expected: 6,
+                found: 5
+            }
+        );
+        assert_eq!(
+            err.to_string(),
+            "the column has 5 entries but the frame has 6 lines"
+        );
+    }
+
+    fn temperatures(values: Vec<Data>) -> DataFrame {
+        DataFrame::new(vec!["temperature"]).append_lines(values.into_iter().map(|data| vec![data]))
+    }
+
+    fn column(df: &DataFrame) -> Vec<Data> {
+        df.iter().map(|line| line[0].clone()).collect()
+    }
+
+    #[test]
+    fn integers_are_widened_to_floats() {
+        let january = temperatures(vec![Data::Integer(1), Data::Null, Data::Integer(-3)]);
+        let february = temperatures(vec![Data::Float(2.5), Data::Integer(4), Data::Float(0.5)]);
+        let (df, coercions) = january
+            .append_data_frame_coerced(february, false)
+            .expect("Integer and Float are compatible");
+        assert_eq!(
+            coercions,
+            [Coercion {
+                column: "temperature".to_owned(),
+                left: "Integer",
+                right: "Float",
+                to: "Float",
+            }]
+        );
+        let float = Data::Float;
+        assert_eq!(
+            column(&df),
+            [
+                float(1.0),
+                Data::Null,
+                float(-3.0),
+                float(2.5),
+                float(4.0),
+                float(0.5)
+            ]
+        );
+    }
+
+    #[test]
+    fn incompatible_types_name_the_column() {
+        let flags = || temperatures(vec![Data::Boolean(true)]);
+        let floats = || temperatures(vec![Data::Float(2.5)]);
+        let Err(err) = flags().append_data_frame_coerced(floats(), false) else {
+            panic!("Boolean and Float are incompatible");
+        };
+        assert_eq!(
+            err,
+            AppendError::IncompatibleTypes {
+                column: "temperature".to_owned(),
+                left: "Boolean",
+                right: "Float",
+            }
+        );
+
+        let (df, coercions) = flags()
+            .append_data_frame_coerced(floats(), true)
+            .expect("both become Strings");
+        assert_eq!(coercions[0].to, "String");
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        assert_eq!(column(&df), [string("true"), string("2.5")]);
+    }
+
+    #[test]
+    fn categories_and_strings_are_compatible() {
+        let cache = StringCache::new();
+        let categories =
+            temperatures(vec![Data::String(Box::new("warm".to_owned()))]).categorize(0, &cache);
+        let strings = temperatures(vec![Data::String(Box::new("cold".to_owned()))]);
+        let (df, coercions) = categories
+            .append_data_frame_coerced(strings, false)
+            .expect("a Category is a String");
+        assert!(coercions.is_empty());
+        assert_eq!(df.len(), 2);
+    }
+}
@@ -1,65 +1,534 @@
 use std::{
-    collections::{hash_map::Drain, HashMap},
+    cmp::Reverse,
+    collections::HashMap,
+    fmt::{Debug, Display, Formatter, Result as FmtResult},
     hash::Hash,
+    iter::repeat_n,
     ops::{Index, IndexMut},
+    sync::Arc,
 };
 
-use super::DataFrame;
+use super::{Agg, ColumnMaps, Data, DataFrame, DataFrameColumnIndex, InnerDataFrame, Line};
 
+#[derive(Debug, Clone, PartialEq)]
+pub struct DistributionEntry {
+    pub group_size: usize,
+    pub num_groups: u32,
+    pub fraction_of_groups: f64,
+    pub fraction_of_rows: f64,
+    ///the lines in all groups up to and including this size
+    pub cumulative_rows: usize,
+}
+
+const HISTOGRAM_WIDTH: f64 = 40.0;
+
+///a line of a histogram, the bar is as long as fraction_of_groups of 40 chars
+impl Display for DistributionEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        let bar = "#".repeat((self.fraction_of_groups * HISTOGRAM_WIDTH).round() as usize);
+        write!(
+            f,
+            "{:>8} | {:<width$} {} ({:.1}% of lines)",
+            self.group_size,
+            bar,
+            self.num_groups,
+            self.fraction_of_rows * 100.0,
+            width = HISTOGRAM_WIDTH as usize
+        )
+    }
+}
+
+///the groups keep an order, keys holds the keys in that order
+///and the position of a key in frames is stored with the key
 pub struct Groups<G: Eq + Hash> {
-    groups: HashMap<G, DataFrame>,
+    positions: HashMap<Arc<G>, usize>,
+    keys: Vec<Arc<G>>,
+    frames: Vec<DataFrame>,
 }
 
 impl<G: Eq + Hash> Groups<G> {
-    pub(super) fn new(groups: HashMap<G, DataFrame>) -> Groups<G> {
-        Groups { groups }
+    ///the groups are kept in the order of groups, each key must be unique
+    pub(super) fn new(groups: impl IntoIterator<Item = (G, DataFrame)>) -> Groups<G> {
+        let mut new = Groups {
+            positions: HashMap::new(),
+            keys: Vec::new(),
+            frames: Vec::new(),
+        };
+        for (key, group) in groups {
+            assert!(
+                !new.positions.contains_key(&key),
+                "the keys of groups must be unique"
+            );
+            new.push(key, group);
+        }
+        new
+    }
+
+    ///the groups in the order of their position in frames
+    fn from_positions(positions: HashMap<G, usize>, frames: Vec<DataFrame>) -> Groups<G> {
+        let mut keys: Vec<Option<G>> = frames.iter().map(|_| None).collect();
+        for (key, position) in positions {
+            keys[position] = Some(key);
+        }
+        Groups::new(
+            keys.into_iter()
+                .map(|key| key.expect("every position has a key"))
+                .zip(frames),
+        )
+    }
+
+    ///adds a group after all others, the key must not have a group yet
+    fn push(&mut self, key: G, group: DataFrame) {
+        let key = Arc::new(key);
+        self.positions.insert(key.clone(), self.frames.len());
+        self.keys.push(key);
+        self.frames.push(group);
+    }
+
+    ///the number of groups
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
     }
 
     ///(group lenght, number of groups with that lenght)
     pub fn distribution(&self) -> Vec<(usize, u32)> {
+        self.distribution_ext()
+            .iter()
+            .map(|entry| (entry.group_size, entry.num_groups))
+            .collect()
+    }
+
+    ///one entry per group lenght, ordered by the lenght
+    pub fn distribution_ext(&self) -> Vec<DistributionEntry> {
         let mut map = HashMap::new();
-        for (_key, group) in self.groups.iter() {
+        for group in self.frames.iter() {
             let num: &mut u32 = map.entry(group.len()).or_default();
             *num += 1;
         }
         let mut map = map.drain().collect::<Vec<_>>();
         map.sort_by_key(|(a, _b)| *a);
 
-        map
+        let num_groups = self.frames.len() as f64;
+        let num_rows: usize = map.iter().map(|(size, num)| size * *num as usize).sum();
+        let mut cumulative_rows = 0;
+        map.iter()
+            .map(|(group_size, num_groups_of_size)| {
+                let rows = group_size * *num_groups_of_size as usize;
+                cumulative_rows += rows;
+                DistributionEntry {
+                    group_size: *group_size,
+                    num_groups: *num_groups_of_size,
+                    fraction_of_groups: *num_groups_of_size as f64 / num_groups,
+                    fraction_of_rows: if num_rows == 0 {
+                        0.0
+                    } else {
+                        rows as f64 / num_rows as f64
+                    },
+                    cumulative_rows,
+                }
+            })
+            .collect()
     }
 
     pub fn filter<F>(mut self, mut filter: F) -> Groups<G>
     where
         F: FnMut((&G, &DataFrame)) -> bool,
     {
-        self.groups.drain_filter(|key, group| !filter((key, group)));
+        let groups: Vec<_> = self
+            .drain()
+            .filter(|(key, group)| filter((key, group)))
+            .collect();
+        Groups::new(groups)
+    }
+
+    ///groups whose keys collide after mapping are merged into one group,
+    ///it takes the place of the first of them
+    pub fn map_keys<H, F>(mut self, mut f: F) -> Groups<H>
+    where
+        H: Eq + Hash,
+        F: FnMut(G) -> H,
+    {
+        let mut positions = HashMap::new();
+        let mut merged: Vec<Vec<DataFrame>> = Vec::new();
+        for (key, group) in self.drain() {
+            let position = *positions.entry(f(key)).or_insert_with(|| {
+                merged.push(Vec::new());
+                merged.len() - 1
+            });
+            merged[position].push(group);
+        }
+
+        let frames = merged.drain(..).map(DataFrame::merge_groups).collect();
+        Groups::from_positions(positions, frames)
+    }
+
+    ///like map_keys but returns the first key that was produced twice instead of merging
+    pub fn map_keys_no_merge<H, F>(mut self, mut f: F) -> Result<Groups<H>, H>
+    where
+        H: Eq + Hash,
+        F: FnMut(G) -> H,
+    {
+        let mut positions = HashMap::with_capacity(self.len());
+        let mut frames = Vec::with_capacity(self.len());
+        for (key, group) in self.drain() {
+            let key = f(key);
+            if positions.contains_key(&key) {
+                return Err(key);
+            }
+            positions.insert(key, frames.len());
+            frames.push(group);
+        }
+        Ok(Groups::from_positions(positions, frames))
+    }
+
+    ///concatenates all groups into one frame with the key of each line in a leading column,
+    ///DataFrame::group_by_column_as_keys reverses this
+    pub fn to_long_frame(&self, key_column_name: &str) -> DataFrame
+    where
+        G: Into<Data> + Clone,
+    {
+        let mut header = vec![key_column_name.to_owned()];
+        if let Some(group) = self.frames.first() {
+            header.extend(group.header().map(|string| string.to_owned()));
+        }
+
+        let data = self.iter().flat_map(|(key, group)| {
+            let key: Data = key.clone().into();
+            group.iter().map(move |line| {
+                let mut new_line = Vec::with_capacity(line.iter().count() + 1);
+                new_line.push(key.clone());
+                new_line.extend(line.iter().cloned());
+                new_line
+            })
+        });
+        DataFrame::new(header).append_lines(data)
+    }
+
+    ///like to_long_frame but consumes the groups so the keys do not need to be Clone,
+    ///without any group the frame only has the key column
+    pub fn into_data_frame(mut self, key_column_name: &str) -> DataFrame
+    where
+        G: Into<Data>,
+    {
+        let mut header = vec![key_column_name.to_owned()];
+        if let Some(group) = self.frames.first() {
+            header.extend(group.header().map(|string| string.to_owned()));
+        }
+
+        let mut data = Vec::new();
+        for (key, group) in self.drain() {
+            let key: Data = key.into();
+            data.extend(group.iter().map(|line| {
+                let mut new_line = Vec::with_capacity(line.iter().count() + 1);
+                new_line.push(key.clone());
+                new_line.extend(line.iter().cloned());
+                new_line
+            }));
+        }
+        DataFrame::new(header).append_lines(data.into_iter())
+    }
+
+    ///one line per group with its key followed by one column per spec named
+    ///"{column}_{agg}" (e.g. "price_mean"), in the order of the groups
+    pub fn agg(&self, key_column_name: &str, specs: &[(&str, Agg)]) -> DataFrame
+    where
+        G: Into<Data> + Clone,
+    {
+        let mut header = vec![key_column_name.to_owned()];
+        header.extend(
+            specs
+                .iter()
+                .map(|(column, agg)| format!("{column}_{}", agg.name())),
+        );
+
+        let data = self.iter().map(|(key, group)| {
+            let mut line = Vec::with_capacity(specs.len() + 1);
+            line.push(key.clone().into());
+            line.extend(specs.iter().map(|(column, agg)| {
+                let index = column.get_usize(group.header());
+                let values: Vec<Data> = group.iter().map(|line| line[index].clone()).collect();
+                agg.apply(&values)
+            }));
+            line
+        });
+        DataFrame::new(header).append_lines(data)
+    }
+
+    ///one line per group with its key and the values of column in the group
+    ///collected into a Vector in line order, DataFrame::implode_by does this in one call
+    pub fn collect_column<I>(&self, key_column_name: &str, column: I, out_name: &str) -> DataFrame
+    where
+        G: Into<Data> + Clone,
+        I: DataFrameColumnIndex,
+    {
+        let data = self.iter().map(|(key, group)| {
+            let index = column.get_usize(group.header());
+            let values = group.iter().map(|line| line[index].clone()).collect();
+            vec![key.clone().into(), Data::Vector(Box::new(values))]
+        });
+        DataFrame::new(vec![key_column_name, out_name]).append_lines(data)
+    }
+
+    ///one line per group with its key and the number of its lines in a column "size"
+    pub fn keys_frame(&self) -> DataFrame
+    where
+        G: Into<Data> + Clone,
+    {
+        let data = self
+            .iter()
+            .map(|(key, group)| vec![key.clone().into(), Data::Integer(group.len() as i32)]);
+        DataFrame::new(vec!["key", "size"]).append_lines(data)
+    }
+
+    ///the lines of all groups with the value stat computes for their group in a new column,
+    ///in the order of the frame they were grouped from
+    ///
+    ///if the groups are not views of the same frame (e.g. after insert_lines) the lines follow
+    ///the order of the groups, without any group the frame only has the new column
+    pub fn annotate<F>(self, out_name: &str, mut stat: F) -> DataFrame
+    where
+        F: FnMut(&G, &DataFrame) -> Data,
+    {
+        let values: Vec<Data> = self.iter().map(|(key, group)| stat(key, group)).collect();
+
+        if let Some((df, mut lines)) = self.shared_lines() {
+            lines.sort_unstable_by_key(|(index, _group)| *index);
+            let column = lines
+                .iter()
+                .map(|(_index, group)| values[*group].clone())
+                .collect();
+            let index_map: Vec<usize> = lines.into_iter().map(|(index, _group)| index).collect();
+            let df: DataFrame = InnerDataFrame::LineReorder {
+                df,
+                index_map: index_map.into(),
+            }
+            .into();
+            return df.append_column(out_name, column);
+        }
+
+        let column = self
+            .frames
+            .iter()
+            .zip(values)
+            .flat_map(|(group, value)| repeat_n(value, group.len()))
+            .collect();
+        self.frames
+            .into_iter()
+            .reduce(DataFrame::concat)
+            .unwrap_or_else(DataFrame::empty)
+            .append_column(out_name, column)
+    }
+
+    ///the frame below the views of all groups (with the column view they share on top)
+    ///and (line of that frame, position of the group) for all lines,
+    ///None if the groups are not views of one frame
+    fn shared_lines(&self) -> Option<(DataFrame, Vec<(usize, usize)>)> {
+        let mut shared: Option<(DataFrame, Option<ColumnMaps>)> = None;
+        let mut lines = Vec::new();
+        for (position, group) in self.frames.iter().enumerate() {
+            let (df, index_map, columns) = group.flatten_views();
+            match &shared {
+                Some((shared_df, shared_columns))
+                    if !Arc::ptr_eq(&shared_df.inner, &df.inner) || *shared_columns != columns =>
+                {
+                    return None
+                }
+                Some(_) => {}
+                None => shared = Some((df.clone(), columns)),
+            }
+            let index_map = index_map.unwrap_or_else(|| (0..df.len()).into());
+            lines.extend(index_map.iter().map(|index| (index, position)));
+        }
+
+        let (df, columns) = shared?;
+        let df = match columns {
+            Some((index_map, line_map)) => InnerDataFrame::ColumnReorder {
+                df,
+                index_map,
+                line_map,
+            }
+            .into(),
+            None => df,
+        };
+        Some((df, lines))
+    }
+
+    ///groups the lines of additions and appends them to the matching groups,
+    ///the existing lines are not touched so the work only depends on the size of additions,
+    ///new groups are added at the end
+    pub fn insert_lines<F>(&mut self, additions: DataFrame, grouper: F)
+    where
+        F: FnMut(Line) -> G,
+    {
+        if let Some(group) = self.frames.first() {
+            assert!(
+                group.header().eq(additions.header()),
+                "additions must have the header of the groups"
+            );
+        }
+
+        for (key, part) in additions.group_by(grouper).drain() {
+            match self.positions.get(&key) {
+                Some(position) => {
+                    let group = &mut self.frames[*position];
+                    let old = std::mem::replace(group, DataFrame::empty());
+                    *group = old.concat(part);
+                }
+                None => self.push(key, part),
+            }
+        }
+    }
+
+    ///orders the groups by their keys
+    pub fn sorted_by_key(mut self) -> Groups<G>
+    where
+        G: Ord,
+    {
+        let mut order: Vec<usize> = (0..self.len()).collect();
+        order.sort_by(|a, b| self.keys[*a].cmp(&self.keys[*b]));
+        self.reorder(order);
+        self
+    }
+
+    ///orders the groups by their number of lines, groups of equal size keep their order
+    pub fn sorted_by_size(self, descending: bool) -> Groups<G> {
+        if descending {
+            self.sorted_by(|_key, group| Reverse(group.len()))
+        } else {
+            self.sorted_by(|_key, group| group.len())
+        }
+    }
+
+    ///orders the groups by the value f computes for each of them,
+    ///groups with equal values keep their order
+    pub fn sorted_by<F, K>(mut self, mut f: F) -> Groups<G>
+    where
+        F: FnMut(&G, &DataFrame) -> K,
+        K: Ord,
+    {
+        let values: Vec<K> = self.iter().map(|(key, group)| f(key, group)).collect();
+        let mut order: Vec<usize> = (0..values.len()).collect();
+        order.sort_by(|a, b| values[*a].cmp(&values[*b]));
+        self.reorder(order);
         self
     }
 
+    ///the group at order[i] moves to position i, only the frames are moved not their data
+    fn reorder(&mut self, order: Vec<usize>) {
+        let mut new_positions = vec![0; order.len()];
+        for (new_position, old_position) in order.iter().enumerate() {
+            new_positions[*old_position] = new_position;
+        }
+        for position in self.positions.values_mut() {
+            *position = new_positions[*position];
+        }
+        self.keys = order
+            .iter()
+            .map(|old_position| self.keys[*old_position].clone())
+            .collect();
+
+        let mut frames: Vec<Option<DataFrame>> = self.frames.drain(..).map(Some).collect();
+        self.frames = order
+            .iter()
+            .map(|old_position| {
+                frames[*old_position]
+                    .take()
+                    .expect("order is a permutation")
+            })
+            .collect();
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = (&G, &DataFrame)> {
-        self.groups.iter()
+        self.keys
+            .iter()
+            .map(|key| key.as_ref())
+            .zip(self.frames.iter())
     }
 
     pub fn iter_mut(&mut self) -> impl Iterator<Item = (&G, &mut DataFrame)> {
-        self.groups.iter_mut()
+        self.keys
+            .iter()
+            .map(|key| key.as_ref())
+            .zip(self.frames.iter_mut())
+    }
+
+    pub fn drain(&mut self) -> impl Iterator<Item = (G, DataFrame)> + '_ {
+        self.positions.clear();
+        self.keys
+            .drain(..)
+            .map(|key| {
+                Arc::try_unwrap(key)
+                    .unwrap_or_else(|_| unreachable!("a key is only shared with positions"))
+            })
+            .zip(self.frames.drain(..))
+    }
+
+    pub fn get(&self, key: &G) -> Option<&DataFrame> {
+        self.positions
+            .get(key)
+            .map(|position| &self.frames[*position])
+    }
+
+    pub fn get_mut(&mut self, key: &G) -> Option<&mut DataFrame> {
+        self.positions
+            .get(key)
+            .map(|position| &mut self.frames[*position])
+    }
+
+    pub fn contains_key(&self, key: &G) -> bool {
+        self.positions.contains_key(key)
+    }
+
+    ///the keys in the order of the groups
+    pub fn keys(&self) -> impl Iterator<Item = &G> {
+        self.keys.iter().map(|key| key.as_ref())
     }
 
-    pub fn drain(&mut self) -> Drain<'_, G, DataFrame> {
-        self.groups.drain()
+    fn get_position(&self, key: &G) -> usize {
+        *self
+            .positions
+            .get(key)
+            .unwrap_or_else(|| match key.debug_form() {
+                Some(key) => panic!("index out of bound: there is no group with the key {key}"),
+                None => panic!("index out of bound: there is no group with that key"),
+            })
     }
 }
 
+///the Debug form of a key for the panic of Index, if the key type has one
+trait DebugForm {
+    fn debug_form(&self) -> Option<String>;
+}
+
+impl<G> DebugForm for G {
+    default fn debug_form(&self) -> Option<String> {
+        None
+    }
+}
+
+impl<G: Debug> DebugForm for G {
+    fn debug_form(&self) -> Option<String> {
+        Some(format!("{self:?}"))
+    }
+}
+
+///indexing panics for a key without a group, Groups::get returns None instead
 impl<G: Eq + Hash> Index<&G> for Groups<G> {
     type Output = DataFrame;
 
     fn index(&self, index: &G) -> &Self::Output {
-        self.groups.get(index).expect("index out ouf bound")
+        &self.frames[self.get_position(index)]
     }
 }
 
 impl<G: Eq + Hash> IndexMut<&G> for Groups<G> {
     fn index_mut(&mut self, index: &G) -> &mut Self::Output {
-        self.groups.get_mut(index).expect("index out ouf bound")
+        let position = self.get_position(index);
+        &mut self.frames[position]
     }
 }
 
@@ -67,12 +536,393 @@ impl<G: Eq + Hash> Index<G> for Groups<G> {
     type Output = DataFrame;
 
     fn index(&self, index: G) -> &Self::Output {
-        self.groups.get(&index).expect("index out ouf bound")
+        &self.frames[self.get_position(&index)]
     }
 }
 
 impl<G: Eq + Hash> IndexMut<G> for Groups<G> {
     fn index_mut(&mut self, index: G) -> &mut Self::Output {
-        self.groups.get_mut(&index).expect("index out ouf bound")
+        let position = self.get_position(&index);
+        &mut self.frames[position]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Deref;
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::data_frame::InnerDataFrame;
+    use crate::Data;
+
+    fn frame() -> DataFrame {
+        let rows = [3, 1, 2, 1, 3, 2]
+            .into_iter()
+            .enumerate()
+            .map(|(line, key)| vec![Data::Integer(key), Data::Integer(line as i32)]);
+        DataFrame::new(vec!["key", "line"]).append_lines(rows)
+    }
+
+    fn lines(df: &DataFrame) -> Vec<i32> {
+        df.iter().map(|line| line[1].as_integer()).collect()
+    }
+
+    fn keys<G: Eq + Hash + Copy>(groups: &Groups<G>) -> Vec<G> {
+        groups.iter().map(|(key, _group)| *key).collect()
+    }
+
+    #[test]
+    fn merged_groups_follow_the_group_order() {
+        let groups = frame()
+            .group_by(|line| line[0].as_integer())
+            .sorted_by_key();
+        let merged = groups.map_keys(|key| key != 2);
+
+        assert_eq!(keys(&merged), vec![true, false]);
+        //group 1 holds lines 1 and 3, group 3 holds lines 0 and 4
+        assert_eq!(lines(&merged[true]), vec![1, 3, 0, 4]);
+        assert_eq!(lines(&merged[false]), vec![2, 5]);
+    }
+
+    #[test]
+    fn groups_follow_each_sort() {
+        //key 1 has three lines, key 3 two and key 2 one
+        let rows = [3, 1, 2, 1, 3, 1]
+            .into_iter()
+            .enumerate()
+            .map(|(line, key)| vec![Data::Integer(key), Data::Integer(line as i32)]);
+        let df = DataFrame::new(vec!["key", "line"]).append_lines(rows);
+        let groups = df.group_by(|line| line[0].as_integer());
+
+        let groups = groups.sorted_by_key();
+        assert_eq!(keys(&groups), vec![1, 2, 3]);
+        let groups = groups.sorted_by_size(true);
+        assert_eq!(keys(&groups), vec![1, 3, 2]);
+        let groups = groups.sorted_by_size(false);
+        assert_eq!(keys(&groups), vec![2, 3, 1]);
+        let groups = groups.sorted_by(|key, _group| std::cmp::Reverse(*key));
+        assert_eq!(keys(&groups), vec![3, 2, 1]);
+        //equal values keep the order
+        let groups = groups.sorted_by(|_key, group| group.len() > 1);
+        assert_eq!(keys(&groups), vec![2, 3, 1]);
+
+        let mut groups = groups.filter(|(key, _group)| *key != 2);
+        assert_eq!(keys(&groups), vec![3, 1]);
+        assert_eq!(lines(&groups[3]), vec![0, 4]);
+        assert_eq!(lines(&groups[1]), vec![1, 3, 5]);
+        let drained: Vec<(i32, usize)> = groups
+            .drain()
+            .map(|(key, group)| (key, group.len()))
+            .collect();
+        assert_eq!(drained, vec![(3, 2), (1, 3)]);
+    }
+
+    #[test]
+    fn map_keys_no_merge_returns_the_colliding_key() {
+        let groups = frame().group_by(|line| line[0].as_integer());
+        assert_eq!(groups.map_keys_no_merge(|key| key % 2).err(), Some(1));
+    }
+
+    #[test]
+    fn distribution_of_one_group() {
+        let groups = frame().group_by(|_line| ());
+        assert_eq!(groups.distribution(), vec![(6, 1)]);
+        let entries = groups.distribution_ext();
+        assert_eq!(
+            entries,
+            vec![DistributionEntry {
+                group_size: 6,
+                num_groups: 1,
+                fraction_of_groups: 1.0,
+                fraction_of_rows: 1.0,
+                cumulative_rows: 6,
+            }]
+        );
+        assert_eq!(
+            entries[0].to_string(),
+            format!("{:>8} | {} 1 (100.0% of lines)", 6, "#".repeat(40))
+        );
+        assert!(frame()
+            .group_by(|_line| ())
+            .filter(|_| false)
+            .distribution_ext()
+            .is_empty());
+    }
+
+    #[test]
+    fn distribution_of_many_equal_groups() {
+        //100 groups of 3 lines and 1 group of 100 lines
+        let rows = (0..400).map(|line| vec![Data::Integer(line.min(300) / 3), Data::Integer(line)]);
+        let df = DataFrame::new(vec!["key", "line"]).append_lines(rows);
+        let groups = df.group_by(|line| line[0].as_integer());
+        assert_eq!(groups.distribution(), vec![(3, 100), (100, 1)]);
+
+        let entries = groups.distribution_ext();
+        assert_eq!(entries[0].fraction_of_groups, 100.0 / 101.0);
+        assert_eq!(entries[0].fraction_of_rows, 0.75);
+        assert_eq!(entries[0].cumulative_rows, 300);
+        assert_eq!(entries[1].fraction_of_groups, 1.0 / 101.0);
+        assert_eq!(entries[1].fraction_of_rows, 0.25);
+        assert_eq!(entries[1].cumulative_rows, 400);
+        assert!(entries[0]
+            .to_string()
+            .contains(&format!("{} 100 (75.0% of lines)", "#".repeat(40))));
+        //the bar of a single group rounds to nothing
+        let empty_bar = " ".repeat(40);
+        assert!(entries[1]
+            .to_string()
+            .ends_with(&format!("| {empty_bar} 1 (25.0% of lines)")));
+    }
+
+    fn rows(df: &DataFrame) -> Vec<Vec<Data>> {
+        df.iter()
+            .map(|line| line.iter().cloned().collect())
+            .collect()
+    }
+
+    #[test]
+    fn groups_follow_the_first_occurrence() {
+        //keys in a scrambled order, every key occurs several times
+        let line_keys: Vec<i32> = (0..2000).map(|line| (line * 7919 + 13) % 311).collect();
+        let mut first_occurrence = Vec::new();
+        for key in line_keys.iter() {
+            if !first_occurrence.contains(key) {
+                first_occurrence.push(*key);
+            }
+        }
+        let rows = line_keys
+            .iter()
+            .enumerate()
+            .map(|(line, key)| vec![Data::Integer(*key), Data::Integer(line as i32)]);
+        let df = DataFrame::new(vec!["key", "line"]).append_lines(rows);
+
+        for _run in 0..5 {
+            let mut groups = df.clone().group_by(|line| line[0].as_integer());
+            assert_eq!(keys(&groups), first_occurrence);
+            let mut_keys: Vec<i32> = groups.iter_mut().map(|(key, _group)| *key).collect();
+            assert_eq!(mut_keys, first_occurrence);
+            let drained: Vec<i32> = groups.drain().map(|(key, _group)| key).collect();
+            assert_eq!(drained, first_occurrence);
+        }
+    }
+
+    #[test]
+    fn agg_gives_one_line_per_group() {
+        let specs = [
+            ("line", Agg::Sum),
+            ("line", Agg::Last),
+            ("line", Agg::Mean),
+            ("line", Agg::Count),
+        ];
+        let df = frame()
+            .group_by(|line| line[0].as_integer())
+            .sorted_by_key()
+            .agg("key", &specs);
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            ["key", "line_sum", "line_last", "line_mean", "line_count"]
+        );
+        let (int, float) = (Data::Integer, Data::Float);
+        assert_eq!(
+            rows(&df),
+            [
+                vec![int(1), int(4), int(3), float(2.0), int(2)],
+                vec![int(2), int(7), int(5), float(3.5), int(2)],
+                vec![int(3), int(4), int(4), float(2.0), int(2)],
+            ]
+        );
+    }
+
+    #[test]
+    fn long_frame_round_trips() {
+        let rows_in = [("b", 1.5), ("a", 2.0), ("b", -3.0), ("c", 4.0), ("a", 5.5)]
+            .into_iter()
+            .map(|(key, value)| vec![Data::String(Box::new(key.to_owned())), Data::Float(value)]);
+        let df = DataFrame::new(vec!["name", "value"]).append_lines(rows_in);
+        //the group order differs from the order the keys appear in the frame
+        let groups = df.group_by_column_as_keys("name").sorted_by_size(false);
+        let long = groups.to_long_frame("name");
+        assert_eq!(long.header().collect::<Vec<_>>(), ["name", "value"]);
+        assert_eq!(long.len(), 5);
+        //the long frame follows the group order
+        let c = Data::String(Box::new("c".to_owned()));
+        assert_eq!(groups.iter().next().map(|(key, _group)| key), Some(&c));
+        assert_eq!(long.get(0).expect("the frame has lines")[0], c);
+
+        let path = std::env::temp_dir().join(format!("rundas_groups_{}.csv", std::process::id()));
+        long.to_file(&path, None).expect("the temp dir is writable");
+        let from_file = DataFrame::from_file(&path, None).expect("the file was just written");
+        std::fs::remove_file(&path).expect("the file was just written");
+        let path = path.with_extension("bin");
+        long.to_binary_file(&path)
+            .expect("the temp dir is writable");
+        let from_binary = DataFrame::from_binary_file(&path).expect("the file was just written");
+        std::fs::remove_file(&path).expect("the file was just written");
+
+        let back = long.group_by_column_as_keys("name");
+        let back_from_file = from_file.group_by_column_as_keys("name");
+        let back_from_binary = from_binary.group_by_column_as_keys(0);
+        assert_eq!(back.iter().count(), 3);
+        assert_eq!(back_from_file.iter().count(), 3);
+        assert_eq!(back_from_binary.iter().count(), 3);
+        for (key, group) in groups.iter() {
+            assert_eq!(rows(&back[key]), rows(group));
+            assert_eq!(rows(&back_from_file[key]), rows(group));
+            assert_eq!(rows(&back_from_binary[key]), rows(group));
+        }
+        //the lines of a group keep their order
+        let key = Data::String(Box::new("b".to_owned()));
+        assert_eq!(
+            rows(&back[&key]),
+            vec![vec![Data::Float(1.5)], vec![Data::Float(-3.0)]]
+        );
+    }
+
+    fn events(lines: std::ops::Range<i32>, keys: i32) -> DataFrame {
+        let rows = lines.map(|line| vec![Data::Integer(line % keys), Data::Integer(line)]);
+        DataFrame::new(vec!["key", "line"]).append_lines(rows)
+    }
+
+    #[test]
+    fn insert_lines_matches_a_full_regroup() {
+        let by_key = |line: Line| line[0].as_integer();
+        let mut groups = events(0..1000, 7).group_by(by_key);
+        //keys 0 to 8, so two groups are new
+        groups.insert_lines(events(1000..1100, 9), by_key);
+
+        let all = events(0..1000, 7).append_lines(rows(&events(1000..1100, 9)).into_iter());
+        let regrouped = all.group_by(by_key);
+        assert_eq!(groups.iter().count(), 9);
+        for (key, group) in regrouped.iter() {
+            assert_eq!(lines(&groups[*key]), lines(group), "group {key}");
+        }
+    }
+
+    #[test]
+    fn insert_lines_keeps_the_existing_groups() {
+        let by_key = |line: Line| line[0].as_integer();
+        let mut groups = events(0..1000, 10).group_by(by_key);
+        let before: Vec<(i32, DataFrame)> = (0..10).map(|key| (key, groups[key].clone())).collect();
+
+        //only keys 0 to 4 get lines
+        groups.insert_lines(events(0..5, 10), by_key);
+        for (key, group) in before {
+            match groups[key].inner.deref() {
+                InnerDataFrame::Concat { parts, .. } => {
+                    assert!(key < 5);
+                    assert!(Arc::ptr_eq(&parts[0].inner, &group.inner));
+                }
+                _ => {
+                    assert!(key >= 5);
+                    assert!(Arc::ptr_eq(&groups[key].inner, &group.inner));
+                }
+            }
+        }
+    }
+
+    ///a key type without Debug
+    #[derive(PartialEq, Eq, Hash)]
+    struct Parity(bool);
+
+    #[test]
+    fn keys_without_debug_can_index() {
+        let mut groups = frame().group_by(|line| Parity(line[0] == Data::Integer(2)));
+        assert_eq!(lines(&groups[Parity(true)]), vec![2, 5]);
+        assert_eq!(lines(&groups[&Parity(false)]), vec![0, 1, 3, 4]);
+        groups[Parity(true)] = DataFrame::empty();
+        assert!(groups[&Parity(true)].is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no group with that key")]
+    fn missing_key_panics() {
+        let groups = frame().group_by(|line| Parity(line[0] == Data::Integer(2)));
+        let groups = groups.filter(|(key, _group)| key.0);
+        let _ = &groups[Parity(false)];
+    }
+
+    #[test]
+    #[should_panic(expected = "there is no group with the key \"error\"")]
+    fn missing_key_panics_with_its_debug_form() {
+        let groups = frame().group_by(|line| format!("level {}", line[0]));
+        let _ = &groups["error".to_owned()];
+    }
+
+    #[test]
+    fn keys_follow_the_groups() {
+        let key = |line: Line| line[0].try_as_integer().expect("the keys are integers");
+        let mut groups = frame().group_by(key);
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![3, 1, 2]);
+
+        groups.insert_lines(frame().filter(|line| line[0] == Data::Integer(3)), key);
+        let additions = DataFrame::from_rows(
+            vec!["key", "line"],
+            vec![vec![Data::Integer(0), Data::Integer(6)]],
+        )
+        .expect("the rows fit the header");
+        groups.insert_lines(additions, key);
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![3, 1, 2, 0]);
+
+        let groups = groups.sorted_by_key();
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![0, 1, 2, 3]);
+        let groups = groups.sorted_by_size(true);
+        assert_eq!(groups.keys().copied().collect::<Vec<_>>(), vec![3, 1, 2, 0]);
+        for (key, group) in groups.iter() {
+            assert!(group.iter().all(|line| line[0] == Data::Integer(*key)));
+            assert_eq!(groups[key].len(), group.len());
+        }
+
+        let mut groups = groups.map_keys(|key| key * 10);
+        let drained: Vec<(i32, usize)> = groups
+            .drain()
+            .map(|(key, group)| (key, group.len()))
+            .collect();
+        assert_eq!(drained, vec![(30, 4), (10, 2), (20, 2), (0, 1)]);
+        assert!(groups.is_empty());
+        assert_eq!(groups.keys().count(), 0);
+    }
+
+    #[test]
+    fn annotate_matches_a_manual_lookup() {
+        let rows =
+            (0..500).map(|line| vec![Data::Integer((line * 7919) % 13), Data::Integer(line)]);
+        let df = DataFrame::new(vec!["key", "line"]).append_lines(rows);
+        let key = |line: Line| line[0].as_integer();
+        let sum = |group: &DataFrame| lines(group).iter().sum::<i32>();
+
+        let annotated = df
+            .clone()
+            .group_by(key)
+            .annotate("sum", |_key, group| Data::Integer(sum(group)));
+        assert_eq!(lines(&annotated), (0..500).collect::<Vec<_>>());
+
+        let groups = df.group_by(key);
+        for line in (0..50).map(|i| (i * 7717) % 500) {
+            let line = annotated.get(line).expect("the line is below 500");
+            let group = groups
+                .get(&line["key"].as_integer())
+                .expect("every key has a group");
+            assert_eq!(line["sum"], Data::Integer(sum(group)));
+        }
+
+        let keys = groups.keys_frame();
+        assert_eq!(keys.header().collect::<Vec<_>>(), vec!["key", "size"]);
+        assert_eq!(keys.len(), 13);
+        for line in keys.iter() {
+            let group = &groups[line["key"].as_integer()];
+            assert_eq!(line["size"], Data::Integer(group.len() as i32));
+        }
+    }
+
+    #[test]
+    fn annotate_follows_the_groups_without_a_shared_frame() {
+        let key = |line: Line| line[0].as_integer();
+        let mut groups = frame().group_by(key);
+        groups.insert_lines(frame().head(2), key);
+        let annotated = groups.annotate("size", |_key, group| Data::Integer(group.len() as i32));
+        assert_eq!(lines(&annotated), vec![0, 4, 0, 1, 3, 1, 2, 5]);
+        let sizes: Vec<Data> = annotated.iter().map(|line| line["size"].clone()).collect();
+        assert_eq!(sizes, [3, 3, 3, 3, 3, 3, 2, 2].map(Data::Integer));
     }
 }
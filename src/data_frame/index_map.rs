@@ -0,0 +1,143 @@
+use std::ops::Range;
+
+///the lines of the underlying frame a LineReorder shows, a contiguous run of lines
+///(e.g. from head or range) is stored without a Vec
+#[derive(Debug, Clone)]
+pub(super) enum IndexMap {
+    Range(Range<usize>),
+    Vec(Vec<usize>),
+}
+
+impl IndexMap {
+    pub(super) fn len(&self) -> usize {
+        match self {
+            IndexMap::Range(range) => range.len(),
+            IndexMap::Vec(vec) => vec.len(),
+        }
+    }
+
+    pub(super) fn get(&self, index: usize) -> Option<usize> {
+        match self {
+            IndexMap::Range(range) => (index < range.len()).then(|| range.start + index),
+            IndexMap::Vec(vec) => vec.get(index).copied(),
+        }
+    }
+
+    pub(super) fn iter(&self) -> impl Iterator<Item = usize> + '_ {
+        let (range, vec) = match self {
+            IndexMap::Range(range) => (Some(range.clone()), None),
+            IndexMap::Vec(vec) => (None, Some(vec.iter().copied())),
+        };
+        range.into_iter().flatten().chain(vec.into_iter().flatten())
+    }
+
+    ///the part of the map at the positions in range
+    pub(super) fn slice(&self, range: Range<usize>) -> IndexMap {
+        assert!(range.start <= range.end && range.end <= self.len());
+        match self {
+            IndexMap::Range(outer) => {
+                IndexMap::Range(outer.start + range.start..outer.start + range.end)
+            }
+            IndexMap::Vec(vec) => IndexMap::Vec(vec[range].to_vec()),
+        }
+    }
+
+    ///the map of a view with outer over a view with self, so one view replaces both
+    pub(super) fn compose(&self, outer: &IndexMap) -> IndexMap {
+        match (self, outer) {
+            (_, IndexMap::Range(range)) => self.slice(range.clone()),
+            (IndexMap::Range(inner), IndexMap::Vec(vec)) => {
+                IndexMap::Vec(vec.iter().map(|index| inner.start + index).collect())
+            }
+            (IndexMap::Vec(inner), IndexMap::Vec(vec)) => {
+                IndexMap::Vec(vec.iter().map(|index| inner[*index]).collect())
+            }
+        }
+    }
+}
+
+impl From<Vec<usize>> for IndexMap {
+    fn from(vec: Vec<usize>) -> Self {
+        IndexMap::Vec(vec)
+    }
+}
+
+impl From<Range<usize>> for IndexMap {
+    fn from(range: Range<usize>) -> Self {
+        IndexMap::Range(range)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///every range in 0..6 as both variants
+    fn pairs() -> Vec<(IndexMap, IndexMap)> {
+        let mut pairs = Vec::new();
+        for start in 0..6 {
+            for end in start..6 {
+                let range = start..end;
+                pairs.push((range.clone().into(), range.collect::<Vec<_>>().into()));
+            }
+        }
+        pairs
+    }
+
+    fn lines(index_map: &IndexMap) -> Vec<usize> {
+        index_map.iter().collect()
+    }
+
+    #[test]
+    fn both_variants_read_the_same() {
+        for (range, vec) in pairs() {
+            assert_eq!(range.len(), vec.len());
+            assert_eq!(lines(&range), lines(&vec));
+            for index in 0..8 {
+                assert_eq!(range.get(index), vec.get(index));
+            }
+        }
+    }
+
+    #[test]
+    fn slices_keep_the_variant() {
+        for (range, vec) in pairs() {
+            for start in 0..=range.len() {
+                for end in start..=range.len() {
+                    let sliced = range.slice(start..end);
+                    assert!(matches!(sliced, IndexMap::Range(_)));
+                    assert_eq!(lines(&sliced), lines(&vec.slice(start..end)));
+                    assert_eq!(lines(&sliced), lines(&vec)[start..end]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn slice_past_the_end_panics() {
+        IndexMap::Range(2..4).slice(1..3);
+    }
+
+    #[test]
+    fn compositions_of_both_variants_agree() {
+        let inners = [IndexMap::Range(2..8), (2..8).collect::<Vec<_>>().into()];
+        let outers = [
+            IndexMap::Range(1..4),
+            (1..4).collect::<Vec<_>>().into(),
+            vec![3, 0, 0, 5].into(),
+        ];
+        for inner in inners.iter() {
+            for outer in outers.iter() {
+                let expected: Vec<usize> = outer.iter().map(|index| index + 2).collect();
+                let composed = inner.compose(outer);
+                assert_eq!(lines(&composed), expected);
+                let both_ranges =
+                    matches!((inner, outer), (IndexMap::Range(_), IndexMap::Range(_)));
+                assert_eq!(matches!(composed, IndexMap::Range(_)), both_ranges);
+            }
+        }
+        let reversed: IndexMap = vec![5, 3, 1, 0].into();
+        assert_eq!(lines(&reversed.compose(&IndexMap::Range(1..3))), vec![3, 1]);
+    }
+}
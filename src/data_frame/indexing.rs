@@ -1,12 +1,34 @@
 pub trait DataFrameColumnIndex {
     #[doc(hidden)]
     fn get_usize<'a>(&self, header: impl Iterator<Item = &'a str>) -> usize;
+
+    ///like get_usize but returns a description of the index instead of panicking
+    #[doc(hidden)]
+    fn try_get_usize(&self, header: &[&str]) -> Result<usize, String>;
+
+    ///the position if it does not depend on the header, so no header has to be collected
+    #[doc(hidden)]
+    fn position(&self) -> Option<usize> {
+        None
+    }
 }
 
 impl DataFrameColumnIndex for usize {
     fn get_usize<'a>(&self, _header: impl Iterator<Item = &'a str>) -> usize {
         *self
     }
+
+    fn try_get_usize(&self, header: &[&str]) -> Result<usize, String> {
+        if *self < header.len() {
+            Ok(*self)
+        } else {
+            Err(format!("{self}"))
+        }
+    }
+
+    fn position(&self) -> Option<usize> {
+        Some(*self)
+    }
 }
 
 impl<'s> DataFrameColumnIndex for &'s str {
@@ -17,4 +39,39 @@ impl<'s> DataFrameColumnIndex for &'s str {
             panic!("index out of Bound: Header does not contain '{self}'")
         }
     }
+
+    fn try_get_usize(&self, header: &[&str]) -> Result<usize, String> {
+        header
+            .iter()
+            .position(|string| self == string)
+            .ok_or_else(|| format!("'{self}'"))
+    }
+}
+
+///a column index resolved once against a frame's header, see DataFrame::column_handle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ColumnHandle(usize);
+
+impl ColumnHandle {
+    pub(super) fn new(index: usize) -> ColumnHandle {
+        ColumnHandle(index)
+    }
+
+    pub fn index(&self) -> usize {
+        self.0
+    }
+}
+
+impl DataFrameColumnIndex for ColumnHandle {
+    fn get_usize<'a>(&self, _header: impl Iterator<Item = &'a str>) -> usize {
+        self.0
+    }
+
+    fn try_get_usize(&self, _header: &[&str]) -> Result<usize, String> {
+        Ok(self.0)
+    }
+
+    fn position(&self) -> Option<usize> {
+        Some(self.0)
+    }
 }
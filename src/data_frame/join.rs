@@ -0,0 +1,761 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::{observed, Data, DataFrame, DataFrameColumnIndex, InnerDataFrame, JoinError};
+
+const UNMATCHED_SAMPLES: usize = 10;
+
+///what happens to a column name that both sides of a join have
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum JoinNaming {
+    ///both columns are kept and the names get the suffix of their side, an empty suffix
+    ///leaves the names of that side unchanged, if a suffixed name is taken as well
+    ///the suffix is appended again until it is unique
+    Suffix {
+        left: &'static str,
+        right: &'static str,
+    },
+    ///the column of the right side is dropped
+    PreferLeft,
+    ///the column of the left side is dropped
+    PreferRight,
+    ///the join fails with JoinError::DuplicateColumn
+    Error,
+}
+
+impl Default for JoinNaming {
+    fn default() -> Self {
+        JoinNaming::Suffix {
+            left: "",
+            right: "_right",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum JoinKind {
+    Inner,
+    Left,
+    Right,
+    Outer,
+}
+
+impl JoinKind {
+    fn name(&self) -> &'static str {
+        match self {
+            JoinKind::Inner => "join",
+            JoinKind::Left => "left_join",
+            JoinKind::Right => "right_join",
+            JoinKind::Outer => "outer_join",
+        }
+    }
+}
+
+///which columns of both sides make up the joined lines and their names
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(super) struct JoinLayout {
+    pub(super) left: Vec<usize>,
+    pub(super) right: Vec<usize>,
+    pub(super) header: Vec<String>,
+}
+
+impl JoinNaming {
+    ///the key column of the right side is left out if right_key is given,
+    ///so the key appears once (as the column of the left side)
+    pub(super) fn layout(
+        &self,
+        left: &[&str],
+        right: &[&str],
+        right_key: Option<usize>,
+    ) -> Result<JoinLayout, JoinError> {
+        let mut left_columns: Vec<usize> = (0..left.len()).collect();
+        let mut right_columns: Vec<usize> = (0..right.len())
+            .filter(|index| Some(*index) != right_key)
+            .collect();
+        let left_names: HashSet<&str> = left.iter().copied().collect();
+        let right_names: HashSet<&str> = right_columns.iter().map(|index| right[*index]).collect();
+
+        let (left_suffix, right_suffix) = match self {
+            JoinNaming::Suffix { left, right } => (*left, *right),
+            JoinNaming::PreferLeft => {
+                right_columns.retain(|index| !left_names.contains(right[*index]));
+                ("", "")
+            }
+            JoinNaming::PreferRight => {
+                left_columns.retain(|index| !right_names.contains(left[*index]));
+                ("", "")
+            }
+            JoinNaming::Error => {
+                if let Some(index) = right_columns
+                    .iter()
+                    .find(|index| left_names.contains(right[**index]))
+                {
+                    return Err(JoinError::DuplicateColumn {
+                        name: right[*index].to_owned(),
+                    });
+                }
+                ("", "")
+            }
+        };
+
+        let mut produced = HashSet::new();
+        let mut rename = |name: &str, suffix: &str, collides: bool| {
+            let mut name = name.to_owned();
+            if collides && !suffix.is_empty() {
+                name.push_str(suffix);
+                while left_names.contains(name.as_str())
+                    || right_names.contains(name.as_str())
+                    || produced.contains(&name)
+                {
+                    name.push_str(suffix);
+                }
+                produced.insert(name.clone());
+            }
+            name
+        };
+        let mut header = Vec::with_capacity(left_columns.len() + right_columns.len());
+        for index in left_columns.iter() {
+            let name = left[*index];
+            header.push(rename(name, left_suffix, right_names.contains(name)));
+        }
+        for index in right_columns.iter() {
+            let name = right[*index];
+            header.push(rename(name, right_suffix, left_names.contains(name)));
+        }
+
+        Ok(JoinLayout {
+            left: left_columns,
+            right: right_columns,
+            header,
+        })
+    }
+}
+
+///how well the keys of two frames match, see DataFrame::join_report
+#[derive(Debug, Clone, PartialEq)]
+pub struct JoinReport {
+    pub self_distinct: usize,
+    pub other_distinct: usize,
+    ///distinct keys found on both sides
+    pub matched_keys: usize,
+    ///the share of lines of self whose key is found in other
+    pub match_rate: f64,
+    ///the most lines of other a single line of self is joined with
+    pub max_duplication: usize,
+    ///the number of lines an inner join would produce
+    pub inner_join_rows: usize,
+    ///up to 10 keys of each side without a partner, in order of first occurrence
+    pub self_unmatched: Vec<Data>,
+    pub other_unmatched: Vec<Data>,
+}
+
+impl Display for JoinReport {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        writeln!(
+            f,
+            "distinct keys: {} left, {} right, {} on both sides",
+            self.self_distinct, self.other_distinct, self.matched_keys
+        )?;
+        writeln!(f, "match rate: {:.1}%", self.match_rate * 100.0)?;
+        writeln!(
+            f,
+            "inner join: {} lines, a line matches up to {} lines",
+            self.inner_join_rows, self.max_duplication
+        )?;
+        for (side, unmatched) in [
+            ("left", &self.self_unmatched),
+            ("right", &self.other_unmatched),
+        ] {
+            if !unmatched.is_empty() {
+                let keys: Vec<String> = unmatched.iter().map(|key| format!("{key:?}")).collect();
+                writeln!(f, "unmatched {side} keys: {}", keys.join(", "))?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl DataFrame {
+    ///every line of self followed by every line of other (self is the outer loop),
+    ///columns of other whose name is already taken get the suffix "_right"
+    ///
+    ///fails without allocating anything if the result would have more than max_rows lines
+    pub fn cross_join(self, other: DataFrame, max_rows: usize) -> Result<DataFrame, JoinError> {
+        let rows = self.len().checked_mul(other.len());
+        if rows.is_none_or(|rows| rows > max_rows) {
+            return Err(JoinError::TooManyRows { rows, max_rows });
+        }
+
+        let rows_in = self.len() + other.len();
+        Ok(observed("cross_join", rows_in, DataFrame::len, move || {
+            let left: Vec<&str> = self.header().collect();
+            let right: Vec<&str> = other.header().collect();
+            let header = JoinNaming::default()
+                .layout(&left, &right, None)
+                .expect("suffixing never fails")
+                .header;
+            let data = self.iter().flat_map(|left| {
+                other
+                    .iter()
+                    .map(move |right| left.iter().chain(right.iter()).cloned().collect())
+            });
+            DataFrame::new(header).append_lines(data)
+        }))
+    }
+
+    ///inner join on the key columns with the default JoinNaming, see join_with
+    pub fn join<I, J>(self, other: DataFrame, self_key: I, other_key: J) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        self.join_with(other, self_key, other_key, JoinNaming::default())
+            .expect("suffixing never fails")
+    }
+
+    ///inner hash join, a line of self is joined with every line of other with an equal key
+    ///(so different variants never match and Null matches nothing),
+    ///the lines follow the order of self and then the order of other
+    ///
+    ///the key column of other is dropped, the rest of the names are resolved by naming
+    pub fn join_with<I, J>(
+        self,
+        other: DataFrame,
+        self_key: I,
+        other_key: J,
+        naming: JoinNaming,
+    ) -> Result<DataFrame, JoinError>
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let self_key = self_key.get_usize(self.header());
+        let other_key = other_key.get_usize(other.header());
+        self.hash_join(other, self_key, other_key, naming, JoinKind::Inner)
+    }
+
+    ///like join but a line of self without a partner is kept once with Null for the columns of other
+    pub fn left_join<I, J>(self, other: DataFrame, self_key: I, other_key: J) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let self_key = self_key.get_usize(self.header());
+        let other_key = other_key.get_usize(other.header());
+        self.hash_join(
+            other,
+            self_key,
+            other_key,
+            JoinNaming::default(),
+            JoinKind::Left,
+        )
+        .expect("suffixing never fails")
+    }
+
+    ///the mirror of left_join: every line of other is kept, in the order of other,
+    ///joined with every line of self with an equal key or once with Null for the columns of self
+    ///except the key column which gets the key of other. the columns are ordered like in join
+    pub fn right_join<I, J>(self, other: DataFrame, self_key: I, other_key: J) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let self_key = self_key.get_usize(self.header());
+        let other_key = other_key.get_usize(other.header());
+        self.hash_join(
+            other,
+            self_key,
+            other_key,
+            JoinNaming::default(),
+            JoinKind::Right,
+        )
+        .expect("suffixing never fails")
+    }
+
+    ///a view of the lines of self whose key is also a key of other (matched like in join),
+    ///each line at most once no matter how often other has the key, no columns are added
+    pub fn semi_join<I, J>(self, other: DataFrame, self_key: I, other_key: J) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let self_key = self_key.get_usize(self.header());
+        let other_key = other_key.get_usize(other.header());
+        self.filter_join(other, self_key, other_key, true)
+    }
+
+    ///a view of the lines of self whose key is no key of other, lines with a Null key are kept
+    pub fn anti_join<I, J>(self, other: DataFrame, self_key: I, other_key: J) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let self_key = self_key.get_usize(self.header());
+        let other_key = other_key.get_usize(other.header());
+        self.filter_join(other, self_key, other_key, false)
+    }
+
+    fn filter_join(
+        self,
+        other: DataFrame,
+        self_key: usize,
+        other_key: usize,
+        keep: bool,
+    ) -> DataFrame {
+        let name = if keep { "semi_join" } else { "anti_join" };
+        let rows_in = self.len() + other.len();
+        observed(name, rows_in, DataFrame::len, move || {
+            let lines = Self::lines_by_key(&other, other_key);
+            let index_map: Vec<usize> = self
+                .iter()
+                .enumerate()
+                .filter(|(_index, line)| lines.contains_key(line.data(self_key)) == keep)
+                .map(|(index, _line)| index)
+                .collect();
+            InnerDataFrame::LineReorder {
+                df: self,
+                index_map: index_map.into(),
+            }
+            .into()
+        })
+    }
+
+    ///like left_join followed by the lines of other without a partner in their order,
+    ///with Null for the columns of self except the key column which gets the key of other
+    pub fn outer_join<I, J>(self, other: DataFrame, self_key: I, other_key: J) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let self_key = self_key.get_usize(self.header());
+        let other_key = other_key.get_usize(other.header());
+        self.hash_join(
+            other,
+            self_key,
+            other_key,
+            JoinNaming::default(),
+            JoinKind::Outer,
+        )
+        .expect("suffixing never fails")
+    }
+
+    fn hash_join(
+        self,
+        other: DataFrame,
+        self_key: usize,
+        other_key: usize,
+        naming: JoinNaming,
+        kind: JoinKind,
+    ) -> Result<DataFrame, JoinError> {
+        let left: Vec<&str> = self.header().collect();
+        let right: Vec<&str> = other.header().collect();
+        let layout = naming.layout(&left, &right, Some(other_key))?;
+
+        let rows_in = self.len() + other.len();
+        Ok(observed(kind.name(), rows_in, DataFrame::len, move || {
+            let mut pairs: Vec<(Option<usize>, Option<usize>)> = Vec::new();
+            if kind == JoinKind::Inner && self.len() < other.len() {
+                //the map is built from the smaller side
+                let lines = Self::lines_by_key(&self, self_key);
+                for (other_index, line) in other.iter().enumerate() {
+                    if let Some(self_indizes) = lines.get(line.data(other_key)) {
+                        pairs.extend(
+                            self_indizes
+                                .iter()
+                                .map(|self_index| (Some(*self_index), Some(other_index))),
+                        );
+                    }
+                }
+                pairs.sort_unstable();
+            } else if kind == JoinKind::Right {
+                let lines = Self::lines_by_key(&self, self_key);
+                for (other_index, line) in other.iter().enumerate() {
+                    match lines.get(line.data(other_key)) {
+                        Some(self_indizes) => pairs.extend(
+                            self_indizes
+                                .iter()
+                                .map(|self_index| (Some(*self_index), Some(other_index))),
+                        ),
+                        None => pairs.push((None, Some(other_index))),
+                    }
+                }
+            } else {
+                let lines = Self::lines_by_key(&other, other_key);
+                let mut matched = vec![false; other.len()];
+                for (self_index, line) in self.iter().enumerate() {
+                    match lines.get(line.data(self_key)) {
+                        Some(other_indizes) => {
+                            for other_index in other_indizes {
+                                pairs.push((Some(self_index), Some(*other_index)));
+                                matched[*other_index] = true;
+                            }
+                        }
+                        None if kind != JoinKind::Inner => pairs.push((Some(self_index), None)),
+                        None => {}
+                    }
+                }
+                if kind == JoinKind::Outer {
+                    pairs.extend(
+                        (0..other.len())
+                            .filter(|other_index| !matched[*other_index])
+                            .map(|other_index| (None, Some(other_index))),
+                    );
+                }
+            }
+
+            let data = pairs.iter().map(|(self_index, other_index)| {
+                let left = self_index.map(|index| self.get(index).expect("index is valid"));
+                let right = other_index.map(|index| other.get(index).expect("index is valid"));
+                let mut new_line = Vec::with_capacity(layout.header.len());
+                new_line.extend(layout.left.iter().map(|index| match (left, right) {
+                    (Some(left), _) => left.data(*index).clone(),
+                    (None, Some(right)) if *index == self_key => right.data(other_key).clone(),
+                    (None, _) => Data::Null,
+                }));
+                new_line.extend(layout.right.iter().map(|index| match right {
+                    Some(right) => right.data(*index).clone(),
+                    None => Data::Null,
+                }));
+                new_line
+            });
+            DataFrame::new(layout.header.clone()).append_lines(data)
+        }))
+    }
+
+    ///the indizes of the lines with each key, Null keys are left out
+    fn lines_by_key(df: &DataFrame, index: usize) -> HashMap<&Data, Vec<usize>> {
+        let mut lines: HashMap<&Data, Vec<usize>> = HashMap::new();
+        for (line_index, line) in df.iter().enumerate() {
+            let key = line.data(index);
+            if !key.is_null() {
+                lines.entry(key).or_default().push(line_index);
+            }
+        }
+        lines
+    }
+
+    ///statistics about joining self and other on the key columns without doing the join,
+    ///a line with a Null key is unmatched but its key is not counted or sampled
+    pub fn join_report<I, J>(&self, other: &DataFrame, self_key: I, other_key: J) -> JoinReport
+    where
+        I: DataFrameColumnIndex,
+        J: DataFrameColumnIndex,
+    {
+        let (self_order, self_counts) = Self::key_counts(self, self_key.get_usize(self.header()));
+        let (other_order, other_counts) =
+            Self::key_counts(other, other_key.get_usize(other.header()));
+
+        let mut matched_keys = 0;
+        let mut matched_lines = 0;
+        let mut max_duplication = 0;
+        let mut inner_join_rows = 0;
+        let mut self_unmatched = Vec::new();
+        for key in self_order {
+            let self_count = self_counts[key];
+            match other_counts.get(key) {
+                Some(other_count) => {
+                    matched_keys += 1;
+                    matched_lines += self_count;
+                    max_duplication = max_duplication.max(*other_count);
+                    inner_join_rows += self_count * other_count;
+                }
+                None if self_unmatched.len() < UNMATCHED_SAMPLES => {
+                    self_unmatched.push(key.clone())
+                }
+                None => {}
+            }
+        }
+        let other_unmatched = other_order
+            .into_iter()
+            .filter(|key| !self_counts.contains_key(key))
+            .take(UNMATCHED_SAMPLES)
+            .cloned()
+            .collect();
+
+        JoinReport {
+            self_distinct: self_counts.len(),
+            other_distinct: other_counts.len(),
+            matched_keys,
+            match_rate: if self.is_empty() {
+                0.0
+            } else {
+                matched_lines as f64 / self.len() as f64
+            },
+            max_duplication,
+            inner_join_rows,
+            self_unmatched,
+            other_unmatched,
+        }
+    }
+
+    ///the distinct keys in order of first occurrence and how often each occurs,
+    ///Null keys are left out like in lines_by_key since they never match
+    fn key_counts(df: &DataFrame, index: usize) -> (Vec<&Data>, HashMap<&Data, usize>) {
+        let mut order = Vec::new();
+        let mut counts: HashMap<&Data, usize> = HashMap::new();
+        for line in df.iter() {
+            let key = line.data(index);
+            if key.is_null() {
+                continue;
+            }
+            let count = counts.entry(key).or_insert_with(|| {
+                order.push(key);
+                0
+            });
+            *count += 1;
+        }
+        (order, counts)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Data;
+
+    fn frame(header: Vec<&str>, lines: i32) -> DataFrame {
+        let columns = header.len() as i32;
+        let rows = (0..lines).map(move |line| {
+            (0..columns)
+                .map(|column| Data::Integer(line * 10 + column))
+                .collect()
+        });
+        DataFrame::new(header).append_lines(rows)
+    }
+
+    fn lines(df: &DataFrame) -> Vec<Vec<Data>> {
+        df.iter()
+            .map(|line| line.iter().cloned().collect())
+            .collect()
+    }
+
+    #[test]
+    fn self_is_the_outer_loop() {
+        let df = frame(vec!["a", "b"], 2)
+            .cross_join(frame(vec!["b", "b_right", "c"], 3), 6)
+            .expect("6 lines are allowed");
+        //b_right is taken by the right side, so its b is suffixed twice
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            ["a", "b", "b_right_right", "b_right", "c"]
+        );
+        let int = Data::Integer;
+        assert_eq!(
+            lines(&df),
+            [
+                vec![int(0), int(1), int(0), int(1), int(2)],
+                vec![int(0), int(1), int(10), int(11), int(12)],
+                vec![int(0), int(1), int(20), int(21), int(22)],
+                vec![int(10), int(11), int(0), int(1), int(2)],
+                vec![int(10), int(11), int(10), int(11), int(12)],
+                vec![int(10), int(11), int(20), int(21), int(22)],
+            ]
+        );
+    }
+
+    #[test]
+    fn an_empty_side_gives_an_empty_result() {
+        for (left, right) in [(0, 3), (3, 0), (0, 0)] {
+            let df = frame(vec!["a"], left)
+                .cross_join(frame(vec!["a", "b"], right), 0)
+                .expect("no line is produced");
+            assert_eq!(df.shape(), (0, 3));
+            assert_eq!(df.header().collect::<Vec<_>>(), ["a", "a_right", "b"]);
+        }
+    }
+
+    #[test]
+    fn the_row_limit_is_checked_before_joining() {
+        let err = frame(vec!["a"], 100)
+            .cross_join(frame(vec!["b"], 100), 9_999)
+            .err();
+        assert_eq!(
+            err.clone(),
+            Some(JoinError::TooManyRows {
+                rows: Some(10_000),
+                max_rows: 9_999
+            })
+        );
+        assert_eq!(
+            err.expect("the join fails").to_string(),
+            "the join would produce 10000 lines but at most 9999 are allowed"
+        );
+    }
+
+    fn layout(naming: JoinNaming, right_key: Option<usize>) -> Result<JoinLayout, JoinError> {
+        naming.layout(
+            &["id", "value", "value_r"],
+            &["id", "value", "extra"],
+            right_key,
+        )
+    }
+
+    fn expected(left: Vec<usize>, right: Vec<usize>, header: &[&str]) -> JoinLayout {
+        let header = header.iter().map(|name| name.to_string()).collect();
+        JoinLayout {
+            left,
+            right,
+            header,
+        }
+    }
+
+    #[test]
+    fn suffixes_rename_only_colliding_columns() {
+        let naming = JoinNaming::Suffix {
+            left: "_l",
+            right: "_r",
+        };
+        assert_eq!(
+            layout(naming, None),
+            Ok(expected(
+                vec![0, 1, 2],
+                vec![0, 1, 2],
+                &["id_l", "value_l", "value_r", "id_r", "value_r_r", "extra"]
+            ))
+        );
+        //the key of the right side is left out and no longer collides
+        assert_eq!(
+            layout(JoinNaming::default(), Some(0)),
+            Ok(expected(
+                vec![0, 1, 2],
+                vec![1, 2],
+                &["id", "value", "value_r", "value_right", "extra"]
+            ))
+        );
+    }
+
+    #[test]
+    fn a_suffix_is_appended_until_the_name_is_unique() {
+        let layout = JoinNaming::default()
+            .layout(&["a", "a_right", "a_right_right"], &["a"], None)
+            .expect("suffixes never fail");
+        assert_eq!(
+            layout.header,
+            ["a", "a_right", "a_right_right", "a_right_right_right"]
+        );
+    }
+
+    #[test]
+    fn colliding_columns_of_one_side_are_dropped() {
+        assert_eq!(
+            layout(JoinNaming::PreferLeft, Some(0)),
+            Ok(expected(
+                vec![0, 1, 2],
+                vec![2],
+                &["id", "value", "value_r", "extra"]
+            ))
+        );
+        assert_eq!(
+            layout(JoinNaming::PreferRight, Some(0)),
+            Ok(expected(
+                vec![0, 2],
+                vec![1, 2],
+                &["id", "value_r", "value", "extra"]
+            ))
+        );
+        assert_eq!(
+            layout(JoinNaming::Error, Some(0)),
+            Err(JoinError::DuplicateColumn {
+                name: "value".to_owned()
+            })
+        );
+    }
+
+    fn string(string: &str) -> Data {
+        Data::String(Box::new(string.to_owned()))
+    }
+
+    fn events() -> DataFrame {
+        let rows = vec![
+            vec![Data::Integer(1), string("login")],
+            vec![Data::Integer(2), string("click")],
+            vec![Data::Integer(1), string("logout")],
+            vec![Data::Null, string("orphan")],
+            vec![Data::Float(2.0), string("float key")],
+            vec![string("1"), string("string key")],
+        ];
+        DataFrame::from_rows(vec!["user_id", "name"], rows).expect("the rows fit the header")
+    }
+
+    fn users() -> DataFrame {
+        let rows = vec![
+            vec![Data::Integer(1), string("ada")],
+            vec![Data::Integer(1), string("ada again")],
+            vec![Data::Integer(3), string("grace")],
+            vec![Data::Null, string("nobody")],
+            vec![Data::Integer(2), string("alan")],
+        ];
+        DataFrame::from_rows(vec!["id", "name"], rows).expect("the rows fit the header")
+    }
+
+    #[test]
+    fn duplicate_keys_expand() {
+        let joined = events().join(users(), "user_id", "id");
+        assert_eq!(
+            joined.header().collect::<Vec<_>>(),
+            vec!["user_id", "name", "name_right"]
+        );
+        let pairs: Vec<(String, String)> = joined
+            .iter()
+            .map(|line| (line[1].as_string(), line[2].as_string()))
+            .collect();
+        let expected = [
+            ("login", "ada"),
+            ("login", "ada again"),
+            ("click", "alan"),
+            ("logout", "ada"),
+            ("logout", "ada again"),
+        ];
+        let expected: Vec<(String, String)> = expected
+            .iter()
+            .map(|(left, right)| (left.to_string(), right.to_string()))
+            .collect();
+        assert_eq!(pairs, expected);
+    }
+
+    #[test]
+    fn other_variants_and_nulls_never_match() {
+        let joined = events().join(users(), 0, 0);
+        assert!(joined
+            .iter()
+            .all(|line| matches!(line[0], Data::Integer(_))));
+        assert!(events()
+            .left_join(users(), 0, 0)
+            .iter()
+            .filter(|line| line[0].is_null())
+            .all(|line| line[2].is_null()));
+    }
+
+    fn names(df: &DataFrame) -> Vec<String> {
+        df.iter().map(|line| line["name"].as_string()).collect()
+    }
+
+    #[test]
+    fn semi_join_keeps_each_line_once() {
+        //users has the key 1 twice
+        let semi = events().semi_join(users(), "user_id", "id");
+        assert_eq!(semi.header().collect::<Vec<_>>(), ["user_id", "name"]);
+        assert_eq!(names(&semi), ["login", "click", "logout"]);
+
+        let anti = events().anti_join(users(), "user_id", "id");
+        assert_eq!(names(&anti), ["orphan", "float key", "string key"]);
+        assert_eq!(semi.len() + anti.len(), events().len());
+
+        let twice = events().concat(events());
+        let semi = users().semi_join(twice, 0, 0);
+        assert_eq!(names(&semi), ["ada", "ada again", "alan"]);
+    }
+
+    #[test]
+    fn report_agrees_with_the_join() {
+        let report = events().join_report(&users(), "user_id", "id");
+        assert_eq!(report.inner_join_rows, events().join(users(), 0, 0).len());
+        assert_eq!(report.self_distinct, 4);
+        assert_eq!(report.other_distinct, 3);
+        assert_eq!(report.matched_keys, 2);
+        assert_eq!(report.match_rate, 3.0 / 6.0);
+        assert_eq!(report.max_duplication, 2);
+        assert_eq!(report.self_unmatched, vec![Data::Float(2.0), string("1")]);
+        assert_eq!(report.other_unmatched, vec![Data::Integer(3)]);
+    }
+}
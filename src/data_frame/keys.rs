@@ -0,0 +1,274 @@
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
+
+use super::{Data, DataFrame, DataFrameColumnIndex, InnerDataFrame, Line};
+
+const CANDIDATE_SAMPLE_LINES: usize = 1000;
+const MAX_PAIR_COLUMNS: usize = 64;
+
+impl DataFrame {
+    ///true if no value of the column occurs twice, Null counts as a value
+    pub fn is_unique<I>(&self, index: I) -> bool
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        self.is_key(&[index])
+    }
+
+    ///all lines whose value in the column also occurs in another line
+    pub fn duplicate_values<I>(&self, index: I) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let mut counts: HashMap<&Data, usize> = HashMap::new();
+        for line in self.iter() {
+            *counts.entry(line.data(index)).or_default() += 1;
+        }
+        let duplicates: HashSet<Data> = counts
+            .drain()
+            .filter(|(_data, count)| *count > 1)
+            .map(|(data, _count)| data.clone())
+            .collect();
+
+        self.clone()
+            .filter(|line| duplicates.contains(&line[index]))
+    }
+
+    ///true if every value is greater or equal to the one before,
+    ///Integer and Float are compared as numbers and any other mix of types (including Null) is false
+    pub fn is_monotonic_increasing<I>(&self, index: I) -> bool
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let mut lines = self.iter();
+        let Some(first) = lines.next() else {
+            return true;
+        };
+        let mut last = first.data(index);
+        for line in lines {
+            let current = line.data(index);
+            let ordering = match (last, current) {
+                (Data::Integer(a), Data::Float(b)) => (*a as f32).partial_cmp(b),
+                (Data::Float(a), Data::Integer(b)) => a.partial_cmp(&(*b as f32)),
+                (Data::Null, _) | (_, Data::Null) => None,
+                (a, b) => a.partial_cmp(b),
+            };
+            if !matches!(ordering, Some(Ordering::Less | Ordering::Equal)) {
+                return false;
+            }
+            last = current;
+        }
+        true
+    }
+
+    ///all single columns and (if max_columns is at least 2) pairs of columns
+    ///whose values identify every line, a pair is left out if one of its columns is a key on its own
+    ///
+    ///larger combinations are not searched and pairs only among the first 64 columns that are
+    ///not keys on their own. every candidate is first checked on the first 1000 lines
+    ///and each check stops at its first duplicate
+    pub fn candidate_keys(&self, max_columns: usize) -> Vec<Vec<String>> {
+        let header: Vec<&str> = self.header().collect();
+        let mut keys = Vec::new();
+        if max_columns == 0 {
+            return keys;
+        }
+
+        let is_candidate = |indizes: &[usize]| {
+            self.is_key_in(indizes, CANDIDATE_SAMPLE_LINES) && self.is_key(indizes)
+        };
+
+        let mut single_keys = vec![false; header.len()];
+        for (index, is_key) in single_keys.iter_mut().enumerate() {
+            if is_candidate(&[index]) {
+                *is_key = true;
+                keys.push(vec![header[index].to_owned()]);
+            }
+        }
+
+        if max_columns >= 2 {
+            let pair_columns: Vec<usize> = (0..header.len())
+                .filter(|index| !single_keys[*index])
+                .take(MAX_PAIR_COLUMNS)
+                .collect();
+            for (position, first) in pair_columns.iter().enumerate() {
+                for second in pair_columns[position + 1..].iter() {
+                    if is_candidate(&[*first, *second]) {
+                        keys.push(vec![header[*first].to_owned(), header[*second].to_owned()]);
+                    }
+                }
+            }
+        }
+        keys
+    }
+
+    ///keeps the first of all lines with equal cells in every column, as a view of self
+    pub fn drop_duplicates(self) -> DataFrame {
+        let indizes: Vec<usize> = (0..self.num_columns()).collect();
+        self.drop_duplicates_at(&indizes)
+    }
+
+    ///like drop_duplicates but only the cells in subset are compared
+    pub fn drop_duplicates_by<I>(self, subset: &[I]) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let indizes: Vec<usize> = subset
+            .iter()
+            .map(|index| index.get_usize(self.header()))
+            .collect();
+        self.drop_duplicates_at(&indizes)
+    }
+
+    fn drop_duplicates_at(self, indizes: &[usize]) -> DataFrame {
+        let index_map: Vec<usize> = {
+            let mut seen = HashSet::with_capacity(self.len());
+            self.iter()
+                .enumerate()
+                .filter(|(_i, line)| seen.insert(Self::key_of(*line, indizes)))
+                .map(|(i, _line)| i)
+                .collect()
+        };
+        InnerDataFrame::LineReorder {
+            df: self,
+            index_map: index_map.into(),
+        }
+        .into()
+    }
+
+    fn is_key(&self, indizes: &[usize]) -> bool {
+        self.is_key_in(indizes, self.len())
+    }
+
+    ///like is_key but only for the first lines
+    fn is_key_in(&self, indizes: &[usize], lines: usize) -> bool {
+        let mut seen = HashSet::with_capacity(lines.min(self.len()));
+        self.iter()
+            .take(lines)
+            .all(|line| seen.insert(Self::key_of(line, indizes)))
+    }
+
+    fn key_of<'df>(line: Line<'df>, indizes: &[usize]) -> Vec<&'df Data> {
+        indizes.iter().map(|index| line.data(*index)).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///(day, shift) identifies a line but neither column does on its own
+    fn schedule() -> DataFrame {
+        let rows = (0..6).map(|line| {
+            vec![
+                Data::Integer(line / 2),
+                Data::String(Box::new(["early", "late"][line as usize % 2].to_owned())),
+                Data::Integer(line % 3),
+                Data::Float(line as f32),
+            ]
+        });
+        DataFrame::new(vec!["day", "shift", "room", "id"]).append_lines(rows)
+    }
+
+    #[test]
+    fn composite_key_is_found() {
+        let df = schedule();
+        assert!(!df.is_unique("day"));
+        assert!(!df.is_unique("shift"));
+        assert!(df.is_unique("id"));
+        assert_eq!(df.duplicate_values("room").len(), 6);
+        assert!(df.is_monotonic_increasing("day"));
+        assert!(!df.is_monotonic_increasing("room"));
+
+        assert_eq!(df.candidate_keys(1), vec![vec!["id".to_owned()]]);
+        assert_eq!(
+            df.candidate_keys(2),
+            vec![
+                vec!["id".to_owned()],
+                vec!["day".to_owned(), "shift".to_owned()],
+                vec!["day".to_owned(), "room".to_owned()],
+                vec!["shift".to_owned(), "room".to_owned()],
+            ]
+        );
+    }
+
+    #[test]
+    fn duplicate_after_the_sample_is_found() {
+        let mut values: Vec<i32> = (0..CANDIDATE_SAMPLE_LINES as i32 + 10).collect();
+        values.push(0);
+        let rows = values.into_iter().map(|value| vec![Data::Integer(value)]);
+        let df = DataFrame::new(vec!["value"]).append_lines(rows);
+        assert!(df.candidate_keys(2).is_empty());
+    }
+
+    #[test]
+    fn pairs_are_searched_in_the_first_columns() {
+        //every column only holds 0 and 1, the first and the last column together are a key
+        let columns = MAX_PAIR_COLUMNS + 2;
+        let header: Vec<String> = (0..columns).map(|column| format!("c{column}")).collect();
+        let rows = (0..4).map(|line| {
+            (0..columns)
+                .map(|column| match column {
+                    0 => Data::Integer(line % 2),
+                    _ if column == columns - 1 => Data::Integer(line / 2),
+                    _ => Data::Integer(0),
+                })
+                .collect()
+        });
+        let df = DataFrame::new(header.clone()).append_lines(rows);
+        assert!(df.candidate_keys(2).is_empty());
+
+        let kept: Vec<&str> = header
+            .iter()
+            .map(|name| name.as_str())
+            .filter(|name| !["c1", "c2"].contains(name))
+            .collect();
+        let df = df.drop_all_column_except(&kept);
+        assert_eq!(
+            df.candidate_keys(2),
+            vec![vec!["c0".to_owned(), format!("c{}", columns - 1)]]
+        );
+    }
+
+    fn readings() -> DataFrame {
+        let rows = [
+            ("a", 1.0),
+            ("b", 2.0),
+            ("a", 1.0),
+            ("c", f32::NAN),
+            ("a", 3.0),
+            ("c", f32::NAN),
+            ("b", 2.0),
+        ]
+        .into_iter()
+        .map(|(sensor, value)| {
+            vec![
+                Data::String(Box::new(sensor.to_owned())),
+                Data::Float(value),
+            ]
+        });
+        DataFrame::new(vec!["sensor", "value"]).append_lines(rows)
+    }
+
+    fn line_strings(df: &DataFrame) -> Vec<String> {
+        df.iter()
+            .map(|line| format!("{}={}", line[0], line[1]))
+            .collect()
+    }
+
+    #[test]
+    fn duplicates_are_dropped_after_other_lines() {
+        let df = readings().drop_duplicates();
+        assert_eq!(line_strings(&df), vec!["a=1", "b=2", "c=NaN", "a=3"]);
+        assert!(matches!(*df.inner, InnerDataFrame::LineReorder { .. }));
+
+        //the first line of each sensor is kept
+        let df = readings().drop_duplicates_by(&["sensor"]);
+        assert_eq!(line_strings(&df), vec!["a=1", "b=2", "c=NaN"]);
+        let df = readings().drop_duplicates_by(&[1]);
+        assert_eq!(line_strings(&df), vec!["a=1", "b=2", "c=NaN", "a=3"]);
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::OnceLock;
+
+use super::Data;
+
+//lines per chunk, a chunk is allocated when the first of its lines is accessed
+const CHUNK_LINES: usize = 1024;
+
+///the cells of a computed column, each one is computed on its first access
+pub(super) struct LazyCells {
+    len: usize,
+    chunks: Vec<OnceLock<Box<[OnceLock<Data>]>>>,
+}
+
+impl LazyCells {
+    pub(super) fn new(len: usize) -> LazyCells {
+        LazyCells {
+            len,
+            chunks: (0..len.div_ceil(CHUNK_LINES))
+                .map(|_| OnceLock::new())
+                .collect(),
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    ///the cell of line, f computes it if it was not accessed before
+    pub(super) fn get_or_init(&self, line: usize, f: impl FnOnce() -> Data) -> &Data {
+        assert!(
+            line < self.len,
+            "index out of bound: {line} >= {}",
+            self.len
+        );
+        let start = line / CHUNK_LINES * CHUNK_LINES;
+        let chunk = self.chunks[line / CHUNK_LINES].get_or_init(|| {
+            (start..self.len.min(start + CHUNK_LINES))
+                .map(|_| OnceLock::new())
+                .collect()
+        });
+        chunk[line - start].get_or_init(f)
+    }
+}
@@ -1,14 +1,31 @@
+use std::fmt::{Debug, Formatter, Result as FmtResult};
 use std::ops::Index;
 
-use super::Data;
+use super::{ColumnHandle, Data, DataFrame, DataFrameColumnIndex};
 
 #[derive(Debug, Clone, Copy)]
 pub struct Line<'df> {
     header: &'df Vec<String>,
-    line: &'df Vec<Data>,
+    cells: Cells<'df>,
     index_map: &'df [usize],
 }
 
+#[derive(Clone, Copy)]
+enum Cells<'df> {
+    Stored(&'df Vec<Data>),
+    //the line of a computed frame, see DataFrame::computed_cell
+    Computed(&'df DataFrame, usize),
+}
+
+impl<'df> Debug for Cells<'df> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            Cells::Stored(line) => line.fmt(f),
+            Cells::Computed(_df, index) => write!(f, "computed line {index}"),
+        }
+    }
+}
+
 impl<'df> IntoIterator for &Line<'df> {
     type Item = &'df Data;
 
@@ -27,7 +44,21 @@ impl<'df> Line<'df> {
     ) -> Line<'df> {
         Line {
             header,
-            line,
+            cells: Cells::Stored(line),
+            index_map,
+        }
+    }
+
+    ///the line index of the computed frame df
+    pub(super) fn computed(
+        header: &'df Vec<String>,
+        index_map: &'df [usize],
+        df: &'df DataFrame,
+        index: usize,
+    ) -> Line<'df> {
+        Line {
+            header,
+            cells: Cells::Computed(df, index),
             index_map,
         }
     }
@@ -37,8 +68,75 @@ impl<'df> Line<'df> {
         self
     }
 
+    ///like indexing, but the reference lives as long as the frame
+    pub(super) fn data(&self, index: usize) -> &'df Data {
+        let position = self.index_map[index];
+        match self.cells {
+            Cells::Stored(line) => &line[position],
+            Cells::Computed(df, index) => df.computed_cell(index, position),
+        }
+    }
+
+    ///the number of cells
+    pub fn len(&self) -> usize {
+        self.index_map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index_map.is_empty()
+    }
+
+    ///clones the cells of all columns, panics listing every column that is not in the header
+    pub fn select<I>(&self, columns: &[I]) -> Vec<Data>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let mut selected = Vec::with_capacity(columns.len());
+        self.select_into(columns, &mut selected);
+        selected
+    }
+
+    ///like select but reuses the allocation of out, which is cleared first
+    ///
+    ///names are looked up in the header for every call, select_handles does not look at it
+    pub fn select_into<I>(&self, columns: &[I], out: &mut Vec<Data>)
+    where
+        I: DataFrameColumnIndex,
+    {
+        out.clear();
+        //positions only need a bounds check, the header is collected for names and the panic
+        let positions = columns.iter().map(|column| match column.position() {
+            Some(index) if index < self.len() => Some(index),
+            _ => None,
+        });
+        if positions.clone().all(|index| index.is_some()) {
+            out.extend(positions.flatten().map(|index| self[index].clone()));
+            return;
+        }
+
+        let header: Vec<&str> = self.header().collect();
+        let mut missing = Vec::new();
+        for column in columns {
+            match column.try_get_usize(&header) {
+                Ok(index) => out.push(self[index].clone()),
+                Err(column) => missing.push(column),
+            }
+        }
+        if !missing.is_empty() {
+            panic!(
+                "index out of Bound: header is {header:?} but does not contain {}",
+                missing.join(", ")
+            );
+        }
+    }
+
+    ///select without looking at the header at all
+    pub fn select_handles(&self, handles: &[ColumnHandle]) -> Vec<Data> {
+        handles.iter().map(|handle| self[*handle].clone()).collect()
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &'df Data> + '_ {
-        self.index_map.iter().map(|index| &self.line[*index])
+        (0..self.index_map.len()).map(|index| self.data(index))
     }
 
     pub fn header(&self) -> impl Iterator<Item = &'df str> + '_ {
@@ -50,8 +148,15 @@ impl<'df> Index<usize> for Line<'df> {
     type Output = Data;
 
     fn index(&self, index: usize) -> &Self::Output {
-        let index = self.index_map[index];
-        &self.line[index]
+        self.data(index)
+    }
+}
+
+impl<'df> Index<ColumnHandle> for Line<'df> {
+    type Output = Data;
+
+    fn index(&self, index: ColumnHandle) -> &Self::Output {
+        &self[index.index()]
     }
 }
 
@@ -74,3 +179,70 @@ impl<'df> Index<&str> for Line<'df> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataFrame;
+
+    ///columns d, b and a of a frame with the columns a to d
+    fn view() -> DataFrame {
+        let rows = (0..3).map(|line| {
+            (0..4)
+                .map(|column| Data::Integer(line * 10 + column))
+                .collect()
+        });
+        DataFrame::new(vec!["a", "b", "c", "d"])
+            .append_lines(rows)
+            .drop_all_column_except(&["d", "b", "a"])
+    }
+
+    #[test]
+    fn select_reads_through_reordered_columns() {
+        let view = view();
+        let line = view.get(1).expect("the view has three lines");
+        let int = Data::Integer;
+        assert_eq!(line.len(), 3);
+        assert_eq!(line.select(&["a", "d"]), [int(10), int(13)]);
+        assert_eq!(line.select(&[2, 0, 1]), [int(10), int(13), int(11)]);
+
+        let handles = [view.column_handle("b"), view.column_handle("a")];
+        assert_eq!(line.select_handles(&handles), [int(11), int(10)]);
+        assert_eq!(line.select(&handles), [int(11), int(10)]);
+    }
+
+    #[test]
+    fn select_into_reuses_the_allocation() {
+        let view = view();
+        let mut out = Vec::with_capacity(8);
+        for (line_index, line) in view.iter().enumerate() {
+            line.select_into(&["b", "d"], &mut out);
+            let line_index = line_index as i32;
+            assert_eq!(
+                out,
+                [
+                    Data::Integer(line_index * 10 + 1),
+                    Data::Integer(line_index * 10 + 3)
+                ]
+            );
+            assert_eq!(out.capacity(), 8);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "does not contain 'c', 'x'")]
+    fn select_names_every_missing_column() {
+        let view = view();
+        let line = view.get(0).expect("the view has three lines");
+        line.select(&["a", "c", "d", "x"]);
+    }
+
+    #[test]
+    #[should_panic(expected = "does not contain 3")]
+    fn positions_are_checked_against_the_view() {
+        //position 3 is a column of the frame below, but not of the view
+        let view = view();
+        let line = view.get(0).expect("the view has three lines");
+        line.select(&[0, 3]);
+    }
+}
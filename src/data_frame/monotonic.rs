@@ -0,0 +1,188 @@
+use std::cmp::Ordering;
+
+use super::{
+    BackwardStep, BaseDataFrame, Data, DataFrame, DataFrameColumnIndex, IndexMap, InnerDataFrame,
+    MonotonicReport, SortOptions,
+};
+
+///how DataFrame::fix_monotonic treats lines whose time goes backwards
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MonotonicStrategy {
+    ///sorts the lines by time, lines with equal times keep their order
+    SortStable,
+    ///drops every line that is before the latest time of the lines kept above it
+    DropBackward,
+    ///sets the time of such a line to one second (or 1 for Integers and Floats)
+    ///after the time of the line above it, other types of cells stay as they are
+    ClampForward,
+}
+
+impl DataFrame {
+    ///self if the time column never goes backwards, otherwise the report lists every line
+    ///before the latest time above it, times are compared like in sort and Null cells are skipped
+    pub fn validate_monotonic<I>(self, time_column: I) -> Result<DataFrame, MonotonicReport>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = time_column.get_usize(self.header());
+        let mut steps = Vec::new();
+        let mut latest: Option<&Data> = None;
+        for (line, time) in self.column(index).enumerate() {
+            if time.is_null() {
+                continue;
+            }
+            match latest {
+                Some(latest) if is_before(time, latest) => steps.push(BackwardStep {
+                    line,
+                    time: time.clone(),
+                    latest: latest.clone(),
+                }),
+                _ => latest = Some(time),
+            }
+        }
+        if steps.is_empty() {
+            Ok(self)
+        } else {
+            Err(MonotonicReport {
+                column: self
+                    .get_on_header(index)
+                    .expect("index out of bound")
+                    .to_owned(),
+                steps,
+            })
+        }
+    }
+
+    ///makes the time column non decreasing, Null cells are skipped
+    pub fn fix_monotonic<I>(self, time_column: I, strategy: MonotonicStrategy) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = time_column.get_usize(self.header());
+        match strategy {
+            MonotonicStrategy::SortStable => self.sort_by_column(index, SortOptions::default()),
+            MonotonicStrategy::DropBackward => {
+                let mut kept = Vec::with_capacity(self.len());
+                let mut latest: Option<&Data> = None;
+                for (line, time) in self.column(index).enumerate() {
+                    match latest {
+                        _ if time.is_null() => {}
+                        Some(latest) if is_before(time, latest) => continue,
+                        _ => latest = Some(time),
+                    }
+                    kept.push(line);
+                }
+                InnerDataFrame::LineReorder {
+                    df: self,
+                    index_map: IndexMap::from(kept),
+                }
+                .into()
+            }
+            MonotonicStrategy::ClampForward => {
+                let mut column = Vec::with_capacity(self.len());
+                let mut latest: Option<Data> = None;
+                for time in self.column(index) {
+                    let time = match &latest {
+                        _ if time.is_null() => time.clone(),
+                        Some(latest) if is_before(time, latest) => {
+                            add_seconds(latest, 1).unwrap_or_else(|| time.clone())
+                        }
+                        _ => time.clone(),
+                    };
+                    if !time.is_null() {
+                        latest = Some(time.clone());
+                    }
+                    column.push(time);
+                }
+                let mut base = BaseDataFrame::from(self);
+                base.replace_column(index, column);
+                InnerDataFrame::Base { df: base }.into()
+            }
+        }
+    }
+}
+
+pub(super) fn is_before(time: &Data, latest: &Data) -> bool {
+    SortOptions::default().compare(time, latest) == Ordering::Less
+}
+
+///Integers and Floats count as seconds, None for other cells or if an Integer overflows
+pub(super) fn add_seconds(time: &Data, seconds: i64) -> Option<Data> {
+    match time {
+        Data::Integer(int) => i32::try_from(seconds)
+            .ok()
+            .and_then(|seconds| int.checked_add(seconds))
+            .map(Data::Integer),
+        Data::Float(float) => Some(Data::Float(float + seconds as f32)),
+        Data::Date(date) => Some(Data::Date(date.add_seconds(seconds))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///two backward jumps: 5 after 10 and 7 after 12, and a Null in between
+    fn times() -> DataFrame {
+        let rows = [
+            Some(1),
+            Some(10),
+            Some(5),
+            None,
+            Some(12),
+            Some(7),
+            Some(13),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(line, time)| {
+            let time = time.map_or(Data::Null, Data::Integer);
+            vec![time, Data::Integer(line as i32)]
+        });
+        DataFrame::new(vec!["time", "line"]).append_lines(rows)
+    }
+
+    fn cells(df: &DataFrame, index: usize) -> Vec<Data> {
+        df.column(index).cloned().collect()
+    }
+
+    fn ints(values: &[i32]) -> Vec<Data> {
+        values.iter().copied().map(Data::Integer).collect()
+    }
+
+    #[test]
+    fn every_backward_step_is_reported() {
+        let Err(report) = times().validate_monotonic("time") else {
+            panic!("the time goes backwards twice");
+        };
+        assert_eq!(report.column, "time");
+        let steps: Vec<(usize, Data, Data)> = report
+            .steps
+            .iter()
+            .map(|step| (step.line, step.time.clone(), step.latest.clone()))
+            .collect();
+        let int = Data::Integer;
+        assert_eq!(steps, [(2, int(5), int(10)), (5, int(7), int(12))]);
+
+        let sorted = times().fix_monotonic(0, MonotonicStrategy::SortStable);
+        assert!(sorted.validate_monotonic(0).is_ok());
+    }
+
+    #[test]
+    fn each_strategy_fixes_both_jumps() {
+        let sorted = times().fix_monotonic("time", MonotonicStrategy::SortStable);
+        assert_eq!(cells(&sorted, 1), ints(&[0, 2, 5, 1, 4, 6, 3]));
+
+        let dropped = times().fix_monotonic("time", MonotonicStrategy::DropBackward);
+        assert_eq!(cells(&dropped, 1), ints(&[0, 1, 3, 4, 6]));
+        assert!(dropped.validate_monotonic(0).is_ok());
+
+        let clamped = times().fix_monotonic("time", MonotonicStrategy::ClampForward);
+        let mut expected = ints(&[1, 10, 11, 0, 12, 13, 13]);
+        expected[3] = Data::Null;
+        assert_eq!(cells(&clamped, 0), expected);
+        assert_eq!(cells(&clamped, 1), ints(&[0, 1, 2, 3, 4, 5, 6]));
+        assert!(clamped.validate_monotonic(0).is_ok());
+    }
+}
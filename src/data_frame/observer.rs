@@ -0,0 +1,126 @@
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+///what a heavy operation reports to the observer of its thread
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OpEvent {
+    pub op: &'static str,
+    pub rows_in: usize,
+    pub rows_out: usize,
+    pub duration: Duration,
+}
+
+type Observer = Box<dyn Fn(OpEvent)>;
+
+thread_local! {
+    static OBSERVER: RefCell<Option<Observer>> = const { RefCell::new(None) };
+}
+
+///from now on from_file, sort, group_by, the joins, materialize and pivot_agg
+///on this thread call observer when they are done, replaces any previous observer
+pub fn set_op_observer(observer: Observer) {
+    OBSERVER.with(|cell| *cell.borrow_mut() = Some(observer));
+}
+
+pub fn clear_op_observer() {
+    OBSERVER.with(|cell| *cell.borrow_mut() = None);
+}
+
+///runs op and reports it, without an observer (and the tracing feature) this is just op()
+pub(super) fn observed<T>(
+    name: &'static str,
+    rows_in: usize,
+    rows_out: impl FnOnce(&T) -> usize,
+    op: impl FnOnce() -> T,
+) -> T {
+    let observing = OBSERVER.with(|cell| cell.borrow().is_some());
+    if !observing && !cfg!(feature = "tracing") {
+        return op();
+    }
+
+    #[cfg(feature = "tracing")]
+    let span = tracing::info_span!("rundas", op = name, rows_in).entered();
+
+    let start = Instant::now();
+    let result = op();
+    let event = OpEvent {
+        op: name,
+        rows_in,
+        rows_out: rows_out(&result),
+        duration: start.elapsed(),
+    };
+
+    #[cfg(feature = "tracing")]
+    {
+        tracing::info!(
+            rows_out = event.rows_out,
+            duration_us = event.duration.as_micros() as u64,
+            "{name} done"
+        );
+        drop(span);
+    }
+
+    if observing {
+        OBSERVER.with(|cell| {
+            if let Some(observer) = cell.borrow().as_ref() {
+                observer(event);
+            }
+        });
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{Data, DataFrame};
+
+    fn frame() -> DataFrame {
+        let rows = (0..100).map(|line| vec![Data::Integer(line % 3), Data::Integer(-line)]);
+        DataFrame::new(vec!["key", "value"]).append_lines(rows)
+    }
+
+    ///the name and line counts of every event
+    type Events = Rc<RefCell<Vec<(&'static str, usize, usize)>>>;
+
+    ///installs an observer that records every event
+    fn record() -> Events {
+        let events = Rc::new(RefCell::new(Vec::new()));
+        let recorded = events.clone();
+        set_op_observer(Box::new(move |event: OpEvent| {
+            recorded
+                .borrow_mut()
+                .push((event.op, event.rows_in, event.rows_out))
+        }));
+        events
+    }
+
+    #[test]
+    fn sort_and_group_by_are_reported() {
+        let events = record();
+        let df = frame();
+        let sorted = df.clone().sort(|line| line[1].as_integer());
+        let groups = df.group_by(|line| line[0].as_integer());
+        clear_op_observer();
+
+        assert_eq!(sorted.len(), 100);
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            *events.borrow(),
+            vec![("sort", 100, 100), ("group_by", 100, 3)]
+        );
+    }
+
+    #[test]
+    fn nothing_is_reported_after_clearing_or_on_other_threads() {
+        let events = record();
+        std::thread::spawn(|| frame().sort(|line| line[1].as_integer()))
+            .join()
+            .expect("the thread does not panic");
+        clear_op_observer();
+        frame().group_by(|line| line[0].as_integer());
+        assert!(events.borrow().is_empty());
+    }
+}
@@ -0,0 +1,419 @@
+use super::aggregate::CompensatedSum;
+use super::{Data, DataFrame, DataFrameColumnIndex};
+
+///how describe_with computes the quantiles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DescribeOptions {
+    ///use quantile_column_approx instead of sorting a copy of every column
+    pub approx: bool,
+    ///the rank error of the approximate quantiles as a fraction of the number of values
+    pub epsilon: f64,
+}
+
+impl Default for DescribeOptions {
+    fn default() -> Self {
+        DescribeOptions {
+            approx: false,
+            epsilon: 0.001,
+        }
+    }
+}
+
+impl DescribeOptions {
+    pub fn approx(epsilon: f64) -> DescribeOptions {
+        DescribeOptions {
+            approx: true,
+            epsilon,
+        }
+    }
+}
+
+impl DataFrame {
+    ///the q quantile of the Integer and Float cells of the column (other cells and NaN are skipped),
+    ///interpolated linearly between the two values next to it, None if there is no such cell
+    pub fn quantile_column<I>(&self, index: I, q: f64) -> Option<f64>
+    where
+        I: DataFrameColumnIndex,
+    {
+        assert!((0.0..=1.0).contains(&q), "q must be in [0, 1] but is {q}");
+        let mut values: Vec<f64> = self.column(index).filter_map(numeric).collect();
+        if values.is_empty() {
+            return None;
+        }
+        values.sort_by(f64::total_cmp);
+        Some(quantile_sorted(&values, q))
+    }
+
+    ///like quantile_column but in one pass with memory for O(log(epsilon * n) / epsilon) values
+    ///
+    ///the result is one of the values whose rank differs by at most epsilon * n
+    ///from the rank q * n of the exact quantile (n is the number of numeric cells),
+    ///there is no interpolation
+    pub fn quantile_column_approx<I>(&self, index: I, q: f64, epsilon: f64) -> Option<f64>
+    where
+        I: DataFrameColumnIndex,
+    {
+        assert!((0.0..=1.0).contains(&q), "q must be in [0, 1] but is {q}");
+        let mut sketch = QuantileSketch::new(epsilon);
+        self.column(index)
+            .filter_map(numeric)
+            .for_each(|value| sketch.insert(value));
+        sketch.query(q)
+    }
+
+    ///one line per column with at least one Integer or Float cell holding its name, count
+    ///(of non Null cells), mean, std, min, 25%, 50%, 75% and max (of the numeric cells),
+    ///Integers and Floats are mixed as numbers and the statistics are Floats.
+    ///the quantiles are interpolated linearly like quantile_column, other columns are skipped
+    pub fn describe(&self) -> DataFrame {
+        self.describe_with(DescribeOptions::default())
+    }
+
+    ///like describe, the quantiles are approximated if options.approx is set
+    pub fn describe_with(&self, options: DescribeOptions) -> DataFrame {
+        const QUANTILES: [f64; 3] = [0.25, 0.5, 0.75];
+
+        let header = vec![
+            "column", "count", "mean", "std", "min", "25%", "50%", "75%", "max",
+        ];
+        let mut data = Vec::new();
+        for (index, name) in self.header().enumerate() {
+            let mut count = 0;
+            let mut moments = Moments::default();
+            let mut sorted = Vec::new();
+            let mut sketch = QuantileSketch::new(options.epsilon);
+            for data in self.column(index) {
+                if !data.is_null() {
+                    count += 1;
+                }
+                if let Some(value) = numeric(data) {
+                    moments.add(value);
+                    if options.approx {
+                        sketch.insert(value);
+                    } else {
+                        sorted.push(value);
+                    }
+                }
+            }
+            if moments.count == 0 {
+                continue;
+            }
+            sorted.sort_by(f64::total_cmp);
+            let quantiles = QUANTILES.map(|q| {
+                if options.approx {
+                    sketch.query(q).expect("the sketch is not empty")
+                } else {
+                    quantile_sorted(&sorted, q)
+                }
+            });
+
+            let float = |value: f64| Data::Float(value as f32);
+            let mut line = vec![
+                Data::String(Box::new(name.to_owned())),
+                Data::Integer(count),
+                float(moments.mean()),
+                moments.std().map(float).unwrap_or(Data::Null),
+                float(moments.min),
+            ];
+            line.extend(quantiles.map(float));
+            line.push(float(moments.max));
+            data.push(line);
+        }
+        DataFrame::new(header).append_lines(data.into_iter())
+    }
+}
+
+fn numeric(data: &Data) -> Option<f64> {
+    match data {
+        Data::Integer(int) => Some(*int as f64),
+        Data::Float(float) if !float.is_nan() => Some(*float as f64),
+        _ => None,
+    }
+}
+
+///values has to be sorted and not empty
+pub(super) fn quantile_sorted(values: &[f64], q: f64) -> f64 {
+    let position = (values.len() - 1) as f64 * q;
+    let lower = position.floor() as usize;
+    let upper = position.ceil() as usize;
+    values[lower] + (values[upper] - values[lower]) * (position - lower as f64)
+}
+
+///count, mean, variance (Welford), min and max in one pass
+struct Moments {
+    count: usize,
+    sum: CompensatedSum,
+    mean: f64,
+    squares: f64,
+    min: f64,
+    max: f64,
+}
+
+impl Default for Moments {
+    fn default() -> Self {
+        Moments {
+            count: 0,
+            sum: CompensatedSum::default(),
+            mean: 0.0,
+            squares: 0.0,
+            min: f64::INFINITY,
+            max: f64::NEG_INFINITY,
+        }
+    }
+}
+
+impl Moments {
+    fn add(&mut self, value: f64) {
+        self.count += 1;
+        self.sum.add(value);
+        let delta = value - self.mean;
+        self.mean += delta / self.count as f64;
+        self.squares += delta * (value - self.mean);
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn mean(&self) -> f64 {
+        self.sum.sum() / self.count as f64
+    }
+
+    ///sample standard deviation like Agg::Std
+    fn std(&self) -> Option<f64> {
+        if self.count < 2 {
+            None
+        } else {
+            Some((self.squares / (self.count - 1) as f64).sqrt())
+        }
+    }
+}
+
+///a Greenwald-Khanna summary, new values are buffered and merged in sorted batches
+///
+///every tuple (value, g, delta) knows that the rank of its value is at least the sum of g
+///up to it and at most delta more, tuples are merged as long as g + delta <= 2 * epsilon * n
+struct QuantileSketch {
+    epsilon: f64,
+    count: usize,
+    tuples: Vec<(f64, usize, usize)>,
+    buffer: Vec<f64>,
+}
+
+impl QuantileSketch {
+    fn new(epsilon: f64) -> QuantileSketch {
+        assert!(
+            epsilon > 0.0 && epsilon < 1.0,
+            "epsilon must be in (0, 1) but is {epsilon}"
+        );
+        QuantileSketch {
+            epsilon,
+            count: 0,
+            tuples: Vec::new(),
+            buffer: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, value: f64) {
+        self.buffer.push(value);
+        if self.buffer.len() as f64 >= 1.0 / (2.0 * self.epsilon) {
+            self.flush();
+        }
+    }
+
+    fn flush(&mut self) {
+        if self.buffer.is_empty() {
+            return;
+        }
+        self.count += self.buffer.len();
+        self.buffer.sort_by(f64::total_cmp);
+
+        //a new value between two tuples is as uncertain as the gap it falls into,
+        //before the first and after the last tuple its rank is exact
+        let mut merged = Vec::with_capacity(self.tuples.len() + self.buffer.len());
+        let mut old = self.tuples.drain(..).peekable();
+        for value in self.buffer.drain(..) {
+            while let Some(tuple) = old.next_if(|(old_value, _g, _delta)| *old_value <= value) {
+                merged.push(tuple);
+            }
+            let delta = match old.peek() {
+                Some((_value, g, delta)) if !merged.is_empty() => g + delta - 1,
+                _ => 0,
+            };
+            merged.push((value, 1, delta));
+        }
+        merged.extend(old);
+        self.tuples = merged;
+        self.compress();
+    }
+
+    ///merges a tuple into its successor while that keeps the error bound,
+    ///the first and the last tuple (min and max) are kept
+    fn compress(&mut self) {
+        let threshold = (2.0 * self.epsilon * self.count as f64).floor() as usize;
+        let len = self.tuples.len();
+        let mut kept: Vec<(f64, usize, usize)> = Vec::with_capacity(len);
+        for (i, tuple) in self.tuples.drain(..).enumerate().rev() {
+            match kept.last_mut() {
+                Some((_value, next_g, next_delta))
+                    if i > 0 && tuple.1 + *next_g + *next_delta <= threshold =>
+                {
+                    *next_g += tuple.1
+                }
+                _ => kept.push(tuple),
+            }
+        }
+        kept.reverse();
+        self.tuples = kept;
+    }
+
+    fn query(&mut self, q: f64) -> Option<f64> {
+        self.flush();
+        if self.tuples.is_empty() {
+            return None;
+        }
+        let rank = (q * self.count as f64).ceil().max(1.0);
+        let mut min_rank = 0;
+        let mut best = (f64::INFINITY, self.tuples[0].0);
+        for (value, g, delta) in &self.tuples {
+            min_rank += g;
+            let max_rank = (min_rank + delta) as f64;
+            let error = (rank - min_rank as f64).max(max_rank - rank);
+            if error < best.0 {
+                best = (error, *value);
+            }
+        }
+        Some(best.1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    ///0 to lines - 1 in a scrambled order, so the rank of a value is the value + 1
+    fn uniform(lines: i32) -> DataFrame {
+        let rows = (0..lines).map(|line| vec![Data::Integer((line * 7919) % lines)]);
+        DataFrame::new(vec!["value"]).append_lines(rows)
+    }
+
+    #[test]
+    fn approximate_quantiles_are_within_epsilon_rank() {
+        let lines = 100_000;
+        let df = uniform(lines);
+        for epsilon in [0.01, 0.001] {
+            for q in [0.0, 0.1, 0.5, 0.9, 1.0] {
+                let value = df
+                    .quantile_column_approx("value", q, epsilon)
+                    .expect("the column is numeric");
+                let rank = value + 1.0;
+                let exact = (q * lines as f64).max(1.0);
+                assert!(
+                    (rank - exact).abs() <= epsilon * lines as f64,
+                    "q {q} epsilon {epsilon}: rank {rank} instead of {exact}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn the_sketch_stays_small() {
+        let epsilon = 0.001;
+        let mut sketch = QuantileSketch::new(epsilon);
+        for line in 0..200_000 {
+            sketch.insert(((line * 7919) % 200_000) as f64);
+        }
+        sketch.flush();
+        assert_eq!(sketch.count, 200_000);
+        //O(log(epsilon * n) / epsilon) with a small constant
+        assert!(
+            sketch.tuples.len() < 10_000,
+            "{} tuples",
+            sketch.tuples.len()
+        );
+    }
+
+    ///4, 1, 3, 2 as Integers and Floats, a Null and a name
+    fn mixed() -> DataFrame {
+        let rows = [
+            Data::Integer(4),
+            Data::Float(1.0),
+            Data::Integer(3),
+            Data::Float(2.0),
+            Data::Null,
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(line, value)| vec![value, Data::String(Box::new(format!("line {line}")))]);
+        DataFrame::new(vec!["value", "name"]).append_lines(rows)
+    }
+
+    #[test]
+    fn small_inputs_are_interpolated_linearly() {
+        let df = mixed();
+        for (q, expected) in [
+            (0.0, 1.0),
+            (0.25, 1.75),
+            (0.5, 2.5),
+            (0.75, 3.25),
+            (1.0, 4.0),
+        ] {
+            assert_eq!(df.quantile_column("value", q), Some(expected), "q {q}");
+        }
+        assert_eq!(df.quantile_column("name", 0.5), None);
+
+        let single =
+            DataFrame::new(vec!["value"]).append_lines([vec![Data::Integer(5)]].into_iter());
+        assert_eq!(single.quantile_column(0, 0.3), Some(5.0));
+        let pair = DataFrame::new(vec!["value"])
+            .append_lines([vec![Data::Integer(10)], vec![Data::Integer(0)]].into_iter());
+        assert_eq!(pair.quantile_column(0, 0.3), Some(3.0));
+    }
+
+    #[test]
+    fn describe_summarises_mixed_columns() {
+        let described = mixed().describe();
+        assert_eq!(
+            described.header().collect::<Vec<_>>(),
+            vec!["column", "count", "mean", "std", "min", "25%", "50%", "75%", "max"]
+        );
+        //the name column has no numbers and is skipped
+        assert_eq!(described.len(), 1);
+        let line = described.get(0).expect("the value column is described");
+        let floats = |values: &[f64]| {
+            values
+                .iter()
+                .map(|value| Data::Float(*value as f32))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(line["column"], Data::String(Box::new("value".to_owned())));
+        assert_eq!(line["count"], Data::Integer(4));
+        let cells: Vec<Data> = line.iter().skip(2).cloned().collect();
+        assert_eq!(
+            cells,
+            floats(&[2.5, (5.0f64 / 3.0).sqrt(), 1.0, 1.75, 2.5, 3.25, 4.0])
+        );
+
+        let single =
+            DataFrame::new(vec!["value"]).append_lines([vec![Data::Integer(5)]].into_iter());
+        let line = single.describe();
+        assert_eq!(
+            line.get(0).expect("the column is described")["std"],
+            Data::Null
+        );
+    }
+
+    #[test]
+    fn describe_skips_empty_and_string_columns_and_nan() {
+        assert!(DataFrame::new(vec!["value"]).describe().is_empty());
+        let strings = mixed().drop_column("value");
+        assert!(strings.describe().is_empty());
+
+        let with_nan = mixed().append_lines([vec![Data::Float(f32::NAN), Data::Null]].into_iter());
+        let described = with_nan.describe();
+        assert_eq!(described.len(), 1);
+        let line = described.get(0).expect("the value column is described");
+        //NaN counts as a cell but not as a number
+        assert_eq!(line["count"], Data::Integer(5));
+        assert_eq!(line["mean"], Data::Float(2.5));
+        assert_eq!(line["max"], Data::Float(4.0));
+    }
+}
@@ -0,0 +1,261 @@
+use std::io::Error as IoError;
+
+use super::{Data, StringCache};
+
+pub(super) type ColumnParser = Box<dyn FnMut(&str) -> Result<Data, String>>;
+
+///how a single cell is turned into Data
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParseRules {
+    pub seperator: char,
+    ///if false every cell that is not a group stays a String
+    pub infer_types: bool,
+    ///two numbers seperated by a space become a Vec2D
+    pub vec2d: bool,
+    ///a group in brackets becomes a Vector, otherwise it stays a String as written
+    pub vectors: bool,
+    ///further words (ignoring case) that are read as true or false besides "true" and "false",
+    ///they are only tried after numbers so "1" and "0" stay Integers
+    pub truthy: &'static [&'static str],
+    pub falsy: &'static [&'static str],
+}
+
+impl Default for ParseRules {
+    fn default() -> Self {
+        ParseRules {
+            seperator: ',',
+            infer_types: true,
+            vec2d: true,
+            vectors: true,
+            truthy: &[],
+            falsy: &[],
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct ReadOptions {
+    pub(super) rules: ParseRules,
+    pub(super) drop_repeated_headers: bool,
+    pub(super) utf16: bool,
+    parsers: Vec<(String, ColumnParser)>,
+    pub(super) string_cache: Option<StringCache>,
+    na_values: Vec<String>,
+    column_na_values: Vec<(String, Vec<String>)>,
+}
+
+impl ReadOptions {
+    pub fn new() -> ReadOptions {
+        ReadOptions::default()
+    }
+
+    pub fn seperator(mut self, seperator: char) -> ReadOptions {
+        self.rules.seperator = seperator;
+        self
+    }
+
+    ///the seperator of rules replaces the one set so far
+    pub fn parse_rules(mut self, rules: ParseRules) -> ReadOptions {
+        self.rules = rules;
+        self
+    }
+
+    ///lines equal to the header (e.g. left over from concatenating files) are skipped
+    pub fn drop_repeated_headers(mut self, drop: bool) -> ReadOptions {
+        self.drop_repeated_headers = drop;
+        self
+    }
+
+    ///cells whose trimmed text is one of values are read as Null in every column,
+    ///a quoted cell is never replaced
+    pub fn na_values(mut self, values: &[&str]) -> ReadOptions {
+        self.na_values
+            .extend(values.iter().map(|value| value.to_string()));
+        self
+    }
+
+    ///like na_values but only for this column, in addition to the ones for every column
+    pub fn na_values_for(mut self, column: &str, values: &[&str]) -> ReadOptions {
+        self.column_na_values.push((
+            column.to_owned(),
+            values.iter().map(|value| value.to_string()).collect(),
+        ));
+        self
+    }
+
+    ///a file starting with a UTF-16 byte order mark is transcoded instead of being an error,
+    ///it is read into memory as a whole for that
+    pub fn utf16(mut self, transcode: bool) -> ReadOptions {
+        self.utf16 = transcode;
+        self
+    }
+
+    ///the cells of this column are parsed by parser from the raw trimmed text
+    ///instead of the default type inference
+    pub fn parser<F>(mut self, column: &str, parser: F) -> ReadOptions
+    where
+        F: FnMut(&str) -> Result<Data, String> + 'static,
+    {
+        self.parsers.push((column.to_owned(), Box::new(parser)));
+        self
+    }
+
+    ///the String cells read are stored as Categories in cache
+    pub fn string_cache(mut self, cache: &StringCache) -> ReadOptions {
+        self.string_cache = Some(cache.clone());
+        self
+    }
+
+    ///moves the registered parsers to the position of their column in the header
+    pub(super) fn take_parsers(
+        &mut self,
+        header: &[String],
+    ) -> Result<Vec<Option<ColumnParser>>, IoError> {
+        let mut parsers: Vec<Option<ColumnParser>> = header.iter().map(|_| None).collect();
+        for (column, parser) in self.parsers.drain(..) {
+            let index = header
+                .iter()
+                .position(|head_elem| *head_elem == column)
+                .ok_or_else(|| {
+                    IoError::other(format!(
+                        "a parser is registered for '{column}' but the header does not contain it"
+                    ))
+                })?;
+            parsers[index] = Some(parser);
+        }
+        Ok(parsers)
+    }
+
+    ///the values read as Null at the position of their column in the header
+    pub(super) fn take_na_values(
+        &mut self,
+        header: &[String],
+    ) -> Result<Vec<Vec<String>>, IoError> {
+        let mut na_values: Vec<Vec<String>> =
+            header.iter().map(|_| self.na_values.clone()).collect();
+        for (column, values) in self.column_na_values.drain(..) {
+            let index = header
+                .iter()
+                .position(|head_elem| *head_elem == column)
+                .ok_or_else(|| {
+                    IoError::other(format!(
+                        "na values are registered for '{column}' but the header does not contain it"
+                    ))
+                })?;
+            na_values[index].extend(values);
+        }
+        Ok(na_values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DataFrame;
+
+    fn hex(raw: &str) -> Result<Data, String> {
+        let digits = raw.trim_start_matches("0x");
+        i32::from_str_radix(digits, 16)
+            .map(Data::Integer)
+            .map_err(|err| format!("'{raw}' is not hex: {err}"))
+    }
+
+    #[test]
+    fn hex_parser_reads_integers() {
+        let csv = "id,name\n0x1f,a\n 0xFF ,b\n10,c\n".to_owned();
+        let df = DataFrame::from_string_with_options(csv, ReadOptions::new().parser("id", hex))
+            .expect("every id is hex");
+        let ids: Vec<Data> = df.iter().map(|line| line["id"].clone()).collect();
+        assert_eq!(
+            ids,
+            vec![Data::Integer(31), Data::Integer(255), Data::Integer(16)]
+        );
+        //the other columns are still inferred
+        assert_eq!(
+            df.get(0).expect("three lines")["name"],
+            Data::String(Box::new("a".to_owned()))
+        );
+    }
+
+    #[test]
+    fn parser_error_names_line_and_column() {
+        let csv = "id,name\n0x1f,a\n0xzz,b\n".to_owned();
+        let Err(err) =
+            DataFrame::from_string_with_options(csv, ReadOptions::new().parser("id", hex))
+        else {
+            panic!("0xzz is not hex");
+        };
+        assert!(
+            err.to_string()
+                .starts_with("Line 3 column 'id': '0xzz' is not hex"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn strings_read_with_a_cache_are_categories() {
+        let cache = StringCache::new();
+        let csv = "id,name\n1,a\n2,b\n3,a\n".to_owned();
+        let df = DataFrame::from_string_with_options(csv, ReadOptions::new().string_cache(&cache))
+            .expect("the csv is valid");
+        let names: Vec<Data> = df.iter().map(|line| line["name"].clone()).collect();
+        assert_eq!(
+            names,
+            vec![
+                Data::Category(cache.intern("a")),
+                Data::Category(cache.intern("b")),
+                Data::Category(cache.intern("a")),
+            ]
+        );
+        assert_eq!(cache.len(), 2);
+        assert!(df.string_cache().is_some_and(|own| own.same_as(&cache)));
+        //cells of other types are kept
+        assert_eq!(df.get(0).expect("three lines")["id"], Data::Integer(1));
+    }
+
+    #[test]
+    fn parser_for_a_missing_column_is_an_error() {
+        let csv = "id,name\n1,a\n".to_owned();
+        let options = ReadOptions::new().parser("code", hex);
+        assert!(DataFrame::from_string_with_options(csv, options).is_err());
+    }
+
+    fn column_of(df: &DataFrame, name: &str) -> Vec<Data> {
+        df.iter().map(|line| line[name].clone()).collect()
+    }
+
+    #[test]
+    fn a_sentinel_is_null_only_in_its_column() {
+        let csv = "temperature,altitude\n-999,-999\n12,NA\n\"-999\",3\n".to_owned();
+        let options = ReadOptions::new()
+            .na_values(&["NA"])
+            .na_values_for("temperature", &["-999"]);
+        let df = DataFrame::from_string_with_options(csv, options).expect("the csv is valid");
+        //a quoted cell is never replaced
+        assert_eq!(
+            column_of(&df, "temperature"),
+            vec![
+                Data::Null,
+                Data::Integer(12),
+                Data::String(Box::new("-999".to_owned()))
+            ]
+        );
+        assert_eq!(
+            column_of(&df, "altitude"),
+            vec![Data::Integer(-999), Data::Null, Data::Integer(3)]
+        );
+    }
+
+    #[test]
+    fn na_values_for_a_missing_column_are_an_error() {
+        let csv = "id,name\n1,a\n".to_owned();
+        let options = ReadOptions::new().na_values_for("temperature", &["-999"]);
+        let Err(err) = DataFrame::from_string_with_options(csv, options) else {
+            panic!("the header has no temperature");
+        };
+        assert_eq!(
+            err.to_string(),
+            "na values are registered for 'temperature' but the header does not contain it"
+        );
+    }
+}
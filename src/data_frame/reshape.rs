@@ -0,0 +1,921 @@
+use std::collections::{HashMap, HashSet};
+
+use super::{observed, Agg, Data, DataFrame, DataFrameColumnIndex, ShapeError};
+
+impl DataFrame {
+    ///builds a pivot table with one line per distinct value of the index column
+    ///and one column per distinct value of the columns column (both in order of first occurrence),
+    ///all values falling into the same cell are combined with agg
+    ///
+    ///cells without any value are Null, or Integer(0) for Count if zero_fill_count is set
+    pub fn pivot_agg<I, C, V>(
+        self,
+        index: I,
+        columns: C,
+        values: V,
+        agg: Agg,
+        zero_fill_count: bool,
+    ) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        C: DataFrameColumnIndex,
+        V: DataFrameColumnIndex,
+    {
+        observed("pivot_agg", self.len(), DataFrame::len, move || {
+            let index = index.get_usize(self.header());
+            let columns = columns.get_usize(self.header());
+            let values = values.get_usize(self.header());
+
+            let (positions, mut rows, header) = self.pivot_positions(index, columns);
+            let mut cells: HashMap<(usize, usize), Vec<Data>> = HashMap::new();
+            for (line, position) in self.iter().zip(positions) {
+                cells
+                    .entry(position)
+                    .or_default()
+                    .push(line[values].clone());
+            }
+
+            let missing = if zero_fill_count && agg == Agg::Count {
+                Data::Integer(0)
+            } else {
+                Data::Null
+            };
+            let num_pivot_columns = header.len() - 1;
+            let data = rows.drain(..).enumerate().map(|(row, key)| {
+                let mut line = vec![key];
+                line.extend((0..num_pivot_columns).map(|column| {
+                    cells
+                        .get(&(row, column))
+                        .map(|cell| agg.apply(cell))
+                        .unwrap_or_else(|| missing.clone())
+                }));
+                line
+            });
+
+            DataFrame::new(header).append_lines(data)
+        })
+    }
+
+    ///like pivot_agg but every cell takes the value of a single line,
+    ///if more than one line falls into a cell the last one wins or,
+    ///if error_on_collision is set, the second one is an error. cells without any line get fill
+    pub fn pivot<I, C, V>(
+        self,
+        index: I,
+        columns: C,
+        values: V,
+        fill: Data,
+        error_on_collision: bool,
+    ) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+        C: DataFrameColumnIndex,
+        V: DataFrameColumnIndex,
+    {
+        observed(
+            "pivot",
+            self.len(),
+            |result: &Result<DataFrame, ShapeError>| result.as_ref().map_or(0, DataFrame::len),
+            move || {
+                let index = index.get_usize(self.header());
+                let columns = columns.get_usize(self.header());
+                let values = values.get_usize(self.header());
+
+                let (positions, mut rows, header) = self.pivot_positions(index, columns);
+                let mut cells: HashMap<(usize, usize), Data> = HashMap::new();
+                for (line_index, (line, position)) in self.iter().zip(positions).enumerate() {
+                    let previous = cells.insert(position, line[values].clone());
+                    if previous.is_some() && error_on_collision {
+                        return Err(ShapeError::DuplicateCell {
+                            line: line_index,
+                            row: line[index].clone(),
+                            column: header[position.1 + 1].clone(),
+                        });
+                    }
+                }
+
+                let num_pivot_columns = header.len() - 1;
+                let data = rows.drain(..).enumerate().map(|(row, key)| {
+                    let mut line = vec![key];
+                    line.extend((0..num_pivot_columns).map(|column| {
+                        cells.remove(&(row, column)).unwrap_or_else(|| fill.clone())
+                    }));
+                    line
+                });
+
+                Ok(DataFrame::new(header).append_lines(data))
+            },
+        )
+    }
+
+    ///the (row, column) of every line of a pivot, the keys of the rows and the header
+    ///(the index column followed by the pivot columns), rows and columns are in order of
+    ///first occurrence. the cells are compared as Data, so an Integer 1 and a String "1"
+    ///get a row or column each, a column name that is taken gets a suffix like in transpose_on
+    fn pivot_positions(
+        &self,
+        index: usize,
+        columns: usize,
+    ) -> (Vec<(usize, usize)>, Vec<Data>, Vec<String>) {
+        let index_name = self
+            .get_on_header(index)
+            .expect("index out of bound")
+            .to_owned();
+        let mut taken = HashSet::from([index_name.clone()]);
+        let mut header = vec![index_name];
+        let mut rows = Vec::new();
+        let mut row_keys: HashMap<&Data, usize> = HashMap::new();
+        let mut column_keys: HashMap<&Data, usize> = HashMap::new();
+
+        let mut positions = Vec::with_capacity(self.len());
+        for line in self.iter() {
+            let row = *row_keys.entry(line.data(index)).or_insert_with(|| {
+                rows.push(line[index].clone());
+                rows.len() - 1
+            });
+            let column = *column_keys.entry(line.data(columns)).or_insert_with(|| {
+                header.push(unique_name(&mut taken, line[columns].as_string()));
+                header.len() - 2
+            });
+            positions.push((row, column));
+        }
+        (positions, rows, header)
+    }
+
+    ///one line per element of the Vector in the column with the other cells copied,
+    ///only the outer Vector is split so nested Vectors stay elements, other cells keep their line.
+    ///a line with an empty Vector is dropped or, if empty_as_null is set, kept with Null
+    pub fn explode<I>(self, index: I, empty_as_null: bool) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        observed("explode", self.len(), DataFrame::len, move || {
+            let index = index.get_usize(self.header());
+            let header: Vec<&str> = self.header().collect();
+            let mut data = Vec::with_capacity(self.len());
+            for line in self.iter() {
+                let elements: &[Data] = match &line[index] {
+                    Data::Vector(vec) if vec.is_empty() && empty_as_null => &[Data::Null],
+                    Data::Vector(vec) => vec,
+                    data => std::slice::from_ref(data),
+                };
+                for element in elements {
+                    let mut new_line: Vec<Data> = line.iter().cloned().collect();
+                    new_line[index] = element.clone();
+                    data.push(new_line);
+                }
+            }
+            DataFrame::new(header).append_lines(data.drain(..))
+        })
+    }
+
+    ///replaces a Vector column by one column per vector element at the same position
+    ///
+    ///if names is empty the columns are named {header}_0.. and their number is the length of the first vector,
+    ///shorter vectors are padded with Null, longer ones are an error unless truncate is set
+    pub fn explode_to_columns<I>(
+        self,
+        index: I,
+        names: &[&str],
+        truncate: bool,
+    ) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+
+        let width = if names.is_empty() {
+            match self.get(0).map(|line| line[index].clone()) {
+                Some(Data::Vector(vec)) => vec.len(),
+                Some(found) => return Err(ShapeError::NotAVector { line: 0, found }),
+                None => 0,
+            }
+        } else {
+            names.len()
+        };
+
+        let mut header = Vec::new();
+        for (i, head_elem) in self.header().enumerate() {
+            if i != index {
+                header.push(head_elem.to_owned());
+            } else if names.is_empty() {
+                header.extend((0..width).map(|n| format!("{head_elem}_{n}")));
+            } else {
+                header.extend(names.iter().map(|name| name.to_string()));
+            }
+        }
+
+        let mut data = Vec::with_capacity(self.len());
+        for (line_index, line) in self.iter().enumerate() {
+            let mut new_line = Vec::with_capacity(header.len());
+            for (i, elem) in line.iter().enumerate() {
+                if i != index {
+                    new_line.push(elem.clone());
+                    continue;
+                }
+                let vec = elem.try_as_vec().ok_or_else(|| ShapeError::NotAVector {
+                    line: line_index,
+                    found: elem.clone(),
+                })?;
+                if vec.len() > width && !truncate {
+                    return Err(ShapeError::LineLength {
+                        line: line_index,
+                        expected: width,
+                        found: vec.len(),
+                    });
+                }
+                new_line.extend(vec.iter().take(width).cloned());
+                new_line.extend((vec.len()..width).map(|_| Data::Null));
+            }
+            data.push(new_line);
+        }
+
+        Ok(DataFrame::new(header).append_lines(data.drain(..)))
+    }
+
+    ///replaces a column by one column per part of its text split at delimiter
+    ///
+    ///if strict every cell has to split into exactly names.len() parts,
+    ///otherwise missing parts are Null and extra parts are dropped,
+    ///the parts are parsed like a cell in a file if infer_types is set and Strings otherwise
+    ///
+    ///like in a file an empty part is Null, a Null cell gives Null in every new column
+    pub fn split_column<I>(
+        self,
+        index: I,
+        delimiter: &str,
+        names: &[&str],
+        strict: bool,
+        infer_types: bool,
+    ) -> Result<DataFrame, ShapeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let rules = self.parse_rules();
+
+        let mut header = Vec::new();
+        for (i, head_elem) in self.header().enumerate() {
+            if i == index {
+                header.extend(names.iter().map(|name| name.to_string()));
+            } else {
+                header.push(head_elem.to_owned());
+            }
+        }
+
+        let mut data = Vec::with_capacity(self.len());
+        for (line_index, line) in self.iter().enumerate() {
+            let mut new_line = Vec::with_capacity(header.len());
+            for (i, elem) in line.iter().enumerate() {
+                if i != index {
+                    new_line.push(elem.clone());
+                    continue;
+                }
+                if elem.is_null() {
+                    new_line.extend(names.iter().map(|_| Data::Null));
+                    continue;
+                }
+                let string = elem.as_string();
+                let parts: Vec<&str> = string.split(delimiter).collect();
+                if strict && parts.len() != names.len() {
+                    return Err(ShapeError::SplitParts {
+                        line: line_index,
+                        expected: names.len(),
+                        found: parts.len(),
+                        value: elem.clone(),
+                    });
+                }
+                new_line.extend(
+                    parts
+                        .iter()
+                        .take(names.len())
+                        .map(|part| match part.trim() {
+                            "" => Data::Null,
+                            _ if infer_types => Data::parse_with(&rules, part),
+                            _ => Data::String(Box::new(part.to_string())),
+                        }),
+                );
+                new_line.extend((parts.len()..names.len()).map(|_| Data::Null));
+            }
+            data.push(new_line);
+        }
+
+        Ok(DataFrame::new(header).append_lines(data.drain(..)))
+    }
+
+    ///one line per distinct combination of the key columns (in order of first occurrence)
+    ///with the values of value_column in these lines collected into a Vector in line order,
+    ///a single line still gives a Vector with one element
+    pub fn implode_by<I>(self, key_columns: &[I], value_column: I) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let key_indizes: Vec<usize> = key_columns
+            .iter()
+            .map(|column| column.get_usize(self.header()))
+            .collect();
+        let value_index = value_column.get_usize(self.header());
+
+        let mut group_positions = HashMap::new();
+        let mut groups: Vec<(Vec<Data>, Vec<Data>)> = Vec::new();
+        for line in self.iter() {
+            let key = line.select(&key_indizes);
+            let position = *group_positions.entry(key.clone()).or_insert_with(|| {
+                groups.push((key, Vec::new()));
+                groups.len() - 1
+            });
+            groups[position].1.push(line[value_index].clone());
+        }
+
+        let mut header: Vec<String> = key_indizes
+            .iter()
+            .map(|index| {
+                self.header()
+                    .nth(*index)
+                    .expect("index is valid")
+                    .to_owned()
+            })
+            .collect();
+        header.push(
+            self.header()
+                .nth(value_index)
+                .expect("index is valid")
+                .to_owned(),
+        );
+        let data = groups.drain(..).map(|(mut key, values)| {
+            key.push(Data::Vector(Box::new(values)));
+            key
+        });
+        DataFrame::new(header).append_lines(data)
+    }
+
+    ///the inverse of pivot: one line per line of self and value column (in this order)
+    ///holding the id columns, the name of the value column as a String and its cell,
+    ///an empty value_columns takes every column that is not an id column
+    pub fn melt(
+        self,
+        id_columns: &[&str],
+        value_columns: &[&str],
+        var_name: &str,
+        value_name: &str,
+    ) -> DataFrame {
+        let id_indizes: Vec<usize> = id_columns
+            .iter()
+            .map(|column| column.get_usize(self.header()))
+            .collect();
+        let value_indizes: Vec<usize> = if value_columns.is_empty() {
+            (0..self.num_columns())
+                .filter(|index| !id_indizes.contains(index))
+                .collect()
+        } else {
+            value_columns
+                .iter()
+                .map(|column| column.get_usize(self.header()))
+                .collect()
+        };
+        let names: Vec<Data> = value_indizes
+            .iter()
+            .map(|index| {
+                let name = self.header().nth(*index).expect("index is valid");
+                Data::String(Box::new(name.to_owned()))
+            })
+            .collect();
+
+        let mut header: Vec<String> = id_columns.iter().map(|column| column.to_string()).collect();
+        header.push(var_name.to_owned());
+        header.push(value_name.to_owned());
+
+        let mut data = Vec::with_capacity(self.len() * value_indizes.len());
+        for line in self.iter() {
+            for (index, name) in value_indizes.iter().zip(&names) {
+                let mut new_line = line.select(&id_indizes);
+                new_line.push(name.clone());
+                new_line.push(line[*index].clone());
+                data.push(new_line);
+            }
+        }
+        DataFrame::new(header).append_lines(data.drain(..))
+    }
+
+    ///swaps lines and columns: one line per column of self starting with its name in "column",
+    ///followed by one column per line of self named by its position.
+    ///a frame without lines gives only the names, one without columns an empty frame
+    pub fn transpose(self) -> DataFrame {
+        let names = (0..self.len()).map(|line| line.to_string()).collect();
+        self.transpose_with(names, None, "column".to_owned())
+    }
+
+    ///like transpose but the new columns are named by the cells of the key column
+    ///(formatted with Display, a name that is taken gets the first of "_2", "_3", ... appended
+    ///that is not taken)
+    ///and the key column itself becomes the header of the first column instead of a line
+    pub fn transpose_on<I>(self, key: I) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let key = key.get_usize(self.header());
+        let first = self
+            .get_on_header(key)
+            .expect("index out of bound")
+            .to_owned();
+        let mut taken = HashSet::from([first.clone()]);
+        let names = self
+            .column(key)
+            .map(|data| unique_name(&mut taken, data.to_string()))
+            .collect();
+        self.transpose_with(names, Some(key), first)
+    }
+
+    fn transpose_with(self, names: Vec<String>, skip: Option<usize>, first: String) -> DataFrame {
+        observed("transpose", self.len(), DataFrame::len, move || {
+            let mut header = Vec::with_capacity(names.len() + 1);
+            header.push(first);
+            header.extend(names);
+
+            let data: Vec<Vec<Data>> = self
+                .header()
+                .enumerate()
+                .filter(|(index, _name)| Some(*index) != skip)
+                .map(|(index, name)| {
+                    let mut line = Vec::with_capacity(self.len() + 1);
+                    line.push(Data::String(Box::new(name.to_owned())));
+                    line.extend(self.column(index).cloned());
+                    line
+                })
+                .collect();
+            DataFrame::with_capacity(header, data.len()).append_lines(data.into_iter())
+        })
+    }
+}
+
+///name or, if it is taken, name with the first of "_2", "_3", ... that is not taken
+fn unique_name(taken: &mut HashSet<String>, name: String) -> String {
+    let mut unique = name.clone();
+    let mut count = 1;
+    while taken.contains(&unique) {
+        count += 1;
+        unique = format!("{name}_{count}");
+    }
+    taken.insert(unique.clone());
+    unique
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseRules;
+
+    fn vector(values: &[i32]) -> Data {
+        Data::Vector(Box::new(values.iter().map(Data::from).collect()))
+    }
+
+    ///an id column and a Vector column acc holding the given vectors
+    fn ragged(vectors: &[&[i32]]) -> DataFrame {
+        DataFrame::new(vec!["id", "acc"]).append_lines(
+            vectors
+                .iter()
+                .enumerate()
+                .map(|(id, values)| vec![Data::Integer(id as i32), vector(values)]),
+        )
+    }
+
+    fn lines(df: &DataFrame) -> Vec<Vec<Data>> {
+        df.iter()
+            .map(|line| line.iter().cloned().collect())
+            .collect()
+    }
+
+    #[test]
+    fn shorter_vectors_are_padded_with_null() {
+        let df = ragged(&[&[1, 2, 3], &[4], &[], &[5, 6]])
+            .explode_to_columns("acc", &["x", "y", "z"], false)
+            .expect("no vector is longer than three");
+
+        assert_eq!(df.header().collect::<Vec<_>>(), ["id", "x", "y", "z"]);
+        let (int, null) = (Data::Integer, Data::Null);
+        assert_eq!(
+            lines(&df),
+            [
+                vec![int(0), int(1), int(2), int(3)],
+                vec![int(1), int(4), null.clone(), null.clone()],
+                vec![int(2), null.clone(), null.clone(), null.clone()],
+                vec![int(3), int(5), int(6), null],
+            ]
+        );
+    }
+
+    #[test]
+    fn longer_vectors_are_an_error_unless_truncated() {
+        //without names the first vector decides the number of columns
+        let ragged = ragged(&[&[1, 2], &[3], &[4, 5, 6]]);
+        let Err(err) = ragged.clone().explode_to_columns("acc", &[], false) else {
+            panic!("the third vector is longer than the first")
+        };
+        assert_eq!(
+            err,
+            ShapeError::LineLength {
+                line: 2,
+                expected: 2,
+                found: 3
+            }
+        );
+
+        let df = ragged
+            .explode_to_columns(1, &[], true)
+            .expect("longer vectors are truncated");
+        assert_eq!(df.header().collect::<Vec<_>>(), ["id", "acc_0", "acc_1"]);
+        let (int, null) = (Data::Integer, Data::Null);
+        assert_eq!(
+            lines(&df),
+            [
+                vec![int(0), int(1), int(2)],
+                vec![int(1), int(3), null],
+                vec![int(2), int(4), int(5)],
+            ]
+        );
+    }
+
+    #[test]
+    fn cells_that_are_no_vectors_are_an_error() {
+        let df = ragged(&[&[1]]).append_line(vec![Data::Integer(1), Data::Integer(7)]);
+        let Err(err) = df.explode_to_columns("acc", &["x"], true) else {
+            panic!("the second cell of acc is no Vector")
+        };
+        assert_eq!(
+            err,
+            ShapeError::NotAVector {
+                line: 1,
+                found: Data::Integer(7)
+            }
+        );
+    }
+
+    fn column(df: &DataFrame, name: &str) -> Vec<Data> {
+        df.iter().map(|line| line[name].clone()).collect()
+    }
+
+    fn readings() -> DataFrame {
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        let rows = vec![
+            vec![Data::Integer(0), string("2021-05-01T12:00:00/sensor_3/ok")],
+            vec![Data::Integer(1), string("2021-05-01T12:05:00")],
+            vec![
+                Data::Integer(2),
+                string("2021-05-01T12:10:00/sensor_4/ok/late"),
+            ],
+            vec![Data::Integer(3), Data::Null],
+            vec![Data::Integer(4), string("/ 7/")],
+        ];
+        DataFrame::new(vec!["id", "raw"]).append_lines(rows.into_iter())
+    }
+
+    #[test]
+    fn lenient_split_pads_and_truncates() {
+        let df = readings()
+            .split_column("raw", "/", &["time", "sensor", "status"], false, true)
+            .expect("lenient splitting does not fail");
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            vec!["id", "time", "sensor", "status"]
+        );
+        let column = |name| column(&df, name);
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        //the parts are parsed like a cell in a file
+        let rules = ParseRules::default();
+        assert_eq!(
+            column("time")[0],
+            Data::parse_with(&rules, "2021-05-01T12:00:00")
+        );
+        assert_eq!(
+            column("sensor"),
+            vec![
+                string("sensor_3"),
+                Data::Null,
+                string("sensor_4"),
+                Data::Null,
+                Data::Integer(7),
+            ]
+        );
+        assert_eq!(
+            column("status"),
+            vec![
+                string("ok"),
+                Data::Null,
+                string("ok"),
+                Data::Null,
+                Data::Null
+            ]
+        );
+        assert_eq!(column("time")[3], Data::Null);
+        assert_eq!(column("time")[4], Data::Null);
+    }
+
+    #[test]
+    fn parts_stay_strings_without_inference() {
+        let df = readings()
+            .split_column(1, "/", &["time", "sensor", "status"], false, false)
+            .expect("lenient splitting does not fail");
+        assert_eq!(
+            column(&df, "time")[0],
+            Data::String(Box::new("2021-05-01T12:00:00".to_owned()))
+        );
+        assert_eq!(
+            column(&df, "sensor")[4],
+            Data::String(Box::new(" 7".to_owned()))
+        );
+        assert_eq!(column(&df, "sensor")[3], Data::Null);
+    }
+
+    #[test]
+    fn strict_split_reports_the_first_bad_line() {
+        let err = readings()
+            .split_column("raw", "/", &["time", "sensor", "status"], true, true)
+            .err();
+        assert_eq!(
+            err,
+            Some(ShapeError::SplitParts {
+                line: 1,
+                expected: 3,
+                found: 1,
+                value: Data::String(Box::new("2021-05-01T12:05:00".to_owned())),
+            })
+        );
+
+        let err = readings()
+            .filter(|line| line[0] != Data::Integer(1))
+            .split_column("raw", "/", &["time", "sensor", "status"], true, true)
+            .err();
+        assert!(matches!(
+            err,
+            Some(ShapeError::SplitParts {
+                line: 1,
+                found: 4,
+                ..
+            })
+        ));
+    }
+
+    fn events() -> DataFrame {
+        let rows = [(1, 10), (2, 20), (1, 11), (3, 30), (1, 12), (2, 21)]
+            .into_iter()
+            .map(|(session, event)| vec![Data::Integer(session), Data::Integer(event)]);
+        DataFrame::new(vec!["session", "event"]).append_lines(rows)
+    }
+
+    #[test]
+    fn implode_collects_the_values_in_line_order() {
+        let df = events().implode_by(&["session"], "event");
+        assert_eq!(df.header().collect::<Vec<_>>(), ["session", "event"]);
+        //a single line still gives a Vector
+        let int = Data::Integer;
+        assert_eq!(
+            lines(&df),
+            [
+                vec![int(1), vector(&[10, 11, 12])],
+                vec![int(2), vector(&[20, 21])],
+                vec![int(3), vector(&[30])],
+            ]
+        );
+
+        let collected = events()
+            .group_by(|line| line["session"].as_integer())
+            .sorted_by_key()
+            .collect_column("session", "event", "events");
+        assert_eq!(
+            collected.header().collect::<Vec<_>>(),
+            ["session", "events"]
+        );
+        assert_eq!(lines(&collected), lines(&df));
+    }
+
+    #[test]
+    fn explode_splits_only_the_outer_vector() {
+        let nested = Data::Vector(Box::new(vec![vector(&[1, 2]), Data::Integer(3)]));
+        let rows = vec![
+            vec![Data::Integer(0), nested],
+            vec![Data::Integer(1), Data::Integer(7)],
+            vec![Data::Integer(2), vector(&[])],
+            vec![Data::Integer(3), vector(&[8, 9])],
+        ];
+        let df = DataFrame::new(vec!["id", "acc"]).append_lines(rows.into_iter());
+        let int = Data::Integer;
+        assert_eq!(
+            lines(&df.clone().explode("acc", false)),
+            [
+                vec![int(0), vector(&[1, 2])],
+                vec![int(0), int(3)],
+                vec![int(1), int(7)],
+                vec![int(3), int(8)],
+                vec![int(3), int(9)],
+            ]
+        );
+        assert_eq!(
+            lines(&df.explode(1, true))[3..],
+            [
+                vec![int(2), Data::Null],
+                vec![int(3), int(8)],
+                vec![int(3), int(9)],
+            ]
+        );
+    }
+
+    #[test]
+    fn implode_of_explode_is_the_frame() {
+        let df = events().implode_by(&["session"], "event");
+        let back = df
+            .clone()
+            .explode("event", false)
+            .implode_by(&["session"], "event");
+        assert_eq!(lines(&back), lines(&df));
+
+        let exploded = events().explode("event", false);
+        assert_eq!(lines(&exploded), lines(&events()));
+    }
+
+    fn string(string: &str) -> Data {
+        Data::String(Box::new(string.to_owned()))
+    }
+
+    ///(time, sensor, value) with three values of sensor a at time 0, none of b at time 1
+    ///and a sensor named like the index column
+    fn long() -> DataFrame {
+        let rows = [
+            (0, "a", 1),
+            (0, "b", 2),
+            (0, "a", 3),
+            (1, "a", 4),
+            (0, "a", 5),
+            (2, "b", 6),
+            (2, "time", 7),
+        ]
+        .into_iter()
+        .map(|(time, sensor, value)| {
+            vec![Data::Integer(time), string(sensor), Data::Integer(value)]
+        })
+        .collect();
+        DataFrame::from_rows(vec!["time", "sensor", "value"], rows)
+            .expect("the rows fit the header")
+    }
+
+    #[test]
+    fn pivot_fills_missing_cells_and_keeps_the_last_value() {
+        let df = long()
+            .pivot("time", "sensor", "value", Data::Integer(-1), false)
+            .expect("collisions are allowed");
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            vec!["time", "a", "b", "time_2"]
+        );
+        let int = Data::Integer;
+        assert_eq!(
+            lines(&df),
+            vec![
+                vec![int(0), int(5), int(2), int(-1)],
+                vec![int(1), int(4), int(-1), int(-1)],
+                vec![int(2), int(-1), int(6), int(7)],
+            ]
+        );
+    }
+
+    #[test]
+    fn pivot_can_reject_collisions() {
+        let Err(err) = long().pivot(0, 1, 2, Data::Null, true) else {
+            panic!("sensor a has three values at time 0");
+        };
+        assert_eq!(
+            err,
+            ShapeError::DuplicateCell {
+                line: 2,
+                row: Data::Integer(0),
+                column: "a".to_owned(),
+            }
+        );
+        let unique = long().filter(|line| line["value"].as_integer() > 3);
+        assert!(unique.pivot(0, 1, 2, Data::Null, true).is_ok());
+    }
+
+    #[test]
+    fn pivot_agg_combines_duplicates() {
+        let int = Data::Integer;
+        let df = long().pivot_agg("time", "sensor", "value", Agg::Sum, false);
+        assert_eq!(
+            lines(&df),
+            vec![
+                vec![int(0), int(9), int(2), Data::Null],
+                vec![int(1), int(4), Data::Null, Data::Null],
+                vec![int(2), Data::Null, int(6), int(7)],
+            ]
+        );
+
+        let df = long().pivot_agg("time", "sensor", "sensor", Agg::Count, true);
+        assert_eq!(
+            lines(&df),
+            vec![
+                vec![int(0), int(3), int(1), int(0)],
+                vec![int(1), int(1), int(0), int(0)],
+                vec![int(2), int(0), int(1), int(1)],
+            ]
+        );
+        let df = long().pivot_agg("time", "sensor", "value", Agg::Count, false);
+        assert_eq!(column(&df, "b")[1], Data::Null);
+    }
+
+    #[test]
+    fn pivot_keys_that_only_display_the_same_stay_apart() {
+        let rows = vec![
+            vec![Data::Integer(1), Data::Integer(1), Data::Integer(10)],
+            vec![string("1"), string("1"), Data::Integer(20)],
+            vec![Data::Integer(1), string("1"), Data::Integer(30)],
+        ];
+        let df = DataFrame::from_rows(vec!["row", "column", "value"], rows)
+            .expect("the rows fit the header");
+
+        let pivot = df
+            .clone()
+            .pivot(0, 1, 2, Data::Null, true)
+            .expect("every cell has one value");
+        assert_eq!(pivot.header().collect::<Vec<_>>(), vec!["row", "1", "1_2"]);
+        assert_eq!(column(&pivot, "row"), vec![Data::Integer(1), string("1")]);
+        assert_eq!(
+            lines(&pivot)[0],
+            vec![Data::Integer(1), Data::Integer(10), Data::Integer(30)]
+        );
+
+        let agg = df.pivot_agg(0, 1, 2, Agg::Count, true);
+        assert_eq!(agg.shape(), (2, 3));
+        assert_eq!(column(&agg, "1_2"), vec![Data::Integer(1); 2]);
+    }
+
+    fn wide() -> DataFrame {
+        let int = Data::Integer;
+        let rows = vec![
+            vec![int(0), int(1), Data::Null, string("x")],
+            vec![int(1), int(4), int(2), string("y")],
+            vec![int(2), int(-1), int(6), string("z")],
+        ];
+        DataFrame::from_rows(vec!["time", "a", "b", "note"], rows).expect("the rows fit the header")
+    }
+
+    #[test]
+    fn melt_lists_every_value_column_per_line() {
+        let df = wide().melt(&["time"], &["b", "a"], "sensor", "value");
+        assert_eq!(df.header().collect::<Vec<_>>(), ["time", "sensor", "value"]);
+        let int = Data::Integer;
+        assert_eq!(
+            lines(&df)[..4],
+            [
+                vec![int(0), string("b"), Data::Null],
+                vec![int(0), string("a"), int(1)],
+                vec![int(1), string("b"), int(2)],
+                vec![int(1), string("a"), int(4)],
+            ]
+        );
+        let all = wide().melt(&["time"], &[], "column", "cell");
+        assert_eq!(all.len(), 9);
+        assert_eq!(
+            column(&all, "column")[..3],
+            [string("a"), string("b"), string("note")]
+        );
+    }
+
+    #[test]
+    fn pivot_of_melt_is_the_frame() {
+        let df = wide();
+        let melted = df.clone().melt(&["time"], &[], "column", "cell");
+        let back = melted
+            .pivot("time", "column", "cell", Data::Null, true)
+            .expect("every cell comes from one line");
+        assert_eq!(
+            back.header().collect::<Vec<_>>(),
+            df.header().collect::<Vec<_>>()
+        );
+        assert_eq!(lines(&back), lines(&df));
+    }
+
+    #[test]
+    fn transpose_on_never_repeats_a_name() {
+        let rows = ["a", "a_2", "a", "key", "a"]
+            .into_iter()
+            .enumerate()
+            .map(|(line, name)| vec![string(name), Data::Integer(line as i32)])
+            .collect();
+        let df = DataFrame::from_rows(vec!["key", "value"], rows).expect("the rows fit the header");
+        let transposed = df.transpose_on("key");
+        assert_eq!(
+            transposed.header().collect::<Vec<_>>(),
+            vec!["key", "a", "a_2", "a_3", "key_2", "a_4"]
+        );
+        assert_eq!(transposed.column_to_vec("key"), vec![string("value")]);
+        assert_eq!(transposed.column_to_vec("a_3"), vec![Data::Integer(2)]);
+        //a frame with thousands of lines gives as many columns
+        let wide = numbers_frame(5000).transpose_on(0);
+        assert_eq!(wide.shape(), (1, 5001));
+    }
+
+    fn numbers_frame(lines: i32) -> DataFrame {
+        let rows = (0..lines)
+            .map(|line| vec![Data::Integer(line % 10), Data::Float(line as f32)])
+            .collect();
+        DataFrame::from_rows(vec!["digit", "value"], rows).expect("the rows fit the header")
+    }
+}
@@ -0,0 +1,184 @@
+use std::num::NonZeroU32;
+
+use super::monotonic::{add_seconds, is_before};
+use super::{Data, DataFrame, DataFrameColumnIndex, DataTypeError, IndexMap, InnerDataFrame};
+
+impl DataFrame {
+    ///a view of the lines where at least one of columns differs from the line above,
+    ///the first and the last line are always kept
+    pub fn compress_runs(self, columns: &[&str]) -> DataFrame {
+        let indizes: Vec<usize> = columns
+            .iter()
+            .map(|column| column.get_usize(self.header()))
+            .collect();
+        let mut kept = Vec::new();
+        let mut previous = None;
+        for (line_index, line) in self.iter().enumerate() {
+            let values = line.select(&indizes);
+            if previous.as_ref() != Some(&values) || line_index + 1 == self.len() {
+                kept.push(line_index);
+            }
+            previous = Some(values);
+        }
+        InnerDataFrame::LineReorder {
+            df: self,
+            index_map: IndexMap::from(kept),
+        }
+        .into()
+    }
+
+    ///one line every every_seconds from the first to the last time of the time column
+    ///holding the cells of the last line at or before that time, the reverse of compress_runs
+    ///
+    ///the time column has to be sorted (see fix_monotonic) and hold Dates, or Integers or Floats
+    ///counting seconds, Null times are skipped and every other cell is an error
+    pub fn expand_runs<I>(
+        self,
+        time_column: I,
+        every_seconds: NonZeroU32,
+    ) -> Result<DataFrame, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = time_column.get_usize(self.header());
+        let mut lines = Vec::with_capacity(self.len());
+        for (line_index, time) in self.column(index).enumerate() {
+            match time {
+                Data::Null => {}
+                Data::Integer(_) | Data::Float(_) | Data::Date(_) => lines.push(line_index),
+                found => {
+                    return Err(DataTypeError {
+                        line: line_index,
+                        expected: "Integer, Float or Date",
+                        found: found.clone(),
+                    })
+                }
+            }
+        }
+
+        let header: Vec<&str> = self.header().collect();
+        let mut data = Vec::new();
+        if let (Some(first), Some(last)) = (lines.first(), lines.last()) {
+            let time_of =
+                |line_index: usize| self.get(line_index).expect("line is valid").data(index);
+            let last_time = time_of(*last).clone();
+            let mut time = time_of(*first).clone();
+            let mut position = 0;
+            while !is_before(&last_time, &time) {
+                while position + 1 < lines.len() && !is_before(&time, time_of(lines[position + 1]))
+                {
+                    position += 1;
+                }
+                let mut line: Vec<Data> = self
+                    .get(lines[position])
+                    .expect("line is valid")
+                    .iter()
+                    .cloned()
+                    .collect();
+                line[index] = time.clone();
+                data.push(line);
+                time = match add_seconds(&time, every_seconds.get() as i64) {
+                    Some(next) => next,
+                    None => break,
+                };
+            }
+        }
+        Ok(DataFrame::new(header).append_lines(data.drain(..)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn seconds(every: u32) -> NonZeroU32 {
+        NonZeroU32::new(every).expect("the step is not 0")
+    }
+
+    fn string(string: &str) -> Data {
+        Data::String(Box::new(string.to_owned()))
+    }
+
+    ///a status sampled every 10 seconds from 0 to 100 that changes three times
+    fn states() -> DataFrame {
+        let rows = (0..=10)
+            .map(|step| {
+                let status = match step {
+                    0..=2 => "idle",
+                    3..=6 => "running",
+                    7 => "error",
+                    _ => "idle",
+                };
+                vec![Data::Integer(step * 10), string(status), Data::Integer(1)]
+            })
+            .collect();
+        DataFrame::from_rows(vec!["time", "status", "level"], rows)
+            .expect("the rows fit the header")
+    }
+
+    fn times(df: &DataFrame) -> Vec<i32> {
+        df.column("time")
+            .map(|data| data.try_as_integer().expect("the times are integers"))
+            .collect()
+    }
+
+    #[test]
+    fn compressing_a_minimal_frame_changes_nothing() {
+        let compressed = states().compress_runs(&["status", "level"]);
+        assert_eq!(times(&compressed), vec![0, 30, 70, 80, 100]);
+
+        let again = compressed.clone().compress_runs(&["status", "level"]);
+        assert_eq!(again.to_csv_string(), compressed.to_csv_string());
+        let all_differ = states().compress_runs(&["time"]);
+        assert_eq!(all_differ.to_csv_string(), states().to_csv_string());
+    }
+
+    #[test]
+    fn expanding_reproduces_the_sampled_instants() {
+        let expanded = states()
+            .compress_runs(&["status"])
+            .expand_runs("time", seconds(10))
+            .expect("the times are Integers");
+        assert_eq!(expanded.to_csv_string(), states().to_csv_string());
+
+        //a coarser grid reads the status at or before each instant
+        let coarse = states()
+            .compress_runs(&["status"])
+            .expand_runs(0, seconds(25))
+            .expect("the times are Integers");
+        assert_eq!(times(&coarse), vec![0, 25, 50, 75, 100]);
+        assert_eq!(
+            coarse.column_to_vec("status"),
+            ["idle", "idle", "running", "error", "idle"]
+                .map(string)
+                .to_vec()
+        );
+    }
+
+    #[test]
+    fn expanding_dates_and_bad_times() {
+        let start = chrono::DateTime::from_timestamp(1_600_000_000, 0).expect("the time is valid");
+        let start = crate::SimpleDateTime::from_utc(start);
+        let df = states().with_column("time", |line| {
+            let step = line["time"]
+                .try_as_integer()
+                .expect("the times are integers");
+            Data::Date(start.add_seconds(step as i64))
+        });
+        let df = df
+            .drop_column(0)
+            .drop_all_column_except(&["time", "status", "level"]);
+        let expanded = df
+            .clone()
+            .compress_runs(&["status"])
+            .expand_runs("time", seconds(10))
+            .expect("the times are Dates");
+        assert_eq!(expanded.to_csv_string(), df.to_csv_string());
+
+        let df = states().append_line(vec![string("later"), string("idle"), Data::Integer(1)]);
+        let Err(err) = df.expand_runs("time", seconds(10)) else {
+            panic!("a String is no time");
+        };
+        assert_eq!(err.line, 11);
+    }
+}
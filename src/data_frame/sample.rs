@@ -0,0 +1,276 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+use super::{DataFrame, DataFrameColumnIndex, InnerDataFrame};
+
+///SplitMix64, small and good enough to pick lines, not for anything cryptographic
+pub(super) struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    ///a random seed if none is given
+    pub(super) fn new(seed: Option<u64>) -> Rng {
+        let state = seed.unwrap_or_else(|| RandomState::new().build_hasher().finish());
+        Rng { state }
+    }
+
+    pub(super) fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    ///uniform in 0..bound, bound must not be 0
+    pub(super) fn below(&mut self, bound: usize) -> usize {
+        ((self.next_u64() as u128 * bound as u128) >> 64) as usize
+    }
+
+    ///moves amount randomly chosen elements to the front of slice (a partial Fisher-Yates shuffle)
+    pub(super) fn choose_front<T>(&mut self, slice: &mut [T], amount: usize) {
+        for i in 0..amount.min(slice.len()) {
+            let j = i + self.below(slice.len() - i);
+            slice.swap(i, j);
+        }
+    }
+}
+
+impl DataFrame {
+    ///n different lines in random order, all lines shuffled if n is at least len
+    pub fn sample(self, n: usize, seed: u64) -> DataFrame {
+        let mut index_map: Vec<usize> = (0..self.len()).collect();
+        let amount = n.min(index_map.len());
+        Rng::new(Some(seed)).choose_front(&mut index_map, amount);
+        index_map.truncate(amount);
+        InnerDataFrame::LineReorder {
+            df: self,
+            index_map: index_map.into(),
+        }
+        .into()
+    }
+
+    ///n lines drawn independently of each other, so a line may appear more than once,
+    ///an empty frame stays empty
+    pub fn sample_with_replacement(self, n: usize, seed: u64) -> DataFrame {
+        let len = self.len();
+        let index_map: Vec<usize> = if len == 0 {
+            Vec::new()
+        } else {
+            let mut rng = Rng::new(Some(seed));
+            (0..n).map(|_| rng.below(len)).collect()
+        };
+        InnerDataFrame::LineReorder {
+            df: self,
+            index_map: index_map.into(),
+        }
+        .into()
+    }
+
+    ///all lines in random order
+    pub fn shuffle(self, seed: u64) -> DataFrame {
+        let len = self.len();
+        self.sample(len, seed)
+    }
+
+    ///about fraction of the lines of every distinct value of the strata column (rounded),
+    ///at least one line per value if at_least_one is set, the lines keep their order
+    pub fn stratified_sample<I>(
+        self,
+        strata: I,
+        fraction: f64,
+        seed: Option<u64>,
+        at_least_one: bool,
+    ) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        assert!(
+            (0.0..=1.0).contains(&fraction),
+            "fraction has to be between 0 and 1 but is {fraction}"
+        );
+        self.sample_strata(strata, seed, |len| {
+            let amount = (len as f64 * fraction).round() as usize;
+            if at_least_one {
+                amount.max(1)
+            } else {
+                amount
+            }
+        })
+    }
+
+    ///n lines of every distinct value of the strata column (all of them if there are fewer),
+    ///the lines keep their order
+    pub fn stratified_sample_n<I>(
+        self,
+        strata: I,
+        n_per_stratum: usize,
+        seed: Option<u64>,
+    ) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.sample_strata(strata, seed, |_len| n_per_stratum)
+    }
+
+    fn sample_strata<I, F>(self, strata: I, seed: Option<u64>, mut amount: F) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        F: FnMut(usize) -> usize,
+    {
+        let strata = strata.get_usize(self.header());
+
+        //strata in order of first occurrence so a seed always picks the same lines
+        let mut positions = HashMap::new();
+        let mut groups: Vec<Vec<usize>> = Vec::new();
+        for (i, line) in self.iter().enumerate() {
+            let position = *positions.entry(line.data(strata)).or_insert_with(|| {
+                groups.push(Vec::new());
+                groups.len() - 1
+            });
+            groups[position].push(i);
+        }
+
+        let mut rng = Rng::new(seed);
+        let mut index_map = Vec::new();
+        for group in groups.iter_mut() {
+            let amount = amount(group.len()).min(group.len());
+            rng.choose_front(group, amount);
+            index_map.extend_from_slice(&group[..amount]);
+        }
+        index_map.sort_unstable();
+
+        InnerDataFrame::LineReorder {
+            df: self,
+            index_map: index_map.into(),
+        }
+        .into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Data;
+
+    ///50 lines of stratum a, 10 of b and a single c, interleaved
+    fn frame() -> DataFrame {
+        let rows = (0..61).map(|id| {
+            let stratum = match id {
+                30 => "c",
+                _ if id % 6 == 5 => "b",
+                _ => "a",
+            };
+            vec![
+                Data::Integer(id),
+                Data::String(Box::new(stratum.to_owned())),
+            ]
+        });
+        DataFrame::new(vec!["id", "stratum"]).append_lines(rows)
+    }
+
+    fn ids(df: &DataFrame) -> Vec<i32> {
+        df.iter().map(|line| line[0].as_integer()).collect()
+    }
+
+    ///true if every id is a line of frame() and none is there twice
+    fn distinct_lines(ids: &[i32]) -> bool {
+        let mut sorted = ids.to_vec();
+        sorted.sort();
+        sorted.dedup();
+        sorted.len() == ids.len() && ids.iter().all(|id| (0..61).contains(id))
+    }
+
+    #[test]
+    fn a_seed_always_draws_the_same_lines() {
+        for seed in [0, 1, 42, u64::MAX] {
+            let sample = ids(&frame().sample(10, seed));
+            assert_eq!(sample, ids(&frame().sample(10, seed)));
+            assert_eq!(sample.len(), 10);
+            assert!(distinct_lines(&sample));
+
+            let shuffled = ids(&frame().shuffle(seed));
+            assert_eq!(shuffled, ids(&frame().shuffle(seed)));
+            assert_eq!(shuffled.len(), 61);
+            assert!(distinct_lines(&shuffled));
+
+            let drawn = ids(&frame().sample_with_replacement(100, seed));
+            assert_eq!(drawn, ids(&frame().sample_with_replacement(100, seed)));
+            assert_eq!(drawn.len(), 100);
+            assert!(drawn.iter().all(|id| (0..61).contains(id)));
+        }
+        assert_ne!(ids(&frame().shuffle(1)), ids(&frame().shuffle(2)));
+        assert_ne!(ids(&frame().shuffle(1)), ids(&frame()));
+    }
+
+    #[test]
+    fn a_sample_larger_than_the_frame_is_the_frame_shuffled() {
+        assert_eq!(ids(&frame().sample(1000, 5)), ids(&frame().shuffle(5)));
+        assert_eq!(frame().sample(0, 5).len(), 0);
+        let empty = frame().head(0);
+        assert_eq!(empty.clone().sample(3, 5).len(), 0);
+        assert_eq!(empty.sample_with_replacement(3, 5).len(), 0);
+        //a draw with replacement of many lines repeats some of them
+        assert!(!distinct_lines(&ids(
+            &frame().sample_with_replacement(61, 5)
+        )));
+    }
+
+    fn count(df: &DataFrame, stratum: &str) -> usize {
+        df.iter()
+            .filter(|line| line[1].as_string() == stratum)
+            .count()
+    }
+
+    #[test]
+    fn every_stratum_gets_its_fraction() {
+        let df = frame();
+        assert_eq!(
+            (count(&df, "a"), count(&df, "b"), count(&df, "c")),
+            (50, 10, 1)
+        );
+
+        let sample = df.clone().stratified_sample("stratum", 0.2, Some(7), false);
+        assert_eq!(
+            (
+                count(&sample, "a"),
+                count(&sample, "b"),
+                count(&sample, "c")
+            ),
+            (10, 2, 0)
+        );
+        let sample = df.stratified_sample("stratum", 0.2, Some(7), true);
+        assert_eq!(
+            (
+                count(&sample, "a"),
+                count(&sample, "b"),
+                count(&sample, "c")
+            ),
+            (10, 2, 1)
+        );
+    }
+
+    #[test]
+    fn sample_n_takes_all_lines_of_small_strata() {
+        let sample = frame().stratified_sample_n(1, 3, Some(1));
+        assert_eq!(
+            (
+                count(&sample, "a"),
+                count(&sample, "b"),
+                count(&sample, "c")
+            ),
+            (3, 3, 1)
+        );
+    }
+
+    #[test]
+    fn lines_keep_their_order_and_a_seed_repeats_the_sample() {
+        let sample = |seed| ids(&frame().stratified_sample("stratum", 0.5, Some(seed), true));
+        let first = sample(42);
+        assert!(first.windows(2).all(|pair| pair[0] < pair[1]));
+        assert_eq!(first, sample(42));
+        assert_ne!(first, sample(43));
+    }
+}
@@ -0,0 +1,485 @@
+use std::cmp::Ordering;
+
+use super::{observed, Data, DataFrame, DataFrameColumnIndex, InnerDataFrame, Line};
+
+///how cells are ordered by sort_with, sort_by_column and sort_by_columns
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SortOptions {
+    pub descending: bool,
+    ///Nulls go first or last regardless of descending
+    pub nulls_first: bool,
+    ///Strings are compared by their lowercase form
+    pub case_insensitive: bool,
+    ///lines with equal keys keep their order
+    pub stable: bool,
+}
+
+impl Default for SortOptions {
+    fn default() -> Self {
+        SortOptions {
+            descending: false,
+            nulls_first: false,
+            case_insensitive: false,
+            stable: true,
+        }
+    }
+}
+
+impl SortOptions {
+    pub fn descending() -> SortOptions {
+        SortOptions {
+            descending: true,
+            ..SortOptions::default()
+        }
+    }
+
+    ///Integer and Float are compared as numbers, other variants of Data in the order
+    ///Boolean, numbers, String, Date, Vec2D, Vector. Vectors are compared element by element,
+    ///a Null element is greater than any other
+    pub fn compare(&self, a: &Data, b: &Data) -> Ordering {
+        match (a, b) {
+            (Data::Null, Data::Null) => Ordering::Equal,
+            (Data::Null, _) if self.nulls_first => Ordering::Less,
+            (Data::Null, _) => Ordering::Greater,
+            (_, Data::Null) if self.nulls_first => Ordering::Greater,
+            (_, Data::Null) => Ordering::Less,
+            (a, b) if self.descending => self.compare_values(a, b).reverse(),
+            (a, b) => self.compare_values(a, b),
+        }
+    }
+
+    fn compare_values(&self, a: &Data, b: &Data) -> Ordering {
+        use Data::{Boolean, Date, Float, Integer, Vec2D, Vector};
+        //a Category is ordered like the String it holds
+        if let (Some(a), Some(b)) = (Self::text(a), Self::text(b)) {
+            return if self.case_insensitive {
+                a.to_lowercase().cmp(&b.to_lowercase())
+            } else {
+                a.cmp(b)
+            };
+        }
+        match (a, b) {
+            (Integer(a), Integer(b)) => a.cmp(b),
+            (Integer(a), Float(b)) => (*a as f64).total_cmp(&(*b as f64)),
+            (Float(a), Integer(b)) => (*a as f64).total_cmp(&(*b as f64)),
+            (Float(a), Float(b)) => a.total_cmp(b),
+            (Boolean(a), Boolean(b)) => a.cmp(b),
+            (Date(a), Date(b)) => a.cmp(b),
+            (Vec2D((ax, ay)), Vec2D((bx, by))) => ax.total_cmp(bx).then(ay.total_cmp(by)),
+            (Vector(a), Vector(b)) => a
+                .iter()
+                .zip(b.iter())
+                .map(|(a, b)| self.compare_values(a, b))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or_else(|| a.len().cmp(&b.len())),
+            (a, b) => Self::rank(a).cmp(&Self::rank(b)),
+        }
+    }
+
+    fn text(data: &Data) -> Option<&str> {
+        match data {
+            Data::String(string) => Some(string),
+            Data::Category(category) => Some(category),
+            _ => None,
+        }
+    }
+
+    fn rank(data: &Data) -> u8 {
+        match data {
+            Data::Boolean(_) => 0,
+            Data::Integer(_) | Data::Float(_) => 1,
+            Data::String(_) | Data::Category(_) => 2,
+            Data::Date(_) => 3,
+            Data::Vec2D(_) => 4,
+            Data::Vector(_) => 5,
+            Data::Null => 6,
+        }
+    }
+}
+
+///the direction of a column for sort_by_columns when the other SortOptions are the default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+    Ascending,
+    Descending,
+}
+
+impl From<SortOrder> for SortOptions {
+    fn from(order: SortOrder) -> Self {
+        match order {
+            SortOrder::Ascending => SortOptions::default(),
+            SortOrder::Descending => SortOptions::descending(),
+        }
+    }
+}
+
+///a computed key for DataFrame::sort_by_keys with the options it is compared by
+pub struct SortKey {
+    key: Box<dyn FnMut(Line) -> Data>,
+    options: SortOptions,
+}
+
+impl SortKey {
+    pub fn new<F>(key: F, options: SortOptions) -> SortKey
+    where
+        F: FnMut(Line) -> Data + 'static,
+    {
+        SortKey {
+            key: Box::new(key),
+            options,
+        }
+    }
+}
+
+impl DataFrame {
+    ///sorts by the cell key_gen returns for each line (it is called once per line)
+    pub fn sort_with<F>(self, mut key_gen: F, options: SortOptions) -> DataFrame
+    where
+        F: FnMut(Line) -> Data,
+    {
+        let keys: Vec<Data> = self.iter().map(&mut key_gen).collect();
+        self.sort_by_positions(|a, b| options.compare(&keys[a], &keys[b]), options.stable)
+    }
+
+    ///stable sort by a comparator of two lines, e.g. for Float cells or several levels
+    pub fn sort_by<F>(self, mut cmp: F) -> DataFrame
+    where
+        F: FnMut(&Line, &Line) -> Ordering,
+    {
+        let df = self.clone();
+        self.sort_by_positions(
+            |a, b| {
+                let a = df.get(a).expect("unreachable since a is below len");
+                let b = df.get(b).expect("unreachable since b is below len");
+                cmp(&a, &b)
+            },
+            true,
+        )
+    }
+
+    ///like sort but largest key first, lines with equal keys keep their order
+    pub fn sort_desc<F, K>(self, key_gen: F) -> DataFrame
+    where
+        F: FnMut(Line) -> K,
+        K: Ord,
+    {
+        let keys: Vec<K> = self.iter().map(key_gen).collect();
+        self.sort_by_positions(|a, b| keys[b].cmp(&keys[a]), true)
+    }
+
+    pub fn sort_by_column<I>(self, index: I, options: SortOptions) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        self.sort_with(|line| line[index].clone(), options)
+    }
+
+    ///sorts by the first column, lines with equal cells there by the second and so on,
+    ///each column comes with its SortOptions or a SortOrder,
+    ///the sort is stable if the options of the first column say so
+    pub fn sort_by_columns<I, O>(self, columns: &[(I, O)]) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+        O: Into<SortOptions> + Copy,
+    {
+        let keys = columns
+            .iter()
+            .map(|(index, options)| {
+                let index = index.get_usize(self.header());
+                SortKey::new(move |line| line[index].clone(), (*options).into())
+            })
+            .collect();
+        self.sort_by_keys(keys)
+    }
+
+    ///sorts by the first key, lines with equal keys there by the second and so on,
+    ///every key is computed once per line, the sort is stable if the options of the first key say so
+    pub fn sort_by_keys(self, mut keys: Vec<SortKey>) -> DataFrame {
+        let stable = keys.first().is_none_or(|key| key.options.stable);
+        let values: Vec<Vec<Data>> = self
+            .iter()
+            .map(|line| keys.iter_mut().map(|key| (key.key)(line)).collect())
+            .collect();
+        self.sort_by_positions(
+            |a, b| {
+                values[a]
+                    .iter()
+                    .zip(values[b].iter())
+                    .zip(keys.iter())
+                    .map(|((a, b), key)| key.options.compare(a, b))
+                    .find(|ordering| ordering.is_ne())
+                    .unwrap_or(Ordering::Equal)
+            },
+            stable,
+        )
+    }
+
+    ///compare gets the positions of two lines
+    pub(super) fn sort_by_positions<F>(self, mut compare: F, stable: bool) -> DataFrame
+    where
+        F: FnMut(usize, usize) -> Ordering,
+    {
+        observed("sort", self.len(), DataFrame::len, move || {
+            let mut index_map: Vec<usize> = (0..self.len()).collect();
+            if stable {
+                index_map.sort_by(|a, b| compare(*a, *b));
+            } else {
+                index_map.sort_unstable_by(|a, b| compare(*a, *b));
+            }
+            InnerDataFrame::LineReorder {
+                df: self,
+                index_map: index_map.into(),
+            }
+            .into()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+    use crate::data_frame::StringCache;
+
+    fn vector(cells: Vec<Data>) -> Data {
+        Data::Vector(Box::new(cells))
+    }
+
+    fn string(string: &str) -> Data {
+        Data::String(Box::new(string.to_owned()))
+    }
+
+    ///(value, line) with equal values at lines 0, 2 and 4
+    fn frame() -> DataFrame {
+        let values = [
+            Data::Integer(2),
+            Data::Null,
+            Data::Integer(2),
+            Data::Float(3.5),
+            Data::Integer(2),
+            Data::Null,
+            Data::Integer(-1),
+        ];
+        let rows = values
+            .into_iter()
+            .enumerate()
+            .map(|(line, value)| vec![value, Data::Integer(line as i32)]);
+        DataFrame::new(vec!["value", "line"]).append_lines(rows)
+    }
+
+    fn lines(df: &DataFrame) -> Vec<i32> {
+        df.iter()
+            .map(|line| line[1].try_as_integer().expect("the lines are integers"))
+            .collect()
+    }
+
+    #[test]
+    fn keys_are_computed_once_per_line() {
+        let rows = (0..1000).map(|i| vec![Data::Integer((i * 7919) % 1000)]);
+        let df = DataFrame::new(vec!["value"])
+            .append_lines(rows)
+            .filter(|line| line[0].as_integer() % 3 != 0);
+        let calls = Cell::new(0);
+        let key = |line: Line| {
+            calls.set(calls.get() + 1);
+            line[0].as_integer().to_string()
+        };
+
+        let sorted = df.clone().sort(key);
+        assert_eq!(calls.replace(0), df.len());
+        let values: Vec<String> = sorted.iter().map(|line| line[0].to_string()).collect();
+        assert!(values.windows(2).all(|pair| pair[0] <= pair[1]));
+
+        df.clone().sort_desc(key);
+        assert_eq!(calls.replace(0), df.len());
+        df.clone().sort_with(
+            |line| Data::String(Box::new(key(line))),
+            SortOptions::default(),
+        );
+        assert_eq!(calls.replace(0), df.len());
+    }
+
+    #[test]
+    fn descending_keeps_nulls_last() {
+        let df = frame().sort_by_column("value", SortOptions::descending());
+        assert_eq!(lines(&df), vec![3, 0, 2, 4, 6, 1, 5]);
+
+        let options = SortOptions {
+            nulls_first: true,
+            ..SortOptions::descending()
+        };
+        let df = frame().sort_by_column("value", options);
+        assert_eq!(lines(&df), vec![1, 5, 3, 0, 2, 4, 6]);
+    }
+
+    #[test]
+    fn equal_keys_keep_their_order() {
+        let df = frame().sort_by_column("value", SortOptions::default());
+        assert_eq!(lines(&df), vec![6, 0, 2, 4, 3, 1, 5]);
+
+        let df = frame()
+            .sort_with(|line| line["value"].clone(), SortOptions::descending())
+            .sort_by_column(0, SortOptions::descending());
+        assert_eq!(lines(&df), vec![3, 0, 2, 4, 6, 1, 5]);
+    }
+
+    #[test]
+    fn lines_with_equal_keys_keep_their_order() {
+        let parity = |line: Line| Data::Integer(line["line"].as_integer() % 2);
+        let keys = vec![
+            SortKey::new(parity, SortOptions::default()),
+            SortKey::new(|line| line["value"].clone(), SortOptions::descending()),
+        ];
+        let df = frame().sort_by_keys(keys);
+        //0, 2, 4 and 1, 5 are equal in both keys
+        assert_eq!(lines(&df), vec![0, 2, 4, 6, 3, 1, 5]);
+    }
+
+    ///(group, time, score, line)
+    fn readings() -> DataFrame {
+        let rows = [
+            ("b", 3, 0.5),
+            ("a", 7, 2.25),
+            ("b", 1, 2.25),
+            ("a", 2, -1.0),
+            ("b", 3, 9.0),
+        ]
+        .into_iter()
+        .enumerate()
+        .map(|(line, (group, time, score))| {
+            vec![
+                string(group),
+                Data::Integer(time),
+                Data::Float(score),
+                Data::Integer(line as i32),
+            ]
+        });
+        DataFrame::new(vec!["group", "time", "score", "line"]).append_lines(rows)
+    }
+
+    fn line_order(df: &DataFrame) -> Vec<i32> {
+        df.iter().map(|line| line["line"].as_integer()).collect()
+    }
+
+    #[test]
+    fn comparators_sort_floats_descending() {
+        let df = readings().sort_by(|a, b| b["score"].as_float().total_cmp(&a["score"].as_float()));
+        //1 and 2 have the same score and keep their order
+        assert_eq!(line_order(&df), vec![4, 1, 2, 0, 3]);
+    }
+
+    #[test]
+    fn comparators_sort_by_two_levels() {
+        let df = readings().sort_by(|a, b| {
+            a["group"]
+                .as_string()
+                .cmp(&b["group"].as_string())
+                .then(a["time"].as_integer().cmp(&b["time"].as_integer()))
+        });
+        //0 and 4 are equal in both levels and keep their order
+        assert_eq!(line_order(&df), vec![3, 1, 2, 0, 4]);
+
+        let df = readings().sort_desc(|line| line["time"].as_integer());
+        assert_eq!(line_order(&df), vec![1, 0, 4, 3, 2]);
+    }
+
+    #[test]
+    fn strings_can_ignore_case() {
+        let options = SortOptions {
+            case_insensitive: true,
+            ..SortOptions::default()
+        };
+        assert_eq!(
+            options.compare(&string("b"), &string("A")),
+            Ordering::Greater
+        );
+        assert_eq!(options.compare(&string("a"), &string("A")), Ordering::Equal);
+        let options = SortOptions::default();
+        assert_eq!(
+            options.compare(&string("b"), &string("A")),
+            Ordering::Greater
+        );
+        assert_eq!(
+            options.compare(&string("a"), &string("B")),
+            Ordering::Greater
+        );
+        let cache = StringCache::new();
+        let category = Data::Category(cache.intern("a"));
+        assert_eq!(options.compare(&category, &string("a")), Ordering::Equal);
+        assert_eq!(options.compare(&category, &string("B")), Ordering::Greater);
+    }
+
+    #[test]
+    fn vectors_are_reversed_once() {
+        let small = vector(vec![Data::Integer(1), Data::Integer(5)]);
+        let large = vector(vec![Data::Integer(2), Data::Integer(0)]);
+        let with_null = vector(vec![Data::Integer(1), Data::Null]);
+        let ascending = SortOptions::default();
+        let descending = SortOptions::descending();
+        assert_eq!(ascending.compare(&small, &large), Ordering::Less);
+        assert_eq!(descending.compare(&small, &large), Ordering::Greater);
+        //a Null element is an element, nulls_first only places Null cells
+        assert_eq!(ascending.compare(&small, &with_null), Ordering::Less);
+        assert_eq!(descending.compare(&small, &with_null), Ordering::Greater);
+        let nested = |inner: &Data| vector(vec![inner.clone()]);
+        assert_eq!(
+            descending.compare(&nested(&small), &nested(&large)),
+            Ordering::Greater
+        );
+    }
+
+    ///(group, value, line) with lines 1, 3 and 5 tied on the group and lines 3 and 5 on both
+    fn two_keys() -> DataFrame {
+        let rows = [
+            (string("b"), Data::Integer(1)),
+            (string("a"), Data::Float(2.5)),
+            (string("b"), Data::Integer(0)),
+            (string("a"), Data::Integer(7)),
+            (Data::Integer(4), Data::Integer(9)),
+            (string("a"), Data::Integer(7)),
+            (Data::Null, Data::Integer(0)),
+        ];
+        let rows = rows
+            .into_iter()
+            .enumerate()
+            .map(|(line, (group, value))| vec![group, value, Data::Integer(line as i32)])
+            .collect();
+        DataFrame::from_rows(vec!["group", "value", "line"], rows).expect("the rows fit the header")
+    }
+
+    fn two_key_lines(df: &DataFrame) -> Vec<i32> {
+        df.iter().map(|line| line["line"].as_integer()).collect()
+    }
+
+    #[test]
+    fn ties_on_the_first_column_are_broken_by_the_second() {
+        use SortOrder::{Ascending, Descending};
+        //numbers come before Strings and Null is last
+        let df = two_keys().sort_by_columns(&[("group", Ascending), ("value", Ascending)]);
+        assert_eq!(two_key_lines(&df), vec![4, 1, 3, 5, 2, 0, 6]);
+        let df = two_keys().sort_by_columns(&[("group", Ascending), ("value", Descending)]);
+        assert_eq!(two_key_lines(&df), vec![4, 3, 5, 1, 0, 2, 6]);
+        let df = two_keys().sort_by_columns(&[("group", Descending), ("value", Ascending)]);
+        assert_eq!(two_key_lines(&df), vec![2, 0, 1, 3, 5, 4, 6]);
+        let df = two_keys().sort_by_columns(&[(1, Descending), (0, Descending)]);
+        assert_eq!(two_key_lines(&df), vec![4, 3, 5, 1, 0, 2, 6]);
+    }
+
+    #[test]
+    fn orders_and_options_sort_the_same() {
+        let by_order = two_keys().sort_by_columns(&[
+            ("group", SortOrder::Descending),
+            ("value", SortOrder::Ascending),
+        ]);
+        let by_options = two_keys().sort_by_columns(&[
+            ("group", SortOptions::descending()),
+            ("value", SortOptions::default()),
+        ]);
+        assert_eq!(two_key_lines(&by_order), two_key_lines(&by_options));
+        //the lines tied on both columns keep their order
+        let df = two_keys().sort_by_columns(&[("group", SortOrder::Ascending)]);
+        assert_eq!(two_key_lines(&df), vec![4, 1, 3, 5, 0, 2, 6]);
+    }
+}
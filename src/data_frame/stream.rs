@@ -0,0 +1,117 @@
+use std::future::Future;
+use std::io::Error as IoError;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use futures_util::io::{AsyncWrite, AsyncWriteExt};
+use futures_util::stream::{self, Stream};
+
+use super::DataFrame;
+
+impl DataFrame {
+    ///the lines in chunks of chunk_rows lines (the last one may be shorter),
+    ///every chunk is a view sharing the data of self
+    pub fn into_row_stream(self, chunk_rows: usize) -> impl Stream<Item = DataFrame> {
+        assert!(chunk_rows > 0, "chunk_rows must not be 0");
+        let len = self.len();
+        stream::iter(
+            (0..len)
+                .step_by(chunk_rows)
+                .map(move |start| self.clone().range(start, (start + chunk_rows).min(len))),
+        )
+    }
+
+    ///writes the same text as to_csv_string, chunk_rows lines at a time
+    ///and yields to the executor between the chunks
+    pub async fn write_csv_async<W>(&self, writer: &mut W, chunk_rows: usize) -> Result<(), IoError>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        assert!(chunk_rows > 0, "chunk_rows must not be 0");
+        let seperator = self.parse_rules().seperator;
+        let header = self
+            .clone()
+            .head(0)
+            .csv_text(String::new(), seperator, true);
+        writer.write_all(header.as_bytes()).await?;
+
+        let mut start = 0;
+        while start < self.len() {
+            let end = (start + chunk_rows).min(self.len());
+            let chunk = self.clone().range(start, end);
+            let text = chunk.csv_text(String::new(), seperator, false);
+            writer.write_all(text.as_bytes()).await?;
+            YieldNow(false).await;
+            start = end;
+        }
+        writer.flush().await
+    }
+}
+
+///pending once so other tasks can run
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use futures_executor::block_on;
+    use futures_util::stream::StreamExt;
+
+    use super::*;
+    use crate::Data;
+
+    fn frame(len: i32) -> DataFrame {
+        let rows = (0..len).map(|i| {
+            vec![
+                Data::Integer(i),
+                Data::String(Box::new(format!("a;b {i}"))),
+                Data::Float(i as f32 / 4.0),
+            ]
+        });
+        DataFrame::new(vec!["id", "text", "value"]).append_lines(rows)
+    }
+
+    #[test]
+    fn async_output_equals_the_sync_writer() {
+        for (len, chunk_rows) in [(0, 3), (1, 3), (7, 3), (9, 3), (5, 100)] {
+            let df = frame(len);
+            let mut written = Vec::new();
+            block_on(df.write_csv_async(&mut written, chunk_rows)).expect("a Vec never fails");
+            assert_eq!(
+                String::from_utf8(written).expect("the csv is utf8"),
+                df.to_csv_string()
+            );
+        }
+    }
+
+    #[test]
+    fn the_stream_yields_every_line_once() {
+        let chunks: Vec<DataFrame> = block_on(frame(7).into_row_stream(3).collect());
+        let lens: Vec<usize> = chunks.iter().map(|chunk| chunk.len()).collect();
+        assert_eq!(lens, vec![3, 3, 1]);
+        let ids: Vec<i32> = chunks
+            .iter()
+            .flat_map(|chunk| {
+                chunk
+                    .iter()
+                    .map(|line| line["id"].as_integer())
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+        assert_eq!(ids, (0..7).collect::<Vec<_>>());
+        assert!(block_on(frame(0).into_row_stream(3).next()).is_none());
+    }
+}
@@ -0,0 +1,173 @@
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::borrow::Borrow;
+use std::collections::HashSet;
+use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::ops::Deref;
+use std::sync::{Arc, Mutex};
+
+use super::{BaseDataFrame, Data, DataFrame, DataFrameColumnIndex, InnerDataFrame};
+
+///a string stored once in a StringCache, cloning it only counts a reference
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Category(Arc<String>);
+
+impl Category {
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    ///true if both are the same stored string and not only equal
+    pub fn shares_storage(&self, other: &Category) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Deref for Category {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Borrow<str> for Category {
+    fn borrow(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Display for Category {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl Serialize for Category {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.0)
+    }
+}
+
+///a deserialized Category is not stored in any cache
+impl<'de> Deserialize<'de> for Category {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer).map(|string| Category(Arc::new(string)))
+    }
+}
+
+///opt-in storage shared by frames, equal strings categorized with the same cache are stored once,
+///clones are handles to the same cache
+#[derive(Debug, Clone, Default)]
+pub struct StringCache {
+    strings: Arc<Mutex<HashSet<Category>>>,
+}
+
+impl StringCache {
+    pub fn new() -> StringCache {
+        StringCache::default()
+    }
+
+    ///the stored copy of string, it is copied into the cache on first use
+    pub fn intern(&self, string: &str) -> Category {
+        let mut strings = self
+            .strings
+            .lock()
+            .expect("the string cache is not poisoned");
+        if let Some(category) = strings.get(string) {
+            return category.clone();
+        }
+        let category = Category(Arc::new(string.to_owned()));
+        strings.insert(category.clone());
+        category
+    }
+
+    ///the number of distinct strings stored
+    pub fn len(&self) -> usize {
+        self.strings
+            .lock()
+            .expect("the string cache is not poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    ///true if both are handles to the same cache
+    pub fn same_as(&self, other: &StringCache) -> bool {
+        Arc::ptr_eq(&self.strings, &other.strings)
+    }
+}
+
+impl DataFrame {
+    ///the Strings of the column become Categories stored in cache,
+    ///Categories of other columns stored in another cache are moved to this one
+    pub fn categorize<I>(self, index: I, cache: &StringCache) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        let mut base = BaseDataFrame::from(self);
+        base.use_string_cache(cache);
+        for line in base.data.iter_mut() {
+            if let Data::String(string) = &line[index] {
+                line[index] = Data::Category(cache.intern(string));
+            }
+        }
+        InnerDataFrame::Base { df: base }.into()
+    }
+
+    ///the cache the Categories of this frame are stored in, if it has one
+    pub fn string_cache(&self) -> Option<&StringCache> {
+        match self.inner.deref() {
+            InnerDataFrame::Base { df } => df.string_cache.as_ref(),
+            InnerDataFrame::LineReorder { df, .. }
+            | InnerDataFrame::ColumnReorder { df, .. }
+            | InnerDataFrame::Computed { df, .. } => df.string_cache(),
+            //the parts may store their Categories in different caches
+            InnerDataFrame::Concat { parts, .. } => {
+                let cache = parts[0].string_cache()?;
+                parts
+                    .iter()
+                    .all(|part| part.string_cache().is_some_and(|own| own.same_as(cache)))
+                    .then_some(cache)
+            }
+        }
+    }
+}
+
+impl BaseDataFrame {
+    ///stores the Categories in cache, they are only copied if the frame used another cache
+    pub(super) fn use_string_cache(&mut self, cache: &StringCache) {
+        if self
+            .string_cache
+            .as_ref()
+            .is_some_and(|own| own.same_as(cache))
+        {
+            return;
+        }
+        if self.string_cache.is_some() {
+            intern_categories(&mut self.data, cache);
+        }
+        self.string_cache = Some(cache.clone());
+    }
+
+    ///every String cell becomes a Category stored in cache
+    pub(super) fn categorize_strings(&mut self, cache: &StringCache) {
+        self.use_string_cache(cache);
+        for data in self.data.iter_mut().flat_map(|line| line.iter_mut()) {
+            if let Data::String(string) = data {
+                *data = Data::Category(cache.intern(string));
+            }
+        }
+    }
+}
+
+///moves the Categories of lines to cache
+pub(super) fn intern_categories(lines: &mut [Vec<Data>], cache: &StringCache) {
+    for data in lines.iter_mut().flat_map(|line| line.iter_mut()) {
+        if let Data::Category(category) = data {
+            *category = cache.intern(category);
+        }
+    }
+}
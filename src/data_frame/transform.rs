@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::convert::Infallible;
+
+use super::{BaseDataFrame, Data, DataFrame, DataFrameColumnIndex, DataTypeError, InnerDataFrame};
+
+///what to do with a cell for which no replacement is known
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Unmatched {
+    Keep,
+    Null,
+    Error,
+}
+
+impl Unmatched {
+    fn resolve(
+        &self,
+        line: usize,
+        data: &Data,
+        expected: &'static str,
+    ) -> Result<Data, DataTypeError> {
+        match self {
+            Unmatched::Keep => Ok(data.clone()),
+            Unmatched::Null => Ok(Data::Null),
+            Unmatched::Error => Err(DataTypeError {
+                line,
+                expected,
+                found: data.clone(),
+            }),
+        }
+    }
+}
+
+const MAPPING_EXPECTED: &str = "a value contained in the mapping";
+
+impl DataFrame {
+    ///replaces every cell of the column by its value in mapping
+    pub fn map_values<I>(
+        self,
+        index: I,
+        mapping: &HashMap<Data, Data>,
+        unmatched: Unmatched,
+    ) -> Result<DataFrame, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.try_map_column(index, |line, data| match mapping.get(data) {
+            Some(replacement) => Ok(replacement.clone()),
+            None => unmatched.resolve(line, data, MAPPING_EXPECTED),
+        })
+    }
+
+    ///like map_values but searches the pairs linearly, which is fine for a handful of values
+    pub fn map_values_from_pairs<I>(
+        self,
+        index: I,
+        mapping: &[(Data, Data)],
+        unmatched: Unmatched,
+    ) -> Result<DataFrame, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.try_map_column(index, |line, data| {
+            match mapping.iter().find(|(from, _to)| from == data) {
+                Some((_from, to)) => Ok(to.clone()),
+                None => unmatched.resolve(line, data, MAPPING_EXPECTED),
+            }
+        })
+    }
+
+    ///like map_values with the mapping taken from two columns of lookup,
+    ///if a key occurs more than once the first line wins
+    pub fn map_values_from_frame<I, K, V>(
+        self,
+        index: I,
+        lookup: &DataFrame,
+        key_column: K,
+        value_column: V,
+        unmatched: Unmatched,
+    ) -> Result<DataFrame, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+        K: DataFrameColumnIndex,
+        V: DataFrameColumnIndex,
+    {
+        let key_column = key_column.get_usize(lookup.header());
+        let value_column = value_column.get_usize(lookup.header());
+        let mut mapping = HashMap::with_capacity(lookup.len());
+        for line in lookup.iter() {
+            mapping
+                .entry(line[key_column].clone())
+                .or_insert_with(|| line[value_column].clone());
+        }
+        self.map_values(index, &mapping, unmatched)
+    }
+
+    ///appends a column holding the first cell of columns that is not Null
+    ///(and not an empty String if empty_is_missing is set), Null if there is none
+    pub fn coalesce(
+        self,
+        columns: &[&str],
+        name: &str,
+        drop_sources: bool,
+        empty_is_missing: bool,
+    ) -> DataFrame {
+        let indizes: Vec<usize> = columns
+            .iter()
+            .map(|column| column.get_usize(self.header()))
+            .collect();
+        let is_missing = |data: &Data| match data {
+            Data::Null => true,
+            Data::String(string) => empty_is_missing && string.is_empty(),
+            _ => false,
+        };
+        let column = self
+            .iter()
+            .map(|line| {
+                indizes
+                    .iter()
+                    .map(|index| &line[*index])
+                    .find(|data| !is_missing(data))
+                    .cloned()
+                    .unwrap_or(Data::Null)
+            })
+            .collect();
+
+        let df = self.append_column(name, column);
+        if drop_sources {
+            df.drop_columns(&indizes)
+        } else {
+            df
+        }
+    }
+
+    ///turns a column into Booleans, a cell is true if its text equals one of truthy
+    ///and false if it equals one of falsy (ignoring case and surrounding whitespace),
+    ///Booleans and Nulls stay as they are
+    pub fn to_boolean_column<I>(
+        self,
+        index: I,
+        truthy: &[&str],
+        falsy: &[&str],
+        unmatched: Unmatched,
+    ) -> Result<DataFrame, DataTypeError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.try_map_column(index, |line, data| {
+            if matches!(data, Data::Boolean(_) | Data::Null) {
+                return Ok(data.clone());
+            }
+            let string = data.as_string();
+            let string = string.trim();
+            let matches =
+                |words: &[&str]| words.iter().any(|word| word.eq_ignore_ascii_case(string));
+            if matches(truthy) {
+                Ok(Data::Boolean(true))
+            } else if matches(falsy) {
+                Ok(Data::Boolean(false))
+            } else {
+                unmatched.resolve(line, data, "a truthy or falsy value")
+            }
+        })
+    }
+
+    ///replaces every cell of the column that equals one of values by Null
+    pub fn set_na<I>(self, index: I, values: &[Data]) -> DataFrame
+    where
+        I: DataFrameColumnIndex,
+    {
+        self.try_map_column(index, |_line, data| {
+            if values.contains(data) {
+                Ok::<_, Infallible>(Data::Null)
+            } else {
+                Ok(data.clone())
+            }
+        })
+        .unwrap_or_else(|never| match never {})
+    }
+
+    ///replaces every cell of the column by f(line index, cell), stopping at the first error
+    fn try_map_column<I, F, E>(self, index: I, mut f: F) -> Result<DataFrame, E>
+    where
+        I: DataFrameColumnIndex,
+        F: FnMut(usize, &Data) -> Result<Data, E>,
+    {
+        let index = index.get_usize(self.header());
+        let column = self
+            .iter()
+            .enumerate()
+            .map(|(line_index, line)| f(line_index, &line[index]))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let mut base = BaseDataFrame::from(self);
+        base.replace_column(index, column);
+        Ok(InnerDataFrame::Base { df: base }.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ParseRules;
+
+    const TRUTHY: [&str; 2] = ["yes", "y"];
+    const FALSY: [&str; 2] = ["no", "n"];
+
+    fn answers() -> DataFrame {
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        let rows = [
+            string("Yes"),
+            string(" NO "),
+            string("maybe"),
+            string("y"),
+            Data::Null,
+            Data::Boolean(false),
+        ]
+        .into_iter()
+        .map(|data| vec![data]);
+        DataFrame::new(vec!["answer"]).append_lines(rows)
+    }
+
+    fn column(df: &DataFrame) -> Vec<Data> {
+        df.iter().map(|line| line[0].clone()).collect()
+    }
+
+    #[test]
+    fn words_are_matched_ignoring_case() {
+        let df = answers()
+            .to_boolean_column("answer", &TRUTHY, &FALSY, Unmatched::Null)
+            .expect("unmatched cells become Null");
+        let (boolean, null) = (Data::Boolean, Data::Null);
+        assert_eq!(
+            column(&df),
+            [
+                boolean(true),
+                boolean(false),
+                null.clone(),
+                boolean(true),
+                null,
+                boolean(false)
+            ]
+        );
+
+        let kept = answers()
+            .to_boolean_column(0, &TRUTHY, &FALSY, Unmatched::Keep)
+            .expect("unmatched cells are kept");
+        assert_eq!(column(&kept)[2], Data::String(Box::new("maybe".to_owned())));
+    }
+
+    #[test]
+    fn an_ambiguous_cell_is_an_error() {
+        let err = answers()
+            .to_boolean_column("answer", &TRUTHY, &FALSY, Unmatched::Error)
+            .err();
+        assert_eq!(
+            err,
+            Some(DataTypeError {
+                line: 2,
+                expected: "a truthy or falsy value",
+                found: Data::String(Box::new("maybe".to_owned())),
+            })
+        );
+    }
+
+    #[test]
+    fn parse_rules_read_the_words_after_numbers() {
+        let rules = ParseRules {
+            truthy: &["yes", "1"],
+            falsy: &["no", "0"],
+            ..ParseRules::default()
+        };
+        assert_eq!(Data::parse_with(&rules, "YES"), Data::Boolean(true));
+        assert_eq!(Data::parse_with(&rules, "No"), Data::Boolean(false));
+        assert_eq!(Data::parse_with(&rules, "1"), Data::Integer(1));
+        assert_eq!(
+            Data::parse_with(&rules, "maybe"),
+            Data::String(Box::new("maybe".to_owned()))
+        );
+        assert_eq!(
+            Data::parse_with(&ParseRules::default(), "yes"),
+            Data::String(Box::new("yes".to_owned()))
+        );
+    }
+
+    fn emails() -> DataFrame {
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        let rows = vec![
+            vec![Data::Integer(0), string("a@x"), string("b@x"), Data::Null],
+            vec![Data::Integer(1), Data::Null, string("b@y"), string("c@y")],
+            vec![Data::Integer(2), string(""), Data::Null, Data::Integer(7)],
+            vec![Data::Integer(3), Data::Null, Data::Null, Data::Null],
+        ];
+        DataFrame::new(vec!["id", "email_a", "email_b", "email_c"]).append_lines(rows.into_iter())
+    }
+
+    #[test]
+    fn coalesce_takes_the_first_present_cell() {
+        let columns = ["email_a", "email_b", "email_c"];
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+
+        let df = emails().coalesce(&columns, "email", false, true);
+        assert_eq!(
+            df.header().collect::<Vec<_>>(),
+            ["id", "email_a", "email_b", "email_c", "email"]
+        );
+        let email = |df: &DataFrame| {
+            df.iter()
+                .map(|line| line["email"].clone())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            email(&df),
+            [string("a@x"), string("b@y"), Data::Integer(7), Data::Null]
+        );
+
+        //an empty String is a value unless empty_is_missing is set
+        let df = emails().coalesce(&columns, "email", true, false);
+        assert_eq!(df.header().collect::<Vec<_>>(), ["id", "email"]);
+        assert_eq!(
+            email(&df),
+            [string("a@x"), string("b@y"), string(""), Data::Null]
+        );
+    }
+
+    #[test]
+    fn set_na_replaces_the_values_of_one_column() {
+        let rows = [[-999, -999], [1, 2], [-1, -999]]
+            .into_iter()
+            .map(|line| line.into_iter().map(Data::Integer).collect());
+        let df = DataFrame::new(vec!["temperature", "altitude"])
+            .append_lines(rows)
+            .set_na("temperature", &[Data::Integer(-999), Data::Integer(-1)]);
+        let cells: Vec<Vec<Data>> = df
+            .iter()
+            .map(|line| vec![line[0].clone(), line[1].clone()])
+            .collect();
+        assert_eq!(
+            cells,
+            [
+                [Data::Null, Data::Integer(-999)],
+                [Data::Integer(1), Data::Integer(2)],
+                [Data::Null, Data::Integer(-999)],
+            ]
+        );
+    }
+}
@@ -0,0 +1,333 @@
+use std::fmt::{Display, Formatter, Result as FmtResult};
+
+use super::{BaseDataFrame, Data, DataFrame, DataFrameColumnIndex, InnerDataFrame, ParseRules};
+
+const MAX_EXAMPLES: usize = 5;
+
+///the types a column may be fixed to without being forced, earlier ones win ties
+const CANDIDATES: [&str; 4] = ["Boolean", "Integer", "Float", "Date"];
+
+///which columns DataFrame::plan_type_fixes looks at and when it proposes a type
+#[derive(Debug, Clone)]
+pub struct TypeFixOptions {
+    columns: Option<Vec<String>>,
+    min_share: f32,
+    casts: Vec<(String, &'static str)>,
+}
+
+impl Default for TypeFixOptions {
+    fn default() -> Self {
+        TypeFixOptions {
+            columns: None,
+            min_share: 0.9,
+            casts: Vec::new(),
+        }
+    }
+}
+
+impl TypeFixOptions {
+    pub fn new() -> TypeFixOptions {
+        TypeFixOptions::default()
+    }
+
+    ///only these columns get a proposal, forced casts are planned anyway
+    pub fn columns(mut self, columns: &[&str]) -> TypeFixOptions {
+        self.columns = Some(columns.iter().map(|column| column.to_string()).collect());
+        self
+    }
+
+    ///the share of the non Null cells that has to cast cleanly for a type to be proposed,
+    ///0.9 by default
+    pub fn min_share(mut self, share: f32) -> TypeFixOptions {
+        self.min_share = share;
+        self
+    }
+
+    ///plans the column as to (one of the names of Data::type_name) however many cells fail
+    pub fn cast(mut self, column: &str, to: &'static str) -> TypeFixOptions {
+        self.casts.push((column.to_owned(), to));
+        self
+    }
+}
+
+///the planned change of one column
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeFix {
+    pub column: String,
+    ///the most common type of the non Null cells, None if all are Null
+    pub current: Option<&'static str>,
+    pub proposed: &'static str,
+    ///non Null cells that are or become proposed
+    pub clean: usize,
+    ///non Null cells that become Null
+    pub to_null: usize,
+    ///the first of the cells that become Null with their line
+    pub examples: Vec<(usize, Data)>,
+}
+
+///the result of DataFrame::plan_type_fixes, nothing is changed until apply_type_fixes
+#[derive(Debug, Clone, PartialEq)]
+pub struct TypeFixPlan {
+    fixes: Vec<TypeFix>,
+    rules: ParseRules,
+}
+
+impl TypeFixPlan {
+    pub fn fixes(&self) -> &[TypeFix] {
+        &self.fixes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fixes.is_empty()
+    }
+
+    ///one line per fix with the examples as "line: value" seperated by ", "
+    pub fn to_data_frame(&self) -> DataFrame {
+        let data = self.fixes.iter().map(|fix| {
+            let examples: Vec<String> = fix
+                .examples
+                .iter()
+                .map(|(line, data)| format!("{line}: {data}"))
+                .collect();
+            vec![
+                Data::String(Box::new(fix.column.clone())),
+                fix.current
+                    .map(|current| Data::String(Box::new(current.to_owned())))
+                    .unwrap_or(Data::Null),
+                Data::String(Box::new(fix.proposed.to_owned())),
+                Data::Integer(fix.clean as i32),
+                Data::Integer(fix.to_null as i32),
+                Data::String(Box::new(examples.join(", "))),
+            ]
+        });
+        DataFrame::new(vec![
+            "column", "current", "proposed", "clean", "to_null", "examples",
+        ])
+        .append_lines(data)
+    }
+}
+
+impl Display for TypeFixPlan {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        write!(f, "{}", self.to_data_frame())
+    }
+}
+
+impl DataFrame {
+    ///proposes for every column the one of Boolean, Integer, Float and Date most of its non Null
+    ///cells can be cast to (the first of them on a tie) if that are at least min_share of them,
+    ///a column is only listed if a cell would change.
+    ///Strings are parsed like when reading a file (with type inference) and Integers widen to Float
+    pub fn plan_type_fixes(&self, options: &TypeFixOptions) -> TypeFixPlan {
+        let rules = ParseRules {
+            infer_types: true,
+            ..self.parse_rules()
+        };
+        let mut fixes = Vec::new();
+        for (index, column) in self.header().enumerate() {
+            let forced = options
+                .casts
+                .iter()
+                .rev()
+                .find(|(cast_column, _to)| cast_column == column);
+            if let Some((_column, to)) = forced {
+                let (fix, _changed) = self.plan_column(index, to, &rules);
+                fixes.push(fix);
+                continue;
+            }
+            if let Some(columns) = &options.columns {
+                if !columns.iter().any(|name| name == column) {
+                    continue;
+                }
+            }
+
+            let non_null = self.column(index).filter(|data| !data.is_null()).count();
+            let proposal = CANDIDATES
+                .iter()
+                .map(|to| self.plan_column(index, to, &rules))
+                .rev()
+                .max_by_key(|(fix, _changed)| fix.clean)
+                .filter(|(fix, _changed)| {
+                    non_null > 0 && fix.clean as f32 >= options.min_share * non_null as f32
+                });
+            if let Some((fix, changed)) = proposal {
+                if changed > 0 || fix.to_null > 0 {
+                    fixes.push(fix);
+                }
+            }
+        }
+        TypeFixPlan { fixes, rules }
+    }
+
+    ///casts the columns of the plan, the cells counted as to_null become Null
+    pub fn apply_type_fixes(self, plan: &TypeFixPlan) -> DataFrame {
+        if plan.is_empty() {
+            return self;
+        }
+        let columns: Vec<(usize, Vec<Data>)> = plan
+            .fixes
+            .iter()
+            .map(|fix| {
+                let index = fix.column.as_str().get_usize(self.header());
+                let column = self
+                    .column(index)
+                    .map(|data| cast(data, fix.proposed, &plan.rules).unwrap_or(Data::Null))
+                    .collect();
+                (index, column)
+            })
+            .collect();
+
+        let mut base = BaseDataFrame::from(self);
+        for (index, column) in columns {
+            base.replace_column(index, column);
+        }
+        InnerDataFrame::Base { df: base }.into()
+    }
+
+    ///the fix for casting the column to and the number of cells that would change
+    fn plan_column(&self, index: usize, to: &'static str, rules: &ParseRules) -> (TypeFix, usize) {
+        let mut fix = TypeFix {
+            column: self.header().nth(index).unwrap_or_default().to_owned(),
+            current: self.dominant_type(index),
+            proposed: to,
+            clean: 0,
+            to_null: 0,
+            examples: Vec::new(),
+        };
+        let mut changed = 0;
+        for (line, data) in self.column(index).enumerate() {
+            if data.is_null() {
+                continue;
+            }
+            match cast(data, to, rules) {
+                Some(cast) => {
+                    fix.clean += 1;
+                    if cast.type_name() != data.type_name() {
+                        changed += 1;
+                    }
+                }
+                None => {
+                    fix.to_null += 1;
+                    changed += 1;
+                    if fix.examples.len() < MAX_EXAMPLES {
+                        fix.examples.push((line, data.clone()));
+                    }
+                }
+            }
+        }
+        (fix, changed)
+    }
+}
+
+///None if data can not be cast to the type named to, Null stays Null
+fn cast(data: &Data, to: &str, rules: &ParseRules) -> Option<Data> {
+    match (to, data) {
+        (_, Data::Null) => Some(Data::Null),
+        (to, data) if data.type_name() == to => Some(data.clone()),
+        ("String", data) => Some(Data::String(Box::new(data.as_string()))),
+        ("Float", Data::Integer(int)) => Some(Data::Float(*int as f32)),
+        (to, Data::String(string)) => {
+            let parsed = Data::infer(string.trim().to_owned(), rules);
+            match parsed {
+                Data::String(_) => None,
+                parsed => cast(&parsed, to, rules),
+            }
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn string(text: &str) -> Data {
+        Data::String(Box::new(text.to_owned()))
+    }
+
+    fn messy() -> DataFrame {
+        let rows = (0..10).map(|line| {
+            let count = match line {
+                3 => string("three"),
+                7 => Data::Null,
+                _ => string(&line.to_string()),
+            };
+            let score = match line % 3 {
+                0 => Data::Integer(line),
+                1 => string(&format!("{line}.5")),
+                _ => string(&format!("n/a {line}")),
+            };
+            let flag = string(["true", "false"][line as usize % 2]);
+            vec![count, score, flag, Data::Float(line as f32)]
+        });
+        DataFrame::new(vec!["count", "score", "flag", "ready"]).append_lines(rows)
+    }
+
+    ///the counts of every fix agree with the cells apply_type_fixes writes
+    fn assert_counts_match(df: &DataFrame, plan: &TypeFixPlan) {
+        let applied = df.clone().apply_type_fixes(plan);
+        for fix in plan.fixes() {
+            let before: Vec<&Data> = df.column(fix.column.as_str()).collect();
+            let after: Vec<&Data> = applied.column(fix.column.as_str()).collect();
+            let kept = before
+                .iter()
+                .zip(after.iter())
+                .filter(|(before, after)| !before.is_null() && !after.is_null());
+            assert!(kept
+                .clone()
+                .all(|(_before, after)| after.type_name() == fix.proposed));
+            assert_eq!(kept.count(), fix.clean, "{}", fix.column);
+            let nulled: Vec<usize> = (0..before.len())
+                .filter(|line| !before[*line].is_null() && after[*line].is_null())
+                .collect();
+            assert_eq!(nulled.len(), fix.to_null, "{}", fix.column);
+            let examples: Vec<usize> = fix.examples.iter().map(|(line, _data)| *line).collect();
+            assert_eq!(examples, nulled[..nulled.len().min(MAX_EXAMPLES)]);
+        }
+    }
+
+    #[test]
+    fn planned_counts_match_the_applied_changes() {
+        let df = messy();
+        let plan = df.plan_type_fixes(&TypeFixOptions::new());
+        //count has 8 of 9 cells clean, less than 0.9
+        let columns: Vec<&str> = plan.fixes().iter().map(|fix| fix.column.as_str()).collect();
+        assert_eq!(columns, vec!["flag"]);
+        assert_counts_match(&df, &plan);
+
+        let options = TypeFixOptions::new().min_share(0.8).cast("score", "Float");
+        let plan = df.plan_type_fixes(&options);
+        let fixes: Vec<(&str, Option<&str>, &str, usize, usize)> = plan
+            .fixes()
+            .iter()
+            .map(|fix| {
+                let column = fix.column.as_str();
+                (column, fix.current, fix.proposed, fix.clean, fix.to_null)
+            })
+            .collect();
+        assert_eq!(
+            fixes,
+            vec![
+                ("count", Some("String"), "Integer", 8, 1),
+                ("score", Some("String"), "Float", 7, 3),
+                ("flag", Some("String"), "Boolean", 10, 0),
+            ]
+        );
+        assert_eq!(plan.fixes()[0].examples, vec![(3, string("three"))]);
+        assert_counts_match(&df, &plan);
+    }
+
+    #[test]
+    fn examples_are_capped() {
+        let df = messy();
+        let plan = df.plan_type_fixes(&TypeFixOptions::new().cast("flag", "Date"));
+        let fix = &plan.fixes()[0];
+        assert_eq!((fix.column.as_str(), fix.to_null), ("flag", 10));
+        assert_eq!(fix.examples.len(), MAX_EXAMPLES);
+        assert_counts_match(&df, &plan);
+
+        let table = plan.to_string();
+        assert!(table.contains("to_null"));
+        assert!(table.contains("0: true, 1: false"));
+    }
+}
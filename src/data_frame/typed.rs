@@ -0,0 +1,165 @@
+use super::{
+    ColumnCastError, Data, DataFrame, DataFrameColumnIndex, DataTypeError, Line, ShapeError,
+    SimpleDateTime,
+};
+
+///a struct read from the lines of a frame, see #[derive(FromLine)] with the derive feature
+pub trait FromLine: Sized {
+    ///the columns of the fields, looked up once per frame
+    type Handles: 'static;
+
+    fn resolve(df: &DataFrame) -> Result<Self::Handles, ShapeError>;
+
+    ///the line number of the error is 0, DataFrame::iter_typed sets the real one
+    fn from_line(line: &Line, handles: &Self::Handles) -> Result<Self, DataTypeError>;
+}
+
+///a field type of a FromLine struct or the target of DataFrame::column_as,
+///Option accepts Null and every other type rejects it
+pub trait FromCell: Sized {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError>;
+
+    ///like from_cell but without widening, so f32 rejects Integers
+    fn from_cell_exact(data: &Data) -> Result<Self, DataTypeError> {
+        Self::from_cell(data)
+    }
+}
+
+fn mismatch(expected: &'static str, found: &Data) -> DataTypeError {
+    DataTypeError {
+        line: 0,
+        expected,
+        found: found.clone(),
+    }
+}
+
+impl FromCell for i32 {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        data.try_as_integer()
+            .ok_or_else(|| mismatch("Integer", data))
+    }
+}
+
+///an Integer is accepted as well, a Float column often contains whole numbers
+impl FromCell for f32 {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        match data {
+            Data::Float(float) => Ok(*float),
+            Data::Integer(int) => Ok(*int as f32),
+            data => Err(mismatch("Float", data)),
+        }
+    }
+
+    fn from_cell_exact(data: &Data) -> Result<Self, DataTypeError> {
+        data.try_as_float().ok_or_else(|| mismatch("Float", data))
+    }
+}
+
+impl FromCell for bool {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        data.try_as_boolean()
+            .ok_or_else(|| mismatch("Boolean", data))
+    }
+}
+
+impl FromCell for String {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        match data {
+            Data::String(string) => Ok(string.to_string()),
+            data => Err(mismatch("String", data)),
+        }
+    }
+}
+
+impl FromCell for SimpleDateTime {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        data.try_as_date().ok_or_else(|| mismatch("Date", data))
+    }
+}
+
+impl FromCell for (f32, f32) {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        data.try_as_vec2d().ok_or_else(|| mismatch("Vec2D", data))
+    }
+}
+
+impl FromCell for Data {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        Ok(data.clone())
+    }
+}
+
+impl<T: FromCell> FromCell for Option<T> {
+    fn from_cell(data: &Data) -> Result<Self, DataTypeError> {
+        match data {
+            Data::Null => Ok(None),
+            data => T::from_cell(data).map(Some),
+        }
+    }
+
+    fn from_cell_exact(data: &Data) -> Result<Self, DataTypeError> {
+        match data {
+            Data::Null => Ok(None),
+            data => T::from_cell_exact(data).map(Some),
+        }
+    }
+}
+
+impl DataFrame {
+    ///the column converted into T in visible order, f32 accepts Integers as well,
+    ///the error names the first cell that can not be converted
+    pub fn column_as<T, I>(&self, index: I) -> Result<Vec<T>, ColumnCastError>
+    where
+        T: FromCell,
+        I: DataFrameColumnIndex,
+    {
+        self.column_with(index, T::from_cell)
+    }
+
+    ///like column_as but without widening Integers to f32
+    pub fn column_as_exact<T, I>(&self, index: I) -> Result<Vec<T>, ColumnCastError>
+    where
+        T: FromCell,
+        I: DataFrameColumnIndex,
+    {
+        self.column_with(index, T::from_cell_exact)
+    }
+
+    fn column_with<T, I>(
+        &self,
+        index: I,
+        convert: fn(&Data) -> Result<T, DataTypeError>,
+    ) -> Result<Vec<T>, ColumnCastError>
+    where
+        I: DataFrameColumnIndex,
+    {
+        let index = index.get_usize(self.header());
+        self.column(index)
+            .enumerate()
+            .map(|(line, data)| {
+                convert(data).map_err(|err| ColumnCastError {
+                    column: self.header().nth(index).unwrap_or_default().to_owned(),
+                    line,
+                    expected: err.expected,
+                    found: err.found,
+                })
+            })
+            .collect()
+    }
+
+    ///every line converted into T, the columns are looked up once before the first line
+    pub fn iter_typed<T>(
+        &self,
+    ) -> Result<impl Iterator<Item = Result<T, DataTypeError>> + '_, ShapeError>
+    where
+        T: FromLine,
+    {
+        let handles = T::resolve(self)?;
+        Ok(self.iter().enumerate().map(move |(line_index, line)| {
+            T::from_line(&line, &handles).map_err(|err| DataTypeError {
+                line: line_index,
+                ..err
+            })
+        }))
+    }
+}
@@ -0,0 +1,369 @@
+use std::convert::Infallible;
+use std::fs::{File, OpenOptions};
+use std::io::{Error as IoError, Read, Seek, SeekFrom, Write};
+use std::path::Path;
+
+use super::{BaseDataFrame, Data, DataFrame, ParseRules, WriteError};
+
+///receives a frame line by line from DataFrame::write_rows, the csv writers are built on it
+///
+///the header and the cells are borrowed from the frame only for the duration of the call,
+///a sink that keeps them has to copy them
+pub trait RowSink {
+    type Error;
+
+    ///called once before the first line, also for a frame without lines
+    fn begin(&mut self, header: &[&str]) -> Result<(), Self::Error>;
+
+    ///the cells of one line in the order of the header
+    fn row<'a>(&mut self, cells: impl Iterator<Item = &'a Data>) -> Result<(), Self::Error>;
+
+    ///called once after the last line
+    fn finish(&mut self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+///collects the csv text, the header is only written if header is set
+struct CsvSink {
+    text: String,
+    seperator: char,
+    header: bool,
+}
+
+impl RowSink for CsvSink {
+    type Error = Infallible;
+
+    fn begin(&mut self, header: &[&str]) -> Result<(), Infallible> {
+        if self.header {
+            let header: Vec<Data> = header
+                .iter()
+                .map(|head_elem| Data::String(Box::new(head_elem.to_string())))
+                .collect();
+            self.write_record(header.iter());
+        }
+        Ok(())
+    }
+
+    fn row<'a>(&mut self, cells: impl Iterator<Item = &'a Data>) -> Result<(), Infallible> {
+        self.write_record(cells);
+        Ok(())
+    }
+}
+
+impl CsvSink {
+    fn write_record<'a>(&mut self, cells: impl Iterator<Item = &'a Data>) {
+        for (i, data) in cells.enumerate() {
+            if i != 0 {
+                self.text.push(self.seperator);
+            }
+            self.text.push_str(&data.to_csv_string(self.seperator));
+        }
+        self.text.push('\n');
+    }
+}
+
+impl DataFrame {
+    ///passes the header and then every line in visible order to sink,
+    ///stops at the first error of the sink
+    pub fn write_rows<W: RowSink>(&self, sink: &mut W) -> Result<(), W::Error> {
+        let header: Vec<&str> = self.header().collect();
+        sink.begin(&header)?;
+        for line in self.iter() {
+            sink.row(line.iter())?;
+        }
+        sink.finish()
+    }
+
+    ///writes header and lines so that from_file reads the same frame back,
+    ///the seperator defaults to the one the frame was read with
+    pub fn to_file(&self, path: &Path, seperator: Option<char>) -> Result<(), IoError> {
+        let seperator = seperator.unwrap_or(self.parse_rules().seperator);
+        let text = self.csv_text(String::new(), seperator, true);
+        File::create(path)?.write_all(text.as_bytes())
+    }
+
+    ///the text to_file writes with the seperator the frame was read with
+    pub fn to_csv_string(&self) -> String {
+        self.csv_text(String::new(), self.parse_rules().seperator, true)
+    }
+
+    ///appends the lines to the csv file at path after checking that its header is the one of self,
+    ///a file that does not exist (or is empty) is created with the header
+    ///
+    ///all lines are written with a single append, so a crash can at most leave a partial last line,
+    ///a file not ending in a newline gets one before the new lines
+    pub fn to_file_append(&self, path: &Path, seperator: Option<char>) -> Result<(), WriteError> {
+        let seperator = seperator.unwrap_or(',');
+        let mut text = String::new();
+
+        let existing = match File::open(path) {
+            Ok(file) if file.metadata()?.len() > 0 => Some(file),
+            Ok(_) => None,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err.into()),
+        };
+
+        let header = match existing {
+            Some(mut file) => {
+                let rules = ParseRules {
+                    seperator,
+                    ..self.parse_rules()
+                };
+                let file_header = BaseDataFrame::read_header(path, rules)?;
+                if !file_header.iter().map(String::as_str).eq(self.header()) {
+                    return Err(WriteError::HeaderMismatch {
+                        file: file_header,
+                        frame: self.header().map(str::to_owned).collect(),
+                    });
+                }
+                let mut last = [0u8];
+                file.seek(SeekFrom::End(-1))?;
+                file.read_exact(&mut last)?;
+                if last[0] != b'\n' {
+                    text.push('\n');
+                }
+                false
+            }
+            None => true,
+        };
+        let text = self.csv_text(text, seperator, header);
+
+        let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+        file.write_all(text.as_bytes())?;
+        Ok(())
+    }
+
+    ///appends the csv text of self to text
+    pub(super) fn csv_text(&self, text: String, seperator: char, header: bool) -> String {
+        let mut sink = CsvSink {
+            text,
+            seperator,
+            header,
+        };
+        self.write_rows(&mut sink)
+            .unwrap_or_else(|never| match never {});
+        sink.text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use super::*;
+    use crate::SimpleDateTime;
+
+    ///a path in the temp dir that does not exist yet
+    fn temp_file(name: &str) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("rundas_{}_{name}.csv", std::process::id()));
+        let _ = fs::remove_file(&path);
+        path
+    }
+
+    fn frame(header: Vec<&str>, first: i32) -> DataFrame {
+        let rows = (first..first + 2).map(|line| vec![Data::Integer(line), Data::Float(0.5)]);
+        DataFrame::new(header).append_lines(rows)
+    }
+
+    #[test]
+    fn appending_twice_writes_the_header_once() {
+        let path = temp_file("append_twice");
+        frame(vec!["id", "value"], 0)
+            .to_file_append(&path, None)
+            .expect("the file is created");
+        frame(vec!["id", "value"], 2)
+            .to_file_append(&path, None)
+            .expect("the header matches");
+
+        assert_eq!(
+            fs::read_to_string(&path).expect("the file was written"),
+            "id,value\n0,0.5\n1,0.5\n2,0.5\n3,0.5\n"
+        );
+        let df = DataFrame::from_file(&path, None).expect("the file is valid");
+        assert_eq!(df.shape(), (4, 2));
+        fs::remove_file(&path).expect("the file exists");
+    }
+
+    #[test]
+    fn a_missing_final_newline_is_added() {
+        let path = temp_file("no_newline");
+        fs::write(&path, "id,value\n7,1.5").expect("the temp dir is writable");
+        frame(vec!["id", "value"], 0)
+            .to_file_append(&path, None)
+            .expect("the header matches");
+        assert_eq!(
+            fs::read_to_string(&path).expect("the file was written"),
+            "id,value\n7,1.5\n0,0.5\n1,0.5\n"
+        );
+        fs::remove_file(&path).expect("the file exists");
+    }
+
+    #[test]
+    fn a_different_header_is_not_appended_to() {
+        let path = temp_file("mismatch");
+        fs::write(&path, "id,amount,extra\n7,1.5,x\n").expect("the temp dir is writable");
+        let Err(err) = frame(vec!["id", "value"], 0).to_file_append(&path, None) else {
+            panic!("the headers differ");
+        };
+        assert_eq!(
+            err.to_string(),
+            "the header of the file does not match the one of the frame\n\
+             column 1: file has \"amount\", frame has \"value\"\n\
+             column 2: file has \"extra\", frame has nothing\n"
+        );
+        assert!(matches!(
+            err,
+            WriteError::HeaderMismatch { file, frame } if file.len() == 3 && frame.len() == 2
+        ));
+        assert_eq!(
+            fs::read_to_string(&path).expect("the file exists"),
+            "id,amount,extra\n7,1.5,x\n"
+        );
+        fs::remove_file(&path).expect("the file exists");
+    }
+
+    fn lookalikes() -> Vec<Data> {
+        let string = |string: &str| Data::String(Box::new(string.to_owned()));
+        let date = "2021-05-01T12:00:00"
+            .parse::<SimpleDateTime>()
+            .expect("the date is valid");
+        vec![
+            string("3"),
+            Data::Integer(3),
+            Data::Float(3.0),
+            string("3.0"),
+            string("true"),
+            Data::Boolean(true),
+            string("false"),
+            Data::Boolean(false),
+            Data::Float(-0.0),
+            Data::Float(1e20),
+            Data::Float(f32::INFINITY),
+            string("inf"),
+            Data::Float(f32::NAN),
+            string("NaN"),
+            string("1e5"),
+            string("-7"),
+            Data::Vec2D((1.0, 2.0)),
+            string("1 2"),
+            Data::Date(date),
+            string(&Data::Date(date).to_csv_string(',')),
+            string(""),
+            string(" 3 "),
+            Data::Null,
+            Data::Vector(Box::new(vec![
+                string("3"),
+                Data::Integer(3),
+                Data::Float(3.0),
+            ])),
+            string("[3]"),
+        ]
+    }
+
+    #[test]
+    fn lookalike_values_keep_their_type() {
+        let cells = lookalikes();
+        let rows = cells
+            .iter()
+            .map(|cell| vec![Data::Integer(0), cell.clone()]);
+        let df = DataFrame::new(vec!["id", "cell"]).append_lines(rows);
+
+        let path = temp_file("typed");
+        df.to_file(&path, None).expect("the temp dir is writable");
+        let read = DataFrame::from_file(&path, None).expect("the file was just written");
+        fs::remove_file(&path).expect("the file was just written");
+
+        let read: Vec<Data> = read.iter().map(|line| line[1].clone()).collect();
+        for (written, read) in cells.iter().zip(read.iter()) {
+            assert_eq!(
+                written.type_name(),
+                read.type_name(),
+                "{written:?} read as {read:?}"
+            );
+            if !matches!(written, Data::Float(float) if float.is_nan()) {
+                assert_eq!(written, read);
+            }
+        }
+    }
+
+    #[test]
+    fn floats_are_written_with_a_point_or_exponent() {
+        assert_eq!(Data::Float(3.0).to_csv_string(','), "3.0");
+        assert_eq!(Data::Float(1e20).to_csv_string(','), "1e20");
+        assert_eq!(Data::Integer(3).to_csv_string(','), "3");
+        assert_eq!(
+            Data::String(Box::new("3".to_owned())).to_csv_string(','),
+            "\"3\""
+        );
+    }
+
+    ///records every call, fails on the line fail_at if set
+    #[derive(Default)]
+    struct MockSink {
+        header: Vec<String>,
+        rows: Vec<Vec<Data>>,
+        begins: usize,
+        finishes: usize,
+        fail_at: Option<usize>,
+    }
+
+    impl RowSink for MockSink {
+        type Error = usize;
+
+        fn begin(&mut self, header: &[&str]) -> Result<(), usize> {
+            self.begins += 1;
+            self.header = header.iter().map(|name| name.to_string()).collect();
+            Ok(())
+        }
+
+        fn row<'a>(&mut self, cells: impl Iterator<Item = &'a Data>) -> Result<(), usize> {
+            if self.fail_at == Some(self.rows.len()) {
+                return Err(self.rows.len());
+            }
+            self.rows.push(cells.cloned().collect());
+            Ok(())
+        }
+
+        fn finish(&mut self) -> Result<(), usize> {
+            self.finishes += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn a_sink_sees_the_header_and_every_visible_line() {
+        let df = frame(vec!["id", "value"], 0).append_data_frame(frame(vec!["id", "value"], 2));
+        let view = df.filter(|line| line["id"].as_integer() != 1);
+
+        let mut sink = MockSink::default();
+        view.write_rows(&mut sink).expect("the sink does not fail");
+        assert_eq!(sink.header, vec!["id", "value"]);
+        assert_eq!((sink.begins, sink.finishes), (1, 1));
+        let ids: Vec<&Data> = sink.rows.iter().map(|row| &row[0]).collect();
+        assert_eq!(
+            ids,
+            vec![&Data::Integer(0), &Data::Integer(2), &Data::Integer(3)]
+        );
+        assert!(sink.rows.iter().all(|row| row[1] == Data::Float(0.5)));
+
+        let mut sink = MockSink::default();
+        DataFrame::new(vec!["a"])
+            .write_rows(&mut sink)
+            .expect("the sink does not fail");
+        assert_eq!(sink.header, vec!["a"]);
+        assert_eq!((sink.begins, sink.rows.len(), sink.finishes), (1, 0, 1));
+    }
+
+    #[test]
+    fn an_error_of_the_sink_stops_the_writing() {
+        let mut sink = MockSink {
+            fail_at: Some(1),
+            ..MockSink::default()
+        };
+        let result = frame(vec!["id", "value"], 0).write_rows(&mut sink);
+        assert_eq!(result, Err(1));
+        assert_eq!((sink.rows.len(), sink.finishes), (1, 0));
+    }
+}
@@ -7,9 +7,31 @@
 #![feature(drain_filter)]
 #![feature(type_alias_impl_trait)]
 #![feature(box_into_inner)]
-#![feature(round_char_boundary)]
 #![feature(hash_drain_filter)]
+#![feature(specialization)]
+#![allow(incomplete_features)]
 
 mod data_frame;
 
-pub use data_frame::{Data, DataFrame, Groups, Line, SimpleDateTime};
+pub use data_frame::{
+    clear_op_observer, set_op_observer, Agg, AppendError, ArithmeticError, BackwardStep, BinError,
+    Binning, CappedData, Category, Coercion, ColumnCastError, ColumnHandle, ColumnIter, Data,
+    DataDisplayConfig, DataFrame, DataFrameColumnIndex, DataTypeError, DescribeOptions,
+    DisplayOptions, DistributionEntry, FromCell, FromLine, GroupCacheError, Groups, HeaderIter,
+    JoinError, JoinNaming, JoinReport, Line, LineIter, MonotonicReport, MonotonicStrategy, OpEvent,
+    ParseRules, ReadOptions, RowSink, ShapeError, SimpleDateTime, SortKey, SortOptions, SortOrder,
+    StringCache, TypeFix, TypeFixOptions, TypeFixPlan, Unmatched, WriteError,
+};
+
+#[cfg(feature = "derive")]
+pub use rundas_derive::FromLine;
+
+///the types needed for most work with frames, use rundas::prelude::*
+pub mod prelude {
+    pub use crate::{
+        Agg, AppendError, ArithmeticError, BinError, Category, ColumnCastError, ColumnHandle, Data,
+        DataFrame, DataFrameColumnIndex, DataTypeError, DisplayOptions, Groups, JoinError, Line,
+        ParseRules, ReadOptions, ShapeError, SimpleDateTime, SortKey, SortOptions, SortOrder,
+        StringCache, Unmatched, WriteError,
+    };
+}
@@ -0,0 +1,73 @@
+//!property tests of the csv reader and writer, debug builds run fewer cases than cargo test --release
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use rundas::{Data, DataFrame, SimpleDateTime};
+
+///the generation is slow without optimizations
+const CASES: u32 = if cfg!(debug_assertions) { 64 } else { 2048 };
+
+///any text and short text made of the chars that are special to the reader
+fn text() -> impl Strategy<Value = String> {
+    prop_oneof![any::<String>(), "[a1.e \"'()\\[\\]{}<>,;\n\r\t-]{0,12}"]
+}
+
+fn date() -> impl Strategy<Value = Data> {
+    (1000..9999i32, 1..=12u8, 1..=28u8, 0..24u8, 0..60u8, 0..60u8).prop_map(
+        |(year, month, day, hour, minute, second)| {
+            let string = format!("{year:04}-{month:02}-{day:02}T{hour:02}:{minute:02}:{second:02}");
+            Data::Date(string.parse::<SimpleDateTime>().expect("the date is valid"))
+        },
+    )
+}
+
+fn data() -> impl Strategy<Value = Data> {
+    let leaf = prop_oneof![
+        Just(Data::Null),
+        text().prop_map(|string| Data::String(Box::new(string))),
+        any::<i32>().prop_map(Data::Integer),
+        any::<f32>().prop_map(Data::Float),
+        any::<bool>().prop_map(Data::Boolean),
+        date(),
+        (any::<f32>(), any::<f32>()).prop_map(Data::Vec2D),
+    ];
+    leaf.prop_recursive(3, 16, 4, |inner| {
+        vec(inner, 0..4).prop_map(|vec| Data::Vector(Box::new(vec)))
+    })
+}
+
+fn frame() -> impl Strategy<Value = (Vec<String>, Vec<Vec<Data>>)> {
+    (0..5usize).prop_flat_map(|columns| (vec(text(), columns), vec(vec(data(), columns), 0..8)))
+}
+
+fn lines(df: &DataFrame) -> Vec<Vec<Data>> {
+    df.iter()
+        .map(|line| line.iter().cloned().collect())
+        .collect()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(CASES))]
+
+    #[test]
+    fn csv_round_trip((header, rows) in frame()) {
+        let df = DataFrame::new(header.clone()).append_lines(rows.clone().into_iter());
+        let text = df.to_csv_string();
+        let read = DataFrame::from_string(text.clone(), None)
+            .map_err(|err| TestCaseError::fail(format!("{err}\n{text}")))?;
+        prop_assert_eq!(read.header().collect::<Vec<_>>(), header, "{}", text);
+        prop_assert_eq!(lines(&read), rows, "{}", text);
+    }
+
+    #[test]
+    fn parser_never_panics(bytes in vec(any::<u8>(), 0..200)) {
+        let string = String::from_utf8_lossy(&bytes).into_owned();
+        let _result = DataFrame::from_string(string, None);
+    }
+
+    #[test]
+    fn parser_never_panics_on_csv_like_text(string in "[a1.\"'()\\[\\]{}<>, ;\n-]{0,60}") {
+        let _result = DataFrame::from_string(string.clone(), None);
+        let _result = DataFrame::from_string(string, Some(';'));
+    }
+}
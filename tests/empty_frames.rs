@@ -0,0 +1,376 @@
+//!runs the public operations on frames with no lines, no columns or neither,
+//!next to a frame that has both, none of them may panic
+
+use chrono::{Local, TimeZone};
+use std::collections::HashMap;
+use std::num::NonZeroU32;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::Arc;
+
+use rundas::{
+    Agg, Data, DataFrame, DescribeOptions, DisplayOptions, JoinNaming, MonotonicStrategy,
+    SimpleDateTime, SortKey, SortOptions, TypeFixOptions, Unmatched,
+};
+
+type Operation = fn(DataFrame);
+
+const HEADER: [&str; 4] = ["id", "name", "value", "time"];
+
+fn rows(lines: usize) -> Vec<Vec<Data>> {
+    (0..lines as i32)
+        .map(|line| {
+            let time = Local
+                .with_ymd_and_hms(2024, 1, 1, 0, line as u32, 0)
+                .single()
+                .expect("the date is valid");
+            vec![
+                Data::Integer(line % 2),
+                Data::String(Box::new(format!("a,{line}"))),
+                Data::Float(line as f32 / 2.0),
+                Data::Date(SimpleDateTime::from(time)),
+            ]
+        })
+        .collect()
+}
+
+fn full(lines: usize) -> DataFrame {
+    DataFrame::new(HEADER.to_vec()).append_lines(rows(lines).into_iter())
+}
+
+///(label, frame, has columns) for the four combinations of {0, 3} lines × {0, 4} columns
+fn frames() -> Vec<(&'static str, DataFrame, bool)> {
+    let no_columns = || Vec::<String>::new();
+    vec![
+        ("0x0", DataFrame::empty(), false),
+        (
+            "3x0",
+            DataFrame::new(no_columns()).append_lines(vec![vec![]; 3].into_iter()),
+            false,
+        ),
+        (
+            "3x0 after dropping every column",
+            full(3).drop_all_column_except(&[] as &[&str]),
+            false,
+        ),
+        ("0x4", DataFrame::new(HEADER.to_vec()), true),
+        ("3x4", full(3), true),
+    ]
+}
+
+///operations that need no column
+fn frame_operations() -> Vec<(&'static str, Operation)> {
+    vec![
+        ("head", |df| drop(df.head(2))),
+        ("tail", |df| drop(df.tail(2))),
+        ("range", |df| {
+            let end = df.len();
+            drop(df.range(0, end))
+        }),
+        ("split_at", |df| drop(df.split_at(0))),
+        ("chunks", |df| df.chunks(2).for_each(drop)),
+        ("shape", |df| {
+            assert_eq!(df.shape(), (df.len(), df.num_columns()))
+        }),
+        ("is_empty", |df| {
+            assert_eq!(df.is_empty(), df.iter().next().is_none())
+        }),
+        ("sort", |df| drop(df.sort(|line| line.len()))),
+        ("sort_desc", |df| drop(df.sort_desc(|line| line.len()))),
+        ("sort_by", |df| {
+            drop(df.sort_by(|_a, _b| std::cmp::Ordering::Equal))
+        }),
+        ("sort_by_keys", |df| {
+            let key = SortKey::new(
+                |line| Data::Integer(line.len() as i32),
+                SortOptions::default(),
+            );
+            drop(df.sort_by_keys(vec![key]))
+        }),
+        ("sort_with", |df| {
+            drop(df.sort_with(
+                |line| Data::Integer(line.len() as i32),
+                SortOptions::descending(),
+            ))
+        }),
+        ("filter", |df| drop(df.filter(|_line| true))),
+        ("group_by", |df| {
+            let groups = df.group_by(|_line| 0);
+            drop(groups.distribution());
+            drop(groups.keys_frame());
+            drop(groups.to_long_frame("key"));
+            drop(groups.into_data_frame("key"));
+        }),
+        ("group_by_sorted", |df| {
+            drop(df.group_by_sorted(|_line| 0).sorted_by_size(true))
+        }),
+        ("with_computed_column", |df| {
+            drop(
+                df.with_computed_column("x", Arc::new(|_line| Data::Null))
+                    .materialize(),
+            )
+        }),
+        ("with_derived", |df| {
+            drop(df.with_derived("x", &[], Arc::new(|_line| Data::Null)))
+        }),
+        ("with_column", |df| {
+            drop(df.with_column("x", |_line| Data::Null))
+        }),
+        ("append_column", |df| {
+            let column = vec![Data::Null; df.len()];
+            drop(df.append_column("x", column))
+        }),
+        ("add_column", |df| {
+            let column = vec![Data::Null; df.len()];
+            drop(df.add_column("x", column).expect("the column fits"))
+        }),
+        ("append_line", |df| {
+            let line = vec![Data::Null; df.num_columns()];
+            drop(df.append_line(line))
+        }),
+        ("append_data_frame", |df| {
+            drop(df.clone().append_data_frame(df))
+        }),
+        ("append_data_frame_coerced", |df| {
+            drop(
+                df.clone()
+                    .append_data_frame_coerced(df, true)
+                    .expect("same header"),
+            )
+        }),
+        ("concat_columns", |df| {
+            drop(df.clone().concat_columns(df, JoinNaming::default()))
+        }),
+        ("cross_join", |df| drop(df.clone().cross_join(df, 100))),
+        ("compact", |df| drop(df.compact())),
+        ("materialize", |df| drop(df.materialize())),
+        ("iter", |df| df.iter().for_each(drop)),
+        ("get", |df| assert_eq!(df.get(0).is_some(), !df.is_empty())),
+        ("header", |df| {
+            assert_eq!(df.header().count(), df.num_columns())
+        }),
+        ("preview", |df| drop(df.preview(2, 2))),
+        ("display_with", |df| {
+            drop(df.display_with(&DisplayOptions::new()))
+        }),
+        ("display", |df| drop(df.to_string())),
+        ("schema", |df| drop(df.schema())),
+        ("describe", |df| drop(df.describe())),
+        ("describe_with", |df| {
+            drop(df.describe_with(DescribeOptions::default()))
+        }),
+        ("candidate_keys", |df| drop(df.candidate_keys(2))),
+        ("drop_duplicates", |df| drop(df.drop_duplicates())),
+        ("drop_header_like_rows", |df| {
+            drop(df.drop_header_like_rows())
+        }),
+        ("sample", |df| drop(df.sample(2, 7))),
+        ("sample_with_replacement", |df| {
+            drop(df.sample_with_replacement(2, 7))
+        }),
+        ("shuffle", |df| drop(df.shuffle(7))),
+        ("transpose", |df| drop(df.transpose())),
+        ("plan_type_fixes", |df| {
+            let plan = df.plan_type_fixes(&TypeFixOptions::new());
+            drop(df.apply_type_fixes(&plan))
+        }),
+        ("content_hash", |df| {
+            assert_eq!(df.content_hash(), df.clone().content_hash())
+        }),
+        ("to_csv_string", |df| {
+            let csv = df.to_csv_string();
+            drop(DataFrame::from_string(csv, None))
+        }),
+        ("binary", |df| {
+            let mut bytes = Vec::new();
+            df.write_binary(&mut bytes).expect("writing to a vec works");
+            let read = DataFrame::read_binary(&mut bytes.as_slice()).expect("the bytes are valid");
+            assert_eq!(read.shape(), df.shape());
+        }),
+    ]
+}
+
+///operations that take a column of the header above
+fn column_operations() -> Vec<(&'static str, Operation)> {
+    vec![
+        ("drop_column", |df| drop(df.drop_column(0))),
+        ("drop_columns", |df| drop(df.drop_columns(&[0, 1]))),
+        ("drop_all_column_except", |df| {
+            drop(df.drop_all_column_except(&[0]))
+        }),
+        ("select", |df| drop(df.select(&[0, 2], |_line| true))),
+        ("column", |df| assert_eq!(df.column(0).count(), df.len())),
+        ("column_to_vec", |df| drop(df.column_to_vec("name"))),
+        ("column_handle", |df| {
+            let handle = df.column_handle("value");
+            drop(df.filter(|line| !line[handle].is_null()))
+        }),
+        ("fold_column", |df| {
+            assert_eq!(df.fold_column(0, 0, |count, _data| count + 1), df.len())
+        }),
+        ("sum_column_i64", |df| drop(df.sum_column_i64(0))),
+        ("checked_sum_column", |df| drop(df.checked_sum_column(0))),
+        ("sum_column", |df| drop(df.sum_column(2, false))),
+        ("mean_column", |df| {
+            assert_eq!(
+                df.mean_column(2, false).ok().flatten().is_some(),
+                !df.is_empty()
+            )
+        }),
+        ("min_column", |df| drop(df.min_column(2, true))),
+        ("max_column", |df| drop(df.max_column(2, true))),
+        ("quantile_column", |df| {
+            assert_eq!(df.quantile_column(2, 0.5).is_some(), !df.is_empty())
+        }),
+        ("quantile_column_approx", |df| {
+            assert_eq!(
+                df.quantile_column_approx(2, 0.5, 0.01).is_some(),
+                !df.is_empty()
+            )
+        }),
+        ("group_by_column_as_keys", |df| {
+            let groups = df.group_by_column_as_keys(0);
+            drop(groups.agg("id", &[("value", Agg::Mean), ("value", Agg::Median)]));
+            drop(groups.collect_column("id", 1, "names"));
+        }),
+        ("summary_by", |df| drop(df.summary_by(&["id"], "value"))),
+        ("value_counts", |df| drop(df.value_counts(0))),
+        ("qcut", |df| drop(df.qcut(2, 2, None, "bin"))),
+        ("is_unique", |df| assert!(df.len() > 1 || df.is_unique(0))),
+        ("duplicate_values", |df| drop(df.duplicate_values(0))),
+        ("is_monotonic_increasing", |df| {
+            assert!(df.is_monotonic_increasing(3))
+        }),
+        ("drop_duplicates_by", |df| drop(df.drop_duplicates_by(&[0]))),
+        ("validate_monotonic", |df| drop(df.validate_monotonic(3))),
+        ("fix_monotonic", |df| {
+            drop(df.fix_monotonic(3, MonotonicStrategy::SortStable))
+        }),
+        ("join", |df| drop(df.clone().join(df, 0, 0))),
+        ("join_with", |df| {
+            drop(df.clone().join_with(df, 0, 0, JoinNaming::default()))
+        }),
+        ("left_join", |df| drop(df.clone().left_join(df, 0, 0))),
+        ("right_join", |df| drop(df.clone().right_join(df, 0, 0))),
+        ("semi_join", |df| drop(df.clone().semi_join(df, 0, 0))),
+        ("anti_join", |df| drop(df.clone().anti_join(df, 0, 0))),
+        ("outer_join", |df| drop(df.clone().outer_join(df, 0, 0))),
+        ("join_report", |df| drop(df.join_report(&df, 0, 0))),
+        ("pivot_agg", |df| {
+            drop(df.pivot_agg(0, 1, 2, Agg::Sum, true))
+        }),
+        ("pivot", |df| drop(df.pivot(0, 1, 2, Data::Null, false))),
+        ("explode", |df| drop(df.explode(1, true))),
+        ("explode_to_columns", |df| {
+            drop(df.explode_to_columns(1, &["x", "y"], true))
+        }),
+        ("split_column", |df| {
+            drop(df.split_column(1, ",", &["x", "y"], false, true))
+        }),
+        ("implode_by", |df| drop(df.implode_by(&[0], 1))),
+        ("melt", |df| {
+            drop(df.melt(&["id"], &["value"], "variable", "value_"))
+        }),
+        ("transpose_on", |df| drop(df.transpose_on(1))),
+        ("compress_runs", |df| drop(df.compress_runs(&["id"]))),
+        ("expand_runs", |df| {
+            drop(df.expand_runs(3, NonZeroU32::new(60).expect("60 is not 0")))
+        }),
+        ("stratified_sample", |df| {
+            drop(df.stratified_sample(0, 0.5, Some(7), true))
+        }),
+        ("stratified_sample_n", |df| {
+            drop(df.stratified_sample_n(0, 1, Some(7)))
+        }),
+        ("sort_by_column", |df| {
+            drop(df.sort_by_column(2, SortOptions::descending()))
+        }),
+        ("sort_by_columns", |df| {
+            drop(df.sort_by_columns(&[(0, SortOptions::default()), (2, SortOptions::descending())]))
+        }),
+        ("map_values", |df| {
+            drop(df.map_values(0, &HashMap::new(), Unmatched::Keep))
+        }),
+        ("map_values_from_pairs", |df| {
+            drop(df.map_values_from_pairs(0, &[(Data::Integer(0), Data::Null)], Unmatched::Null))
+        }),
+        ("map_values_from_frame", |df| {
+            let lookup = df.clone();
+            drop(df.map_values_from_frame(0, &lookup, 0, 1, Unmatched::Keep))
+        }),
+        ("coalesce", |df| {
+            drop(df.coalesce(&["id", "value"], "x", true, true))
+        }),
+        ("to_boolean_column", |df| {
+            drop(df.to_boolean_column(0, &["1"], &["0"], Unmatched::Null))
+        }),
+        ("set_na", |df| drop(df.set_na(0, &[Data::Integer(0)]))),
+        ("rename_column", |df| drop(df.rename_column(0, "x"))),
+        ("rename_columns", |df| {
+            drop(df.rename_columns(&[(0, "x"), (1, "y")]))
+        }),
+        ("adopt_column", |df| {
+            let other = df.clone();
+            drop(df.adopt_column(&other, 0, Some("x")))
+        }),
+        ("column_as", |df| drop(df.column_as::<i32, _>(0))),
+        ("column_as_exact", |df| {
+            drop(df.column_as_exact::<f32, _>(2))
+        }),
+    ]
+}
+
+#[test]
+fn no_operation_panics_on_empty_frames() {
+    let mut failures = Vec::new();
+    for (label, df, has_columns) in frames() {
+        let mut operations = frame_operations();
+        if has_columns {
+            operations.extend(column_operations());
+        }
+        for (name, operation) in operations {
+            let df = df.clone();
+            if catch_unwind(AssertUnwindSafe(|| operation(df))).is_err() {
+                failures.push(format!("{name} on {label}"));
+            }
+        }
+    }
+    assert!(failures.is_empty(), "panicked: {failures:?}");
+}
+
+#[test]
+fn empty_frames_keep_their_shape() {
+    for (label, df, _has_columns) in frames() {
+        let (lines, columns) = df.shape();
+        assert_eq!(df.clone().head(0).shape(), (0, columns), "{label}");
+        assert_eq!(
+            df.clone().filter(|_line| true).shape(),
+            (lines, columns),
+            "{label}"
+        );
+        assert_eq!(
+            df.clone().sort(|line| line.len()).shape(),
+            (lines, columns),
+            "{label}"
+        );
+        let csv = df.to_csv_string();
+        let read = DataFrame::from_string(csv, None).expect("the csv was written by rundas");
+        assert_eq!(read.num_columns(), columns, "{label}");
+    }
+}
+
+#[test]
+fn empty_frames_have_defined_results() {
+    let no_lines = DataFrame::new(HEADER.to_vec());
+    assert!(no_lines.to_string().ends_with("[0 lines x 4 columns]\n"));
+    assert_eq!(no_lines.to_csv_string(), "id,name,value,time\n");
+    assert_eq!(no_lines.mean_column(2, true), Ok(None));
+    assert_eq!(no_lines.min_column(2, true), Ok(None));
+    assert_eq!(no_lines.checked_sum_column(0), Ok(Some(0)));
+    assert!(no_lines.clone().group_by(|line| line[0].clone()).is_empty());
+    assert_eq!(no_lines.describe().len(), 0);
+
+    let no_columns = full(3).drop_all_column_except(&[] as &[&str]);
+    assert_eq!(no_columns.shape(), (3, 0));
+    assert!(no_columns.to_string().ends_with("[3 lines x 0 columns]\n"));
+    assert_eq!(no_columns.group_by(|_line| 0).len(), 1);
+}
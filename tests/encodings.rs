@@ -0,0 +1,64 @@
+//!reads fixtures with byte order marks in front of several lines and UTF-16 exports
+
+use std::io::ErrorKind;
+use std::path::Path;
+
+use rundas::{Data, DataFrame, ReadOptions};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn read(name: &str, options: ReadOptions) -> DataFrame {
+    DataFrame::from_file_with_options(&fixture(name), options).expect("the fixture is valid")
+}
+
+fn lines(df: &DataFrame) -> Vec<(Data, Data)> {
+    df.iter()
+        .map(|line| (line["day"].clone(), line["count"].clone()))
+        .collect()
+}
+
+fn expected() -> Vec<(Data, Data)> {
+    [(1, 12), (1, 7), (2, 15)]
+        .into_iter()
+        .map(|(day, count)| (Data::Integer(day), Data::Integer(count)))
+        .collect()
+}
+
+#[test]
+fn byte_order_marks_are_dropped_from_every_line() {
+    let df = read("bom_utf8.csv", ReadOptions::new());
+    assert_eq!(df.header().collect::<Vec<_>>(), ["day", "station", "count"]);
+    assert_eq!(lines(&df), expected());
+}
+
+#[test]
+fn byte_order_mark_after_a_dropped_header() {
+    let df = read(
+        "bom_after_header.csv",
+        ReadOptions::new().drop_repeated_headers(true),
+    );
+    assert_eq!(lines(&df), expected());
+}
+
+#[test]
+fn utf16_is_named_unless_transcoded() {
+    for (name, encoding) in [("utf16le.csv", "utf-16le"), ("utf16be.csv", "utf-16be")] {
+        let Err(err) = DataFrame::from_file_with_options(&fixture(name), ReadOptions::new()) else {
+            panic!("{name} is not read without ReadOptions::utf16");
+        };
+        assert_eq!(err.kind(), ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            format!("the file is encoded as {encoding}, ReadOptions::utf16 reads it")
+        );
+
+        let df = read(name, ReadOptions::new().utf16(true));
+        assert_eq!(df.header().collect::<Vec<_>>(), ["day", "station", "count"]);
+        assert_eq!(lines(&df), expected());
+    }
+}
@@ -0,0 +1,43 @@
+//!reads the fixtures in tests/fixtures whose quoted fields span several lines
+
+use std::path::Path;
+
+use rundas::{Data, DataFrame};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+#[test]
+fn quoted_field_spans_lines() {
+    let df = DataFrame::from_file(&fixture("tickets.csv"), None).expect("the fixture is valid");
+    assert_eq!(df.shape(), (2, 3));
+
+    let description = "Printer on floor 2\nsays \"PC LOAD LETTER\"\nand jams";
+    let first = df.get(0).expect("the fixture has two records");
+    assert_eq!(first["id"], Data::Integer(1));
+    assert_eq!(
+        first["description"],
+        Data::String(Box::new(description.to_owned()))
+    );
+    assert_eq!(first["priority"], Data::String(Box::new("high".to_owned())));
+
+    let second = df.get(1).expect("the fixture has two records");
+    assert_eq!(second["id"], Data::Integer(2));
+    assert_eq!(
+        second["description"],
+        Data::String(Box::new("short".to_owned()))
+    );
+}
+
+#[test]
+fn error_names_the_first_line_of_the_record() {
+    let Err(err) = DataFrame::from_file(&fixture("tickets_broken.csv"), None) else {
+        panic!("the last record misses a field");
+    };
+    //the multi line record takes lines 2 to 4, the broken record is on line 5
+    assert!(err.to_string().starts_with("Line 5"), "{err}");
+}
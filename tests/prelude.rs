@@ -0,0 +1,159 @@
+//!names every public item through rundas::prelude or the crate root,
+//!so making one of them private breaks this build
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use rundas::prelude::*;
+use rundas::{
+    clear_op_observer, set_op_observer, BackwardStep, Binning, CappedData, Coercion, ColumnIter,
+    DataDisplayConfig, DescribeOptions, DistributionEntry, FromCell, FromLine, GroupCacheError,
+    HeaderIter, JoinNaming, JoinReport, LineIter, MonotonicReport, MonotonicStrategy, OpEvent,
+    RowSink, TypeFix, TypeFixOptions, TypeFixPlan,
+};
+
+fn first_cell(line: Line) -> Data {
+    line[0].clone()
+}
+
+fn lines(df: &DataFrame) -> LineIter<'_> {
+    df.iter()
+}
+
+fn header(df: &DataFrame) -> HeaderIter<'_> {
+    df.header()
+}
+
+fn cells(df: &DataFrame) -> ColumnIter<'_> {
+    df.column("b")
+}
+
+fn typed_lines<T: FromLine>(df: &DataFrame) -> Vec<T> {
+    df.iter_typed::<T>()
+        .map_or(Vec::new(), |lines| lines.filter_map(Result::ok).collect())
+}
+
+struct FirstCell(i32);
+
+impl FromLine for FirstCell {
+    type Handles = ColumnHandle;
+
+    fn resolve(df: &DataFrame) -> Result<ColumnHandle, ShapeError> {
+        Ok(df.column_handle(0))
+    }
+
+    fn from_line(line: &Line, handle: &ColumnHandle) -> Result<Self, DataTypeError> {
+        i32::from_cell(&line[*handle]).map(FirstCell)
+    }
+}
+
+fn resolve<I: DataFrameColumnIndex>(df: &DataFrame, index: I) -> usize {
+    df.column_handle(index).index()
+}
+
+struct Rows(usize);
+
+impl RowSink for Rows {
+    type Error = ();
+
+    fn begin(&mut self, _header: &[&str]) -> Result<(), ()> {
+        Ok(())
+    }
+
+    fn row<'a>(&mut self, _cells: impl Iterator<Item = &'a Data>) -> Result<(), ()> {
+        self.0 += 1;
+        Ok(())
+    }
+}
+
+#[allow(clippy::type_complexity)]
+fn results() -> (
+    Option<DataTypeError>,
+    Option<ShapeError>,
+    Option<JoinError>,
+    Option<WriteError>,
+    Option<JoinReport>,
+    Option<OpEvent>,
+    Option<Groups<Data>>,
+    Option<SimpleDateTime>,
+    Option<Category>,
+    Option<ArithmeticError>,
+    Option<ColumnCastError>,
+    Option<MonotonicReport>,
+) {
+    (
+        None, None, None, None, None, None, None, None, None, None, None, None,
+    )
+}
+
+fn load_groups(path: &Path, df: DataFrame) -> Result<Groups<Data>, GroupCacheError> {
+    Groups::load_index(path, df)
+}
+
+#[test]
+fn every_public_item_is_reachable() {
+    let df = DataFrame::new(vec!["a", "b"])
+        .append_lines(vec![vec![Data::Integer(1), Data::Integer(2)]].into_iter());
+    assert_eq!(
+        lines(&df).map(first_cell).collect::<Vec<_>>(),
+        [Data::Integer(1)]
+    );
+    assert_eq!(header(&df).collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(cells(&df).collect::<Vec<_>>(), [&Data::Integer(2)]);
+    assert_eq!(typed_lines::<FirstCell>(&df)[0].0, 1);
+    let handle: ColumnHandle = df.column_handle("b");
+    assert_eq!(resolve(&df, handle), 1);
+    assert_eq!(resolve(&df, "a"), 0);
+    let entries: Vec<DistributionEntry> = df
+        .clone()
+        .group_by(|line| line[0].clone())
+        .distribution_ext();
+    assert_eq!(entries[0].cumulative_rows, 1);
+    let mut rows = Rows(0);
+    assert_eq!(df.write_rows(&mut rows), Ok(()));
+    assert_eq!(rows.0, 1);
+
+    let cache = StringCache::new();
+    let options = ReadOptions::new()
+        .string_cache(&cache)
+        .parse_rules(ParseRules::default());
+    let read = DataFrame::from_string_with_options("x\ny\n".to_owned(), options)
+        .expect("the string is a valid csv");
+    assert_eq!(read.len(), 1);
+
+    let coerced: Result<(DataFrame, Vec<Coercion>), AppendError> =
+        df.clone().append_data_frame_coerced(df.clone(), false);
+    assert!(coerced.is_ok_and(|(_df, coercions)| coercions.is_empty()));
+
+    let config = DataDisplayConfig::new().max_elements(1).max_depth(1);
+    let vector = Data::Vector(Box::new(vec![Data::Integer(1), Data::Integer(2)]));
+    let capped: CappedData = vector.display_capped(&config);
+    assert_eq!(capped.to_string(), "[ 1, … (+1 more) ]");
+    let _ = DisplayOptions::new().vectors(config);
+    let _ = Agg::Sum;
+    let _ = JoinNaming::PreferLeft;
+    let _ = SortKey::new(first_cell, SortOptions::default());
+    let sorted = df.clone().sort_by_columns(&[("a", SortOrder::Descending)]);
+    assert_eq!(sorted.len(), 1);
+    let binned: Result<(DataFrame, Binning), BinError> = df.clone().qcut("a", 1, None, "bin");
+    assert!(binned.is_ok_and(|(df, binning)| df.num_columns() == 3 && binning.edges().len() == 2));
+    let summary = df.describe_with(DescribeOptions::approx(0.01));
+    assert_eq!(summary.len(), 2);
+    let plan: TypeFixPlan = df.plan_type_fixes(&TypeFixOptions::new());
+    let fixes: &[TypeFix] = plan.fixes();
+    assert!(fixes.is_empty());
+    assert_eq!(df.clone().apply_type_fixes(&plan).len(), 1);
+    let fixed = df
+        .clone()
+        .fix_monotonic("a", MonotonicStrategy::DropBackward);
+    let steps: Vec<BackwardStep> = fixed
+        .validate_monotonic("a")
+        .map_or_else(|report| report.steps, |_df| Vec::new());
+    assert!(steps.is_empty());
+    let mapping: HashMap<Data, Data> = HashMap::new();
+    let _ = df.map_values("a", &mapping, Unmatched::Keep);
+    set_op_observer(Box::new(|_event| {}));
+    clear_op_observer();
+    assert!(results().0.is_none());
+    assert!(load_groups(Path::new("no/such/index"), DataFrame::new(vec!["a"])).is_err());
+}
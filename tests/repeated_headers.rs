@@ -0,0 +1,42 @@
+//!reads a fixture made of three concatenated daily exports, each starting with the header
+
+use std::path::Path;
+
+use rundas::{Data, DataFrame, ReadOptions};
+
+fn fixture(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join(name)
+}
+
+fn counts(df: &DataFrame) -> Vec<Data> {
+    df.iter().map(|line| line["count"].clone()).collect()
+}
+
+fn expected_counts() -> Vec<Data> {
+    [12, 7, 15, 11, 9].into_iter().map(Data::Integer).collect()
+}
+
+#[test]
+fn repeated_headers_are_skipped_while_reading() {
+    let options = ReadOptions::new().drop_repeated_headers(true);
+    let df = DataFrame::from_file_with_options(&fixture("concatenated_days.csv"), options)
+        .expect("the fixture is valid");
+    assert_eq!(df.shape(), (5, 3));
+    assert_eq!(counts(&df), expected_counts());
+    assert!(df
+        .iter()
+        .all(|line| matches!(line["day"], Data::Integer(1..=3))));
+}
+
+#[test]
+fn header_like_rows_are_dropped_after_reading() {
+    let df = DataFrame::from_file(&fixture("concatenated_days.csv"), None)
+        .expect("the fixture is valid");
+    assert_eq!(df.len(), 7);
+    let df = df.drop_header_like_rows();
+    assert_eq!(df.len(), 5);
+    assert_eq!(counts(&df), expected_counts());
+}
@@ -0,0 +1,128 @@
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use rundas::{Data, DataFrame, StringCache};
+
+///counts the bytes currently allocated by this test binary
+struct Counting;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATED.fetch_add(layout.size(), Ordering::SeqCst);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        ALLOCATED.fetch_sub(layout.size(), Ordering::SeqCst);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: Counting = Counting;
+
+fn session_file() -> PathBuf {
+    let path = std::env::temp_dir().join(format!("rundas_session_{}.csv", std::process::id()));
+    let mut content = String::from("device,event,count\n");
+    for i in 0..50 {
+        let device = [
+            "sensor-kitchen-01",
+            "sensor-hallway-02",
+            "thermostat-living",
+        ][i % 3];
+        let event = ["door_opened_by_user", "temperature_reading"][i % 2];
+        content.push_str(&format!("{device},{event},{i}\n"));
+    }
+    std::fs::write(&path, content).expect("the temp dir is writable");
+    path
+}
+
+///the bytes still allocated for the frames f loads
+fn retained(f: impl FnOnce() -> Vec<DataFrame>) -> (usize, Vec<DataFrame>) {
+    let before = ALLOCATED.load(Ordering::SeqCst);
+    let frames = f();
+    (ALLOCATED.load(Ordering::SeqCst) - before, frames)
+}
+
+#[test]
+fn a_shared_cache_stores_repeated_strings_once() {
+    let path = session_file();
+    let load = || DataFrame::from_file(&path, None).expect("the file is readable");
+
+    let (plain, plain_frames) = retained(|| (0..100).map(|_| load()).collect());
+    let cache = StringCache::new();
+    let (cached, cached_frames) = retained(|| {
+        (0..100)
+            .map(|_| {
+                load()
+                    .categorize("device", &cache)
+                    .categorize("event", &cache)
+            })
+            .collect()
+    });
+    std::fs::remove_file(&path).expect("the file was written");
+
+    assert_eq!(cache.len(), 5);
+    //every String cell is a heap String of its own without the cache
+    let string_bytes: usize = plain_frames[0]
+        .iter()
+        .flat_map(|line| line.iter().cloned().collect::<Vec<_>>())
+        .filter_map(|data| match data {
+            Data::String(string) => Some(std::mem::size_of::<String>() + string.len()),
+            _ => None,
+        })
+        .sum();
+    assert!(
+        plain - cached >= 99 * string_bytes,
+        "100 cached loads keep {cached} bytes, 100 plain loads {plain}"
+    );
+    for (plain, cached) in plain_frames.iter().zip(cached_frames.iter()) {
+        for (plain, cached) in plain.iter().zip(cached.iter()) {
+            assert!(plain
+                .iter()
+                .map(Data::as_string)
+                .eq(cached.iter().map(Data::as_string)));
+        }
+    }
+}
+
+fn frame(cache: &StringCache, device: &str) -> DataFrame {
+    DataFrame::new(vec!["device"])
+        .append_line(vec![Data::String(Box::new(device.to_owned()))])
+        .categorize("device", cache)
+}
+
+#[test]
+fn frames_with_the_same_cache_share_their_strings_when_appended() {
+    let cache = StringCache::new();
+    let df = frame(&cache, "a").append_data_frame(frame(&cache, "a"));
+
+    let cells: Vec<_> = df.iter().map(|line| line[0].clone()).collect();
+    let (Data::Category(first), Data::Category(second)) = (&cells[0], &cells[1]) else {
+        panic!("the cells stay Categories")
+    };
+    assert!(first.shares_storage(second));
+    assert!(df.string_cache().expect("df has a cache").same_as(&cache));
+    assert_eq!(cache.len(), 1);
+}
+
+#[test]
+fn appending_a_frame_of_another_cache_copies_its_strings() {
+    let (cache, other_cache) = (StringCache::new(), StringCache::new());
+    let other = frame(&other_cache, "b");
+    let df = frame(&cache, "a").append_data_frame(other.clone());
+
+    assert_eq!(cache.len(), 2);
+    let (Data::Category(appended), Data::Category(original)) = (
+        &df.get(1).expect("df has two lines")[0],
+        &other.get(0).expect("other has a line")[0],
+    ) else {
+        panic!("the cells stay Categories")
+    };
+    assert_eq!(appended, original);
+    assert!(!appended.shares_storage(original));
+    assert!(appended.shares_storage(&cache.intern("b")));
+}
@@ -0,0 +1,160 @@
+//!the same data as a base frame and as a stack of column and line views over a wider frame,
+//!every read has to give the same result on both
+
+use std::sync::Arc;
+
+use rundas::{Agg, Data, DataFrame};
+
+const LINES: i32 = 40;
+
+fn speed(id: i32) -> Data {
+    match id % 7 {
+        0 => Data::Null,
+        3 => Data::Integer(id * 3),
+        _ => Data::Float(id as f32 * 1.25 - 10.0),
+    }
+}
+
+fn name(id: i32) -> Data {
+    Data::String(Box::new(
+        ["north", "south", "east"][id as usize % 3].to_owned(),
+    ))
+}
+
+fn base() -> DataFrame {
+    let rows = (0..LINES).map(|id| vec![Data::Integer(id), speed(id), name(id)]);
+    DataFrame::new(vec!["id", "speed", "name"]).append_lines(rows)
+}
+
+///the lines of base in reverse order between lines that are filtered out,
+///with the columns in another order between columns that are dropped
+fn stacked() -> DataFrame {
+    let rows = (0..LINES).rev().flat_map(|id| {
+        let junk = vec![
+            Data::Integer(-1),
+            Data::Float(0.5),
+            Data::String(Box::new("junk".to_owned())),
+            name(id + 1),
+            Data::Integer(-id),
+        ];
+        let line = vec![
+            Data::Integer(id),
+            speed(id),
+            Data::Null,
+            name(id),
+            Data::Integer(id),
+        ];
+        [line, junk]
+    });
+    let wide = DataFrame::new(vec!["id", "speed", "junk", "name", "order"]).append_lines(rows);
+
+    wide.with_computed_column("double", Arc::new(|line| line["order"].clone()))
+        .filter(|line| line["id"] != Data::Integer(-1))
+        .drop_column("junk")
+        .sort(|line| line["order"].as_integer())
+        .drop_all_column_except(&["double", "speed", "name", "id"])
+        .filter(|_line| true)
+        .drop_all_column_except(&["id", "speed", "name"])
+}
+
+fn pair() -> (DataFrame, DataFrame) {
+    (base(), stacked())
+}
+
+#[test]
+fn stack_has_the_data_of_the_base() {
+    let (base, stacked) = pair();
+    assert_eq!(stacked.shape(), base.shape());
+    assert!(stacked.header().eq(base.header()));
+    assert_eq!(stacked.to_csv_string(), base.to_csv_string());
+    for (base_line, stacked_line) in base.iter().zip(stacked.iter()) {
+        assert!(base_line.iter().eq(stacked_line.iter()));
+        assert_eq!(base_line["speed"], stacked_line["speed"]);
+    }
+}
+
+#[test]
+fn columns_agree() {
+    let (base, stacked) = pair();
+    for column in 0..base.num_columns() {
+        assert!(base.column(column).eq(stacked.column(column)), "{column}");
+        assert_eq!(base.column_to_vec(column), stacked.column_to_vec(column));
+    }
+    let handle = stacked.column_handle("name");
+    assert!(base.column("name").eq(stacked.column(handle)));
+}
+
+#[test]
+fn folds_agree() {
+    let (base, stacked) = pair();
+    let fold = |df: &DataFrame| {
+        df.fold_column("speed", Vec::new(), |mut all, data| {
+            all.push(data);
+            all
+        })
+    };
+    assert_eq!(fold(&base), fold(&stacked));
+    let fold_ref =
+        |df: &DataFrame| df.fold_column_ref(2, String::new(), |all, data| all + &data.as_string());
+    assert_eq!(fold_ref(&base), fold_ref(&stacked));
+}
+
+#[test]
+fn aggregations_agree() {
+    let (base, stacked) = pair();
+    assert_eq!(base.sum_column_i64("id"), stacked.sum_column_i64("id"));
+    assert_eq!(base.checked_sum_column(0), stacked.checked_sum_column(0));
+    assert_eq!(
+        base.summary_by(&["name"], "speed").to_string(),
+        stacked.summary_by(&["name"], "speed").to_string()
+    );
+    for strict in [false, true] {
+        assert_eq!(
+            base.sum_column("speed", strict),
+            stacked.sum_column("speed", strict)
+        );
+        assert_eq!(
+            base.mean_column("speed", strict),
+            stacked.mean_column("speed", strict)
+        );
+        assert_eq!(
+            base.min_column("speed", strict),
+            stacked.min_column("speed", strict)
+        );
+        assert_eq!(
+            base.max_column("speed", strict),
+            stacked.max_column("speed", strict)
+        );
+    }
+    assert!(stacked.sum_column("name", true).is_err());
+    for q in [0.0, 0.25, 0.5, 0.9, 1.0] {
+        assert_eq!(base.quantile_column(1, q), stacked.quantile_column(1, q));
+    }
+    assert_eq!(
+        base.value_counts("name").to_csv_string(),
+        stacked.value_counts("name").to_csv_string()
+    );
+    assert_eq!(
+        base.describe().to_csv_string(),
+        stacked.describe().to_csv_string()
+    );
+}
+
+#[test]
+fn group_aggregations_agree() {
+    let specs = [
+        ("speed", Agg::Sum),
+        ("speed", Agg::Mean),
+        ("speed", Agg::Count),
+        ("speed", Agg::Median),
+        ("id", Agg::First),
+        ("id", Agg::Last),
+    ];
+    let agg = |df: DataFrame| {
+        let groups = df
+            .group_by_column_as_keys("name")
+            .sorted_by(|name, _group| name.to_string());
+        groups.agg("name", &specs).to_csv_string()
+    };
+    assert_eq!(agg(base()), agg(stacked()));
+}